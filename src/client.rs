@@ -0,0 +1,1146 @@
+//! Off-chain helpers to build the escrow instructions, derive its addresses and read its state.
+//!
+//! Enabled with the `client` feature. Only depends on the Solana SDK crates, so services can use it without pinocchio.
+
+use solana_instruction::{AccountMeta, Instruction};
+use solana_pubkey::{Pubkey, pubkey};
+use spl_associated_token_account_interface::address::get_associated_token_address_with_program_id;
+
+use crate::errors::EscrowError;
+
+/// The escrow program
+pub const PROGRAM_ID: Pubkey = crate::ID;
+
+/// The system program, also passed in place of a mint for native SOL
+pub const SYSTEM_PROGRAM_ID: Pubkey = pubkey!("11111111111111111111111111111111");
+
+/// Native SOL, passed as the system program in place of the mint
+pub const NATIVE_MINT: Pubkey = SYSTEM_PROGRAM_ID;
+
+/// The SPL Token program
+pub const TOKEN_PROGRAM_ID: Pubkey = pubkey!("TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA");
+
+/// The Token-2022 (Token Extensions) program
+pub const TOKEN_2022_PROGRAM_ID: Pubkey = pubkey!("TokenzQdBNbLqP5VEhdkAS6EPFLC1PHnBqCXEpPxuEb");
+
+/// The associated token account program
+pub const ASSOCIATED_TOKEN_PROGRAM_ID: Pubkey = spl_associated_token_account_interface::program::ID;
+
+/// Seed of an escrow PDA: a single byte (MAKE and MAKE_V2) or a `u64` (MAKE_WIDE)
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum EscrowSeed {
+    Byte(u8),
+    Wide(u64),
+}
+
+impl EscrowSeed {
+    /// The seed bytes of the escrow PDA, the `u64` in little-endian
+    pub fn to_bytes(&self) -> Vec<u8> {
+        match self {
+            Self::Byte(seed) => vec![*seed],
+            Self::Wide(seed) => seed.to_le_bytes().to_vec(),
+        }
+    }
+}
+
+impl From<u8> for EscrowSeed {
+    fn from(seed: u8) -> Self {
+        Self::Byte(seed)
+    }
+}
+
+impl From<u64> for EscrowSeed {
+    fn from(seed: u64) -> Self {
+        Self::Wide(seed)
+    }
+}
+
+/// Escrow PDA of `maker` for the given `seed`, and its bump
+pub fn find_escrow_address(maker: &Pubkey, seed: impl Into<EscrowSeed>) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"escrow", maker.as_ref(), &seed.into().to_bytes()], &PROGRAM_ID)
+}
+
+/// Vault holding `mint_a` for the escrow: the escrow ATA, or the program-owned vault PDA for native SOL
+pub fn find_vault_address(escrow: &Pubkey, mint_a: &Pubkey, token_program_a: &Pubkey) -> Pubkey {
+    if mint_a == &NATIVE_MINT {
+        return Pubkey::find_program_address(&[b"vault", escrow.as_ref()], &PROGRAM_ID).0;
+    }
+    get_associated_token_address_with_program_id(escrow, mint_a, token_program_a)
+}
+
+/// Basket PDA of `maker` for the given `seed`, and its bump
+pub fn find_basket_address(maker: &Pubkey, seed: u64) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"basket", maker.as_ref(), &seed.to_le_bytes()], &PROGRAM_ID)
+}
+
+/// Vesting PDA opened for `taker` by a take of a vesting `escrow`, and its bump
+pub fn find_vesting_address(escrow: &Pubkey, taker: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"vesting", escrow.as_ref(), taker.as_ref()], &PROGRAM_ID)
+}
+
+/// Global config PDA
+pub fn find_config_address() -> Pubkey {
+    Pubkey::find_program_address(&[b"config"], &PROGRAM_ID).0
+}
+
+/// PDA signing the event self-CPI
+pub fn find_event_authority_address() -> Pubkey {
+    Pubkey::find_program_address(&[b"__event_authority"], &PROGRAM_ID).0
+}
+
+/// Token account of `wallet` for a leg: its ATA, or the wallet itself for native SOL
+fn leg_account(wallet: &Pubkey, mint: &Pubkey, token_program: &Pubkey) -> Pubkey {
+    if mint == &NATIVE_MINT {
+        return *wallet;
+    }
+    get_associated_token_address_with_program_id(wallet, mint, token_program)
+}
+
+/// Token program of a leg: the system program for native SOL
+fn leg_token_program(mint: &Pubkey, token_program: &Pubkey) -> Pubkey {
+    if mint == &NATIVE_MINT {
+        return SYSTEM_PROGRAM_ID;
+    }
+    *token_program
+}
+
+/// Parameters of the MAKE instruction
+#[derive(Clone, Debug)]
+pub struct MakeParams {
+    pub maker: Pubkey,
+    pub mint_a: Pubkey,
+    pub mint_b: Pubkey,
+    /// The token program that owns `mint_a` (ignored for native SOL)
+    pub token_program_a: Pubkey,
+    pub amount_a: u64,
+    pub amount_b: u64,
+    /// A `Wide` seed builds MAKE_WIDE instead of MAKE_V2
+    pub seed: EscrowSeed,
+    /// Unix timestamp after which the escrow can no longer be taken, 0 for no expiry
+    pub expires_at: i64,
+    /// Designated taker, `None` for a public offer
+    pub taker: Option<Pubkey>,
+    /// Other `(mint_b, amount_b)` the taker may pay with instead, at most 3
+    pub alternatives: Vec<(Pubkey, u64)>,
+}
+
+/// Builds the MAKE_V2 instruction (MAKE_WIDE for a `u64` seed), deriving the escrow, vault and maker ATA (the program derives the escrow bump)
+pub fn make_instruction(params: &MakeParams) -> Instruction {
+    let (escrow, _) = find_escrow_address(&params.maker, params.seed);
+    let token_program_a = leg_token_program(&params.mint_a, &params.token_program_a);
+
+    let discriminator = match params.seed {
+        EscrowSeed::Byte(_) => 8u8,
+        EscrowSeed::Wide(_) => 9u8,
+    };
+    let mut data = vec![discriminator];
+    data.extend_from_slice(&params.amount_a.to_le_bytes());
+    data.extend_from_slice(&params.amount_b.to_le_bytes());
+    data.extend_from_slice(&params.seed.to_bytes());
+    data.extend_from_slice(&params.expires_at.to_le_bytes());
+    if params.taker.is_some() || !params.alternatives.is_empty() {
+        data.extend_from_slice(params.taker.unwrap_or_default().as_ref());
+    }
+    for (_, amount_b) in &params.alternatives {
+        data.extend_from_slice(&amount_b.to_le_bytes());
+    }
+
+    let mut accounts = vec![
+        AccountMeta::new(params.maker, true),
+        AccountMeta::new_readonly(params.mint_a, false),
+        AccountMeta::new_readonly(params.mint_b, false),
+        AccountMeta::new(leg_account(&params.maker, &params.mint_a, &token_program_a), false),
+        AccountMeta::new(find_vault_address(&escrow, &params.mint_a, &token_program_a), false),
+        AccountMeta::new(escrow, false),
+        AccountMeta::new_readonly(SYSTEM_PROGRAM_ID, false),
+        AccountMeta::new_readonly(token_program_a, false),
+        AccountMeta::new_readonly(ASSOCIATED_TOKEN_PROGRAM_ID, false),
+        AccountMeta::new_readonly(find_config_address(), false),
+        AccountMeta::new_readonly(find_event_authority_address(), false),
+        AccountMeta::new_readonly(PROGRAM_ID, false),
+    ];
+    accounts.extend(params.alternatives.iter().map(|(mint_b, _)| AccountMeta::new_readonly(*mint_b, false)));
+
+    Instruction { program_id: PROGRAM_ID, accounts, data }
+}
+
+/// Price feed terms of an escrow whose `mint_b` is priced from a feed
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct OraclePricing {
+    /// The feed pricing one whole `mint_a` in whole `mint_b` (decoded from a Pyth-priced escrow, the feed id)
+    pub price_feed: Pubkey,
+    /// Premium over the feed price in basis points, a discount when negative
+    pub premium_bps: i16,
+    /// The oldest the feed price may be when taken, in seconds
+    pub max_price_age: u32,
+    /// The widest the feed confidence interval may be when taken, in basis points of the price
+    pub max_confidence_bps: u16,
+}
+
+/// Builds the MAKE_ORACLE instruction, `params.amount_b` being the least accepted for the whole of `amount_a`.
+/// MAKE_ORACLE only takes `u64` seeds, so `params.seed` must be `EscrowSeed::Wide`.
+pub fn make_oracle_instruction(params: &MakeParams, oracle: &OraclePricing) -> Instruction {
+    assert!(matches!(params.seed, EscrowSeed::Wide(_)), "MAKE_ORACLE takes a u64 seed");
+    let mut instruction = make_instruction(params);
+
+    let mut data = vec![13u8];
+    data.extend_from_slice(&oracle.premium_bps.to_le_bytes());
+    data.extend_from_slice(&oracle.max_price_age.to_le_bytes());
+    data.extend_from_slice(&oracle.max_confidence_bps.to_le_bytes());
+    data.extend_from_slice(&instruction.data[1..]);
+    instruction.data = data;
+    instruction.accounts.insert(12, AccountMeta::new_readonly(oracle.price_feed, false));
+
+    instruction
+}
+
+/// Terms of an escrow whose `mint_b` is sold by Dutch auction
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct DutchAuction {
+    /// Amount of `mint_b` asked for the whole `amount_a` once the auction ends
+    pub end_amount_b: u64,
+    /// Unix timestamp at which the price starts decaying from `amount_b`
+    pub auction_start: i64,
+    /// Unix timestamp at which the price reaches `end_amount_b`
+    pub auction_end: i64,
+}
+
+/// Builds the MAKE_DUTCH instruction, `params.amount_b` being the starting price for the whole of `amount_a`.
+/// MAKE_DUTCH only takes `u64` seeds, so `params.seed` must be `EscrowSeed::Wide`.
+pub fn make_dutch_instruction(params: &MakeParams, auction: &DutchAuction) -> Instruction {
+    assert!(matches!(params.seed, EscrowSeed::Wide(_)), "MAKE_DUTCH takes a u64 seed");
+    let mut instruction = make_instruction(params);
+
+    let mut data = vec![14u8];
+    data.extend_from_slice(&auction.end_amount_b.to_le_bytes());
+    data.extend_from_slice(&auction.auction_start.to_le_bytes());
+    data.extend_from_slice(&auction.auction_end.to_le_bytes());
+    data.extend_from_slice(&instruction.data[1..]);
+    instruction.data = data;
+
+    instruction
+}
+
+/// Terms of an escrow whose `mint_a` is sold by English auction
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct EnglishAuction {
+    /// Least each bid must raise the highest bid by
+    pub min_increment: u64,
+    /// Unix timestamp after which no more bids are taken and the auction can be settled
+    pub bidding_end: i64,
+}
+
+/// Builds the MAKE_AUCTION instruction, `params.amount_b` being the reserve price for the whole of `amount_a`.
+/// MAKE_AUCTION only takes `u64` seeds, so `params.seed` must be `EscrowSeed::Wide`.
+pub fn make_auction_instruction(params: &MakeParams, auction: &EnglishAuction) -> Instruction {
+    assert!(matches!(params.seed, EscrowSeed::Wide(_)), "MAKE_AUCTION takes a u64 seed");
+    let mut instruction = make_instruction(params);
+
+    let mut data = vec![15u8];
+    data.extend_from_slice(&auction.min_increment.to_le_bytes());
+    data.extend_from_slice(&auction.bidding_end.to_le_bytes());
+    data.extend_from_slice(&instruction.data[1..]);
+    instruction.data = data;
+
+    instruction
+}
+
+/// Vesting schedule of an escrow whose `mint_a` vests after the take, relative to the take
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct VestingSchedule {
+    /// Seconds after the take before anything can be claimed
+    pub vesting_cliff: i64,
+    /// Seconds after the take until everything has vested
+    pub vesting_duration: i64,
+}
+
+/// Builds the MAKE_VESTING instruction, `amount_a` vesting along `vesting` for the taker, who takes it whole.
+/// MAKE_VESTING only takes `u64` seeds, so `params.seed` must be `EscrowSeed::Wide`.
+pub fn make_vesting_instruction(params: &MakeParams, vesting: &VestingSchedule) -> Instruction {
+    assert!(matches!(params.seed, EscrowSeed::Wide(_)), "MAKE_VESTING takes a u64 seed");
+    let mut instruction = make_instruction(params);
+
+    let mut data = vec![18u8];
+    data.extend_from_slice(&vesting.vesting_cliff.to_le_bytes());
+    data.extend_from_slice(&vesting.vesting_duration.to_le_bytes());
+    data.extend_from_slice(&instruction.data[1..]);
+    instruction.data = data;
+
+    instruction
+}
+
+/// Parameters of the TAKE instruction
+#[derive(Clone, Debug)]
+pub struct TakeParams {
+    pub taker: Pubkey,
+    pub maker: Pubkey,
+    pub mint_a: Pubkey,
+    pub mint_b: Pubkey,
+    /// The token program that owns `mint_a` (ignored for native SOL)
+    pub token_program_a: Pubkey,
+    /// The token program that owns `mint_b` (ignored for native SOL)
+    pub token_program_b: Pubkey,
+    /// The treasury stored in the global config
+    pub treasury: Pubkey,
+    pub seed: EscrowSeed,
+    /// Amount of `mint_a` to receive
+    pub amount_a: u64,
+    /// Most `mint_b` the taker agrees to pay for `amount_a`, `None` for no slippage guard
+    pub max_amount_b: Option<u64>,
+}
+
+/// Builds the TAKE instruction, deriving the escrow, vault and the taker, maker and treasury ATAs
+pub fn take_instruction(params: &TakeParams) -> Instruction {
+    let (escrow, _) = find_escrow_address(&params.maker, params.seed);
+    let token_program_a = leg_token_program(&params.mint_a, &params.token_program_a);
+    let token_program_b = leg_token_program(&params.mint_b, &params.token_program_b);
+
+    let mut data = vec![1u8];
+    data.extend_from_slice(&params.amount_a.to_le_bytes());
+    if let Some(max_amount_b) = params.max_amount_b {
+        data.extend_from_slice(&max_amount_b.to_le_bytes());
+    }
+
+    Instruction {
+        program_id: PROGRAM_ID,
+        accounts: vec![
+            AccountMeta::new(params.taker, true),
+            AccountMeta::new(params.maker, false),
+            AccountMeta::new_readonly(params.mint_a, false),
+            AccountMeta::new_readonly(params.mint_b, false),
+            AccountMeta::new(leg_account(&params.taker, &params.mint_a, &token_program_a), false),
+            AccountMeta::new(leg_account(&params.taker, &params.mint_b, &token_program_b), false),
+            AccountMeta::new(find_vault_address(&escrow, &params.mint_a, &token_program_a), false),
+            AccountMeta::new(leg_account(&params.maker, &params.mint_b, &token_program_b), false),
+            AccountMeta::new(escrow, false),
+            AccountMeta::new_readonly(SYSTEM_PROGRAM_ID, false),
+            AccountMeta::new_readonly(token_program_a, false),
+            AccountMeta::new_readonly(token_program_b, false),
+            AccountMeta::new_readonly(find_config_address(), false),
+            AccountMeta::new(leg_account(&params.treasury, &params.mint_b, &token_program_b), false),
+            AccountMeta::new_readonly(find_event_authority_address(), false),
+            AccountMeta::new_readonly(PROGRAM_ID, false),
+        ],
+        data,
+    }
+}
+
+/// Builds the TAKE instruction of an oracle-priced escrow, passing its price feed
+pub fn take_oracle_instruction(params: &TakeParams, price_feed: &Pubkey) -> Instruction {
+    let mut instruction = take_instruction(params);
+    instruction.accounts.push(AccountMeta::new_readonly(*price_feed, false));
+    instruction
+}
+
+/// Builds the TAKE instruction of a vesting escrow, passing the vesting account opened for the taker and its vault
+pub fn take_vesting_instruction(params: &TakeParams) -> Instruction {
+    let (escrow, _) = find_escrow_address(&params.maker, params.seed);
+    let (vesting, _) = find_vesting_address(&escrow, &params.taker);
+    let mut instruction = take_instruction(params);
+    instruction.accounts.extend([
+        AccountMeta::new(vesting, false),
+        AccountMeta::new(leg_account(&vesting, &params.mint_a, &params.token_program_a), false),
+        AccountMeta::new_readonly(ASSOCIATED_TOKEN_PROGRAM_ID, false),
+    ]);
+    instruction
+}
+
+/// Builds the MAKE_HASHLOCK instruction, `params.taker` being the counterparty and `params.expires_at` the timeout, both required.
+/// MAKE_HASHLOCK only takes `u64` seeds, so `params.seed` must be `EscrowSeed::Wide`.
+pub fn make_hashlock_instruction(params: &MakeParams, hashlock: &[u8; 32]) -> Instruction {
+    assert!(matches!(params.seed, EscrowSeed::Wide(_)), "MAKE_HASHLOCK takes a u64 seed");
+    let mut instruction = make_instruction(params);
+
+    let mut data = vec![29u8];
+    data.extend_from_slice(hashlock);
+    data.extend_from_slice(&instruction.data[1..]);
+    instruction.data = data;
+
+    instruction
+}
+
+/// The hashlock of `preimage`: its SHA-256 hash
+pub fn hashlock_of(preimage: &[u8; 32]) -> [u8; 32] {
+    solana_sha256_hasher::hash(preimage).to_bytes()
+}
+
+/// Builds the TAKE instruction of a hashlocked escrow, revealing the `preimage` of its hashlock
+pub fn take_hashlock_instruction(params: &TakeParams, preimage: &[u8; 32]) -> Instruction {
+    let mut instruction = take_instruction(params);
+    instruction.data.extend_from_slice(preimage);
+    instruction
+}
+
+/// Parameters of the CLAIM instruction
+#[derive(Clone, Debug)]
+pub struct ClaimParams {
+    pub beneficiary: Pubkey,
+    pub maker: Pubkey,
+    pub mint_a: Pubkey,
+    /// The token program that owns `mint_a` (ignored for native SOL)
+    pub token_program_a: Pubkey,
+    pub seed: EscrowSeed,
+}
+
+/// Builds the CLAIM instruction, deriving the escrow, the vesting account and its vault and the beneficiary ATA
+pub fn claim_instruction(params: &ClaimParams) -> Instruction {
+    let (escrow, _) = find_escrow_address(&params.maker, params.seed);
+    let (vesting, _) = find_vesting_address(&escrow, &params.beneficiary);
+    let token_program_a = leg_token_program(&params.mint_a, &params.token_program_a);
+
+    Instruction {
+        program_id: PROGRAM_ID,
+        accounts: vec![
+            AccountMeta::new(params.beneficiary, true),
+            AccountMeta::new_readonly(params.mint_a, false),
+            AccountMeta::new(leg_account(&params.beneficiary, &params.mint_a, &token_program_a), false),
+            AccountMeta::new(vesting, false),
+            AccountMeta::new(leg_account(&vesting, &params.mint_a, &token_program_a), false),
+            AccountMeta::new_readonly(token_program_a, false),
+            AccountMeta::new_readonly(find_event_authority_address(), false),
+            AccountMeta::new_readonly(PROGRAM_ID, false),
+        ],
+        data: vec![19u8],
+    }
+}
+
+/// Builds the MAKE_ARBITER instruction, the maker being the buyer and `params.taker` the seller, required.
+/// MAKE_ARBITER only takes `u64` seeds, so `params.seed` must be `EscrowSeed::Wide`.
+pub fn make_arbiter_instruction(params: &MakeParams, arbiter: &Pubkey) -> Instruction {
+    assert!(matches!(params.seed, EscrowSeed::Wide(_)), "MAKE_ARBITER takes a u64 seed");
+    let mut instruction = make_instruction(params);
+
+    let mut data = vec![20u8];
+    data.extend_from_slice(arbiter.as_ref());
+    data.extend_from_slice(&instruction.data[1..]);
+    instruction.data = data;
+
+    instruction
+}
+
+/// Parameters of the RELEASE and DISPUTE_RESOLVE instructions
+#[derive(Clone, Debug)]
+pub struct ReleaseParams {
+    /// The maker or the seller for RELEASE, the arbiter for DISPUTE_RESOLVE
+    pub authority: Pubkey,
+    pub maker: Pubkey,
+    /// The party receiving `mint_a`: the seller, or the maker
+    pub recipient: Pubkey,
+    pub mint_a: Pubkey,
+    /// The token program that owns `mint_a` (ignored for native SOL)
+    pub token_program_a: Pubkey,
+    pub seed: EscrowSeed,
+}
+
+/// Builds the RELEASE instruction, deriving the escrow, vault and the recipient ATA
+pub fn release_instruction(params: &ReleaseParams) -> Instruction {
+    let (escrow, _) = find_escrow_address(&params.maker, params.seed);
+    let token_program_a = leg_token_program(&params.mint_a, &params.token_program_a);
+
+    Instruction {
+        program_id: PROGRAM_ID,
+        accounts: vec![
+            AccountMeta::new_readonly(params.authority, true),
+            AccountMeta::new(params.maker, false),
+            AccountMeta::new(params.recipient, false),
+            AccountMeta::new_readonly(params.mint_a, false),
+            AccountMeta::new(leg_account(&params.recipient, &params.mint_a, &token_program_a), false),
+            AccountMeta::new(find_vault_address(&escrow, &params.mint_a, &token_program_a), false),
+            AccountMeta::new(escrow, false),
+            AccountMeta::new_readonly(SYSTEM_PROGRAM_ID, false),
+            AccountMeta::new_readonly(token_program_a, false),
+            AccountMeta::new_readonly(find_event_authority_address(), false),
+            AccountMeta::new_readonly(PROGRAM_ID, false),
+        ],
+        data: vec![21u8],
+    }
+}
+
+/// Builds the DISPUTE_RESOLVE instruction, the arbiter releasing the vault to the seller when `to_seller`, back to the maker otherwise
+pub fn dispute_resolve_instruction(params: &ReleaseParams, to_seller: bool) -> Instruction {
+    let mut instruction = release_instruction(params);
+    instruction.data = vec![22u8, to_seller as u8];
+    instruction
+}
+
+/// Approvers of an escrow released to its designated taker by approvals
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ApprovalTerms {
+    /// The distinct approvers, at most 5
+    pub approvers: Vec<Pubkey>,
+    /// Number of approvals releasing the vault
+    pub threshold: u8,
+}
+/// Builds the MAKE_APPROVAL instruction, `params.taker` being the beneficiary and `params.expires_at` the expiry, both required.
+/// Builds the MAKE_APPROVAL instruction, `params.taker` being the beneficiary, required.
+/// MAKE_APPROVAL only takes `u64` seeds, so `params.seed` must be `EscrowSeed::Wide`.
+pub fn make_approval_instruction(params: &MakeParams, approval: &ApprovalTerms) -> Instruction {
+    assert!(matches!(params.seed, EscrowSeed::Wide(_)), "MAKE_APPROVAL takes a u64 seed");
+    let mut instruction = make_instruction(params);
+
+    let mut data = vec![23u8, approval.threshold, approval.approvers.len() as u8];
+    for approver in &approval.approvers {
+        data.extend_from_slice(approver.as_ref());
+    }
+    data.extend_from_slice(&instruction.data[1..]);
+    instruction.data = data;
+
+    instruction
+}
+
+/// Parameters of the APPROVE instruction
+#[derive(Clone, Debug)]
+pub struct ApproveParams {
+    pub approver: Pubkey,
+    pub maker: Pubkey,
+    /// The designated taker receiving `mint_a` once the threshold is reached
+    pub beneficiary: Pubkey,
+    pub mint_a: Pubkey,
+    /// The token program that owns `mint_a` (ignored for native SOL)
+    pub token_program_a: Pubkey,
+    pub seed: EscrowSeed,
+}
+
+/// Builds the APPROVE instruction, deriving the escrow, vault and the beneficiary ATA
+pub fn approve_instruction(params: &ApproveParams) -> Instruction {
+    let (escrow, _) = find_escrow_address(&params.maker, params.seed);
+    let token_program_a = leg_token_program(&params.mint_a, &params.token_program_a);
+
+    Instruction {
+        program_id: PROGRAM_ID,
+        accounts: vec![
+            AccountMeta::new_readonly(params.approver, true),
+            AccountMeta::new(params.maker, false),
+            AccountMeta::new(params.beneficiary, false),
+            AccountMeta::new_readonly(params.mint_a, false),
+            AccountMeta::new(leg_account(&params.beneficiary, &params.mint_a, &token_program_a), false),
+            AccountMeta::new(find_vault_address(&escrow, &params.mint_a, &token_program_a), false),
+            AccountMeta::new(escrow, false),
+            AccountMeta::new_readonly(SYSTEM_PROGRAM_ID, false),
+            AccountMeta::new_readonly(token_program_a, false),
+            AccountMeta::new_readonly(find_event_authority_address(), false),
+            AccountMeta::new_readonly(PROGRAM_ID, false),
+        ],
+        data: vec![24u8],
+    }
+}
+
+/// Builds the REVOKE_APPROVAL instruction, withdrawing the approval of `approver`
+pub fn revoke_approval_instruction(approver: &Pubkey, maker: &Pubkey, seed: impl Into<EscrowSeed>) -> Instruction {
+    let (escrow, _) = find_escrow_address(maker, seed);
+
+    Instruction {
+        program_id: PROGRAM_ID,
+        accounts: vec![
+            AccountMeta::new_readonly(*approver, true),
+            AccountMeta::new_readonly(*maker, false),
+            AccountMeta::new(escrow, false),
+            AccountMeta::new_readonly(find_event_authority_address(), false),
+            AccountMeta::new_readonly(PROGRAM_ID, false),
+        ],
+        data: vec![25u8],
+    }
+}
+
+/// Milestones of an escrow paid to its designated taker one at a time
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MilestoneTerms {
+    /// The arbiter who may also release milestones, and without whom the maker can only cancel with the payee's consent, `None` to leave them to the maker
+    pub arbiter: Option<Pubkey>,
+    /// The amount of `mint_a` each milestone pays, in release order, at most 8 adding up to `amount_a`
+    pub amounts: Vec<u64>,
+}
+
+/// Builds the MAKE_MILESTONE instruction, the maker being the payer and `params.taker` the payee, required.
+/// MAKE_MILESTONE only takes `u64` seeds, so `params.seed` must be `EscrowSeed::Wide`.
+pub fn make_milestone_instruction(params: &MakeParams, milestones: &MilestoneTerms) -> Instruction {
+    assert!(matches!(params.seed, EscrowSeed::Wide(_)), "MAKE_MILESTONE takes a u64 seed");
+    let mut instruction = make_instruction(params);
+
+    let mut data = vec![26u8];
+    data.extend_from_slice(milestones.arbiter.unwrap_or_default().as_ref());
+    data.push(milestones.amounts.len() as u8);
+    for amount in &milestones.amounts {
+        data.extend_from_slice(&amount.to_le_bytes());
+    }
+    data.extend_from_slice(&instruction.data[1..]);
+    instruction.data = data;
+
+    instruction
+}
+
+/// Parameters of the RELEASE_MILESTONE instruction
+#[derive(Clone, Debug)]
+pub struct ReleaseMilestoneParams {
+    /// The maker or the arbiter
+    pub authority: Pubkey,
+    pub maker: Pubkey,
+    /// The designated taker receiving the milestone
+    pub payee: Pubkey,
+    pub mint_a: Pubkey,
+    /// The token program that owns `mint_a` (ignored for native SOL)
+    pub token_program_a: Pubkey,
+    pub seed: EscrowSeed,
+}
+
+/// Builds the RELEASE_MILESTONE instruction paying `milestone`, which must be the next one, deriving the escrow, vault and the payee ATA
+pub fn release_milestone_instruction(params: &ReleaseMilestoneParams, milestone: u8) -> Instruction {
+    let (escrow, _) = find_escrow_address(&params.maker, params.seed);
+    let token_program_a = leg_token_program(&params.mint_a, &params.token_program_a);
+
+    Instruction {
+        program_id: PROGRAM_ID,
+        accounts: vec![
+            AccountMeta::new_readonly(params.authority, true),
+            AccountMeta::new(params.maker, false),
+            AccountMeta::new(params.payee, false),
+            AccountMeta::new_readonly(params.mint_a, false),
+            AccountMeta::new(leg_account(&params.payee, &params.mint_a, &token_program_a), false),
+            AccountMeta::new(find_vault_address(&escrow, &params.mint_a, &token_program_a), false),
+            AccountMeta::new(escrow, false),
+            AccountMeta::new_readonly(SYSTEM_PROGRAM_ID, false),
+            AccountMeta::new_readonly(token_program_a, false),
+            AccountMeta::new_readonly(find_event_authority_address(), false),
+            AccountMeta::new_readonly(PROGRAM_ID, false),
+        ],
+        data: vec![27u8, milestone],
+    }
+}
+
+/// Parameters of the CANCEL instruction
+#[derive(Clone, Debug)]
+pub struct CancelParams {
+    /// The maker, or the arbiter when there is one
+    pub authority: Pubkey,
+    pub maker: Pubkey,
+    pub mint_a: Pubkey,
+    /// The token program that owns `mint_a` (ignored for native SOL)
+    pub token_program_a: Pubkey,
+    pub seed: EscrowSeed,
+    /// The payee co-signing the cancel, only when the maker cancels an escrow with an arbiter
+    pub payee: Option<Pubkey>,
+}
+
+/// Builds the CANCEL instruction, deriving the escrow, vault and maker ATA
+pub fn cancel_instruction(params: &CancelParams) -> Instruction {
+    let (escrow, _) = find_escrow_address(&params.maker, params.seed);
+    let token_program_a = leg_token_program(&params.mint_a, &params.token_program_a);
+
+    let mut accounts = vec![
+        AccountMeta::new_readonly(params.authority, true),
+        AccountMeta::new(params.maker, false),
+        AccountMeta::new_readonly(params.mint_a, false),
+        AccountMeta::new(leg_account(&params.maker, &params.mint_a, &token_program_a), false),
+        AccountMeta::new(find_vault_address(&escrow, &params.mint_a, &token_program_a), false),
+        AccountMeta::new(escrow, false),
+        AccountMeta::new_readonly(SYSTEM_PROGRAM_ID, false),
+        AccountMeta::new_readonly(token_program_a, false),
+        AccountMeta::new_readonly(find_event_authority_address(), false),
+        AccountMeta::new_readonly(PROGRAM_ID, false),
+    ];
+    if let Some(payee) = params.payee {
+        accounts.push(AccountMeta::new_readonly(payee, true));
+    }
+
+    Instruction {
+        program_id: PROGRAM_ID,
+        accounts,
+        data: vec![28u8],
+    }
+}
+
+/// Parameters of the REFUND instruction
+#[derive(Clone, Debug)]
+pub struct RefundParams {
+    pub maker: Pubkey,
+    pub mint_a: Pubkey,
+    pub mint_b: Pubkey,
+    /// The token program that owns `mint_a` (ignored for native SOL)
+    pub token_program_a: Pubkey,
+    pub seed: EscrowSeed,
+}
+
+/// Builds the REFUND instruction, deriving the escrow, vault and maker ATA
+pub fn refund_instruction(params: &RefundParams) -> Instruction {
+    let (escrow, _) = find_escrow_address(&params.maker, params.seed);
+    let token_program_a = leg_token_program(&params.mint_a, &params.token_program_a);
+
+    Instruction {
+        program_id: PROGRAM_ID,
+        accounts: vec![
+            AccountMeta::new(params.maker, true),
+            AccountMeta::new_readonly(params.mint_a, false),
+            AccountMeta::new_readonly(params.mint_b, false),
+            AccountMeta::new(leg_account(&params.maker, &params.mint_a, &token_program_a), false),
+            AccountMeta::new(find_vault_address(&escrow, &params.mint_a, &token_program_a), false),
+            AccountMeta::new(escrow, false),
+            AccountMeta::new_readonly(SYSTEM_PROGRAM_ID, false),
+            AccountMeta::new_readonly(token_program_a, false),
+            AccountMeta::new_readonly(find_event_authority_address(), false),
+            AccountMeta::new_readonly(PROGRAM_ID, false),
+        ],
+        data: vec![2u8],
+    }
+}
+
+/// Builds the MIGRATE instruction, upgrading an escrow created with an older account layout.
+/// Anyone can sign in place of the maker for an escrow from version 3 on
+pub fn migrate_instruction(maker: &Pubkey, mint_a: &Pubkey, token_program_a: &Pubkey, seed: impl Into<EscrowSeed>) -> Instruction {
+    let (escrow, _) = find_escrow_address(maker, seed);
+    let token_program_a = leg_token_program(mint_a, token_program_a);
+
+    Instruction {
+        program_id: PROGRAM_ID,
+        accounts: vec![
+            AccountMeta::new(*maker, true),
+            AccountMeta::new(escrow, false),
+            AccountMeta::new_readonly(find_vault_address(&escrow, mint_a, &token_program_a), false),
+            AccountMeta::new_readonly(SYSTEM_PROGRAM_ID, false),
+        ],
+        data: vec![7u8],
+    }
+}
+
+/// Parameters of the BID instruction
+#[derive(Clone, Debug)]
+pub struct BidParams {
+    pub bidder: Pubkey,
+    pub maker: Pubkey,
+    pub mint_b: Pubkey,
+    /// The token program that owns `mint_b`
+    pub token_program_b: Pubkey,
+    pub seed: EscrowSeed,
+    /// Amount of `mint_b` bid for the whole `amount_a`
+    pub amount_b: u64,
+    /// The current highest bidder, refunded by the bid, `None` for the first bid
+    pub previous_bidder: Option<Pubkey>,
+}
+
+/// Builds the BID instruction, deriving the escrow, the bid vault and the bidder ATAs
+pub fn bid_instruction(params: &BidParams) -> Instruction {
+    let (escrow, _) = find_escrow_address(&params.maker, params.seed);
+
+    let mut accounts = vec![
+        AccountMeta::new(params.bidder, true),
+        AccountMeta::new_readonly(params.maker, false),
+        AccountMeta::new_readonly(params.mint_b, false),
+        AccountMeta::new(get_associated_token_address_with_program_id(&params.bidder, &params.mint_b, &params.token_program_b), false),
+        AccountMeta::new(get_associated_token_address_with_program_id(&escrow, &params.mint_b, &params.token_program_b), false),
+        AccountMeta::new(escrow, false),
+        AccountMeta::new_readonly(SYSTEM_PROGRAM_ID, false),
+        AccountMeta::new_readonly(params.token_program_b, false),
+        AccountMeta::new_readonly(ASSOCIATED_TOKEN_PROGRAM_ID, false),
+        AccountMeta::new_readonly(find_event_authority_address(), false),
+        AccountMeta::new_readonly(PROGRAM_ID, false),
+    ];
+    if let Some(previous_bidder) = params.previous_bidder {
+        accounts.push(AccountMeta::new(get_associated_token_address_with_program_id(&previous_bidder, &params.mint_b, &params.token_program_b), false));
+    }
+
+    let mut data = vec![16u8];
+    data.extend_from_slice(&params.amount_b.to_le_bytes());
+
+    Instruction { program_id: PROGRAM_ID, accounts, data }
+}
+
+/// Parameters of the SETTLE_AUCTION instruction
+#[derive(Clone, Debug)]
+pub struct SettleAuctionParams {
+    pub maker: Pubkey,
+    /// The highest bidder
+    pub winner: Pubkey,
+    pub mint_a: Pubkey,
+    pub mint_b: Pubkey,
+    /// The token program that owns `mint_a` (ignored for native SOL)
+    pub token_program_a: Pubkey,
+    /// The token program that owns `mint_b`
+    pub token_program_b: Pubkey,
+    /// The treasury stored in the global config
+    pub treasury: Pubkey,
+    /// The first bidder, who paid the bid vault rent (`Escrow::bid_vault_payer`, the winner when unset)
+    pub bid_vault_payer: Pubkey,
+    pub seed: EscrowSeed,
+}
+
+/// Builds the SETTLE_AUCTION instruction, deriving the escrow, both vaults and the winner, maker and treasury ATAs
+pub fn settle_auction_instruction(params: &SettleAuctionParams) -> Instruction {
+    let (escrow, _) = find_escrow_address(&params.maker, params.seed);
+    let token_program_a = leg_token_program(&params.mint_a, &params.token_program_a);
+
+    Instruction {
+        program_id: PROGRAM_ID,
+        accounts: vec![
+            AccountMeta::new(params.maker, false),
+            AccountMeta::new(params.winner, false),
+            AccountMeta::new_readonly(params.mint_a, false),
+            AccountMeta::new_readonly(params.mint_b, false),
+            AccountMeta::new(leg_account(&params.winner, &params.mint_a, &token_program_a), false),
+            AccountMeta::new(find_vault_address(&escrow, &params.mint_a, &token_program_a), false),
+            AccountMeta::new(get_associated_token_address_with_program_id(&escrow, &params.mint_b, &params.token_program_b), false),
+            AccountMeta::new(get_associated_token_address_with_program_id(&params.maker, &params.mint_b, &params.token_program_b), false),
+            AccountMeta::new(escrow, false),
+            AccountMeta::new_readonly(SYSTEM_PROGRAM_ID, false),
+            AccountMeta::new_readonly(token_program_a, false),
+            AccountMeta::new_readonly(params.token_program_b, false),
+            AccountMeta::new_readonly(find_config_address(), false),
+            AccountMeta::new(get_associated_token_address_with_program_id(&params.treasury, &params.mint_b, &params.token_program_b), false),
+            AccountMeta::new_readonly(find_event_authority_address(), false),
+            AccountMeta::new_readonly(PROGRAM_ID, false),
+            AccountMeta::new(params.bid_vault_payer, false),
+        ],
+        data: vec![17u8],
+    }
+}
+
+/// One leg of a basket to make
+#[derive(Clone, Debug)]
+pub struct BasketLegParams {
+    pub mint_a: Pubkey,
+    /// The token program that owns `mint_a`
+    pub token_program_a: Pubkey,
+    pub amount_a: u64,
+}
+
+/// Parameters of the MAKE_BASKET instruction
+#[derive(Clone, Debug)]
+pub struct MakeBasketParams {
+    pub maker: Pubkey,
+    pub mint_b: Pubkey,
+    /// Amount of `mint_b` asked for the whole basket
+    pub amount_b: u64,
+    pub seed: u64,
+    /// Unix timestamp after which the basket can no longer be taken, 0 for no expiry
+    pub expires_at: i64,
+    pub legs: Vec<BasketLegParams>,
+}
+
+/// Builds the MAKE_BASKET instruction, deriving the basket, the vault and maker ATA of every leg
+pub fn make_basket_instruction(params: &MakeBasketParams) -> Instruction {
+    let (basket, _) = find_basket_address(&params.maker, params.seed);
+
+    let mut data = vec![10u8];
+    data.extend_from_slice(&params.amount_b.to_le_bytes());
+    data.extend_from_slice(&params.seed.to_le_bytes());
+    data.extend_from_slice(&params.expires_at.to_le_bytes());
+    let mut accounts = vec![
+        AccountMeta::new(params.maker, true),
+        AccountMeta::new_readonly(params.mint_b, false),
+        AccountMeta::new(basket, false),
+        AccountMeta::new_readonly(SYSTEM_PROGRAM_ID, false),
+        AccountMeta::new_readonly(ASSOCIATED_TOKEN_PROGRAM_ID, false),
+        AccountMeta::new_readonly(find_config_address(), false),
+        AccountMeta::new_readonly(find_event_authority_address(), false),
+        AccountMeta::new_readonly(PROGRAM_ID, false),
+    ];
+    for leg in &params.legs {
+        data.extend_from_slice(&leg.amount_a.to_le_bytes());
+        accounts.extend([
+            AccountMeta::new_readonly(leg.mint_a, false),
+            AccountMeta::new(get_associated_token_address_with_program_id(&params.maker, &leg.mint_a, &leg.token_program_a), false),
+            AccountMeta::new(get_associated_token_address_with_program_id(&basket, &leg.mint_a, &leg.token_program_a), false),
+            AccountMeta::new_readonly(leg.token_program_a, false),
+        ]);
+    }
+
+    Instruction { program_id: PROGRAM_ID, accounts, data }
+}
+
+/// Parameters of the TAKE_BASKET instruction
+#[derive(Clone, Debug)]
+pub struct TakeBasketParams {
+    pub taker: Pubkey,
+    pub maker: Pubkey,
+    pub mint_b: Pubkey,
+    /// The token program that owns `mint_b` (ignored for native SOL)
+    pub token_program_b: Pubkey,
+    /// The treasury stored in the global config
+    pub treasury: Pubkey,
+    pub seed: u64,
+    /// `(mint_a, token_program_a)` of every leg, in the basket order
+    pub legs: Vec<(Pubkey, Pubkey)>,
+}
+
+/// Builds the TAKE_BASKET instruction, deriving the basket, the taker, maker and treasury ATAs and the vault of every leg
+pub fn take_basket_instruction(params: &TakeBasketParams) -> Instruction {
+    let (basket, _) = find_basket_address(&params.maker, params.seed);
+    let token_program_b = leg_token_program(&params.mint_b, &params.token_program_b);
+
+    let mut accounts = vec![
+        AccountMeta::new(params.taker, true),
+        AccountMeta::new(params.maker, false),
+        AccountMeta::new_readonly(params.mint_b, false),
+        AccountMeta::new(leg_account(&params.taker, &params.mint_b, &token_program_b), false),
+        AccountMeta::new(leg_account(&params.maker, &params.mint_b, &token_program_b), false),
+        AccountMeta::new(basket, false),
+        AccountMeta::new_readonly(SYSTEM_PROGRAM_ID, false),
+        AccountMeta::new_readonly(token_program_b, false),
+        AccountMeta::new_readonly(find_config_address(), false),
+        AccountMeta::new(leg_account(&params.treasury, &params.mint_b, &token_program_b), false),
+        AccountMeta::new_readonly(find_event_authority_address(), false),
+        AccountMeta::new_readonly(PROGRAM_ID, false),
+    ];
+    for (mint_a, token_program_a) in &params.legs {
+        accounts.extend([
+            AccountMeta::new_readonly(*mint_a, false),
+            AccountMeta::new(get_associated_token_address_with_program_id(&params.taker, mint_a, token_program_a), false),
+            AccountMeta::new(get_associated_token_address_with_program_id(&basket, mint_a, token_program_a), false),
+            AccountMeta::new_readonly(*token_program_a, false),
+        ]);
+    }
+
+    Instruction { program_id: PROGRAM_ID, accounts, data: vec![11u8] }
+}
+
+/// Parameters of the REFUND_BASKET instruction
+#[derive(Clone, Debug)]
+pub struct RefundBasketParams {
+    pub maker: Pubkey,
+    pub mint_b: Pubkey,
+    pub seed: u64,
+    /// `(mint_a, token_program_a)` of every leg, in the basket order
+    pub legs: Vec<(Pubkey, Pubkey)>,
+}
+
+/// Builds the REFUND_BASKET instruction, deriving the basket and the vault and maker ATA of every leg
+pub fn refund_basket_instruction(params: &RefundBasketParams) -> Instruction {
+    let (basket, _) = find_basket_address(&params.maker, params.seed);
+
+    let mut accounts = vec![
+        AccountMeta::new(params.maker, true),
+        AccountMeta::new_readonly(params.mint_b, false),
+        AccountMeta::new(basket, false),
+        AccountMeta::new_readonly(SYSTEM_PROGRAM_ID, false),
+        AccountMeta::new_readonly(find_event_authority_address(), false),
+        AccountMeta::new_readonly(PROGRAM_ID, false),
+    ];
+    for (mint_a, token_program_a) in &params.legs {
+        accounts.extend([
+            AccountMeta::new_readonly(*mint_a, false),
+            AccountMeta::new(get_associated_token_address_with_program_id(&params.maker, mint_a, token_program_a), false),
+            AccountMeta::new(get_associated_token_address_with_program_id(&basket, mint_a, token_program_a), false),
+            AccountMeta::new_readonly(*token_program_a, false),
+        ]);
+    }
+
+    Instruction { program_id: PROGRAM_ID, accounts, data: vec![12u8] }
+}
+
+/// Decoded escrow state account
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Escrow {
+    pub maker: Pubkey,
+    /// The deposited mint, `NATIVE_MINT` for native SOL
+    pub mint_a: Pubkey,
+    pub mint_b: Pubkey,
+    pub amount_b: u64,
+    pub amount_a: u64,
+    pub remaining_a: u64,
+    /// Unix timestamp after which the escrow can no longer be taken, 0 for no expiry
+    pub expires_at: i64,
+    pub fee_bps: u16,
+    /// Designated taker, `None` for a public offer
+    pub taker: Option<Pubkey>,
+    pub seed: EscrowSeed,
+    pub bump: u8,
+    /// Bump of the native SOL vault PDA, 0 when `mint_a` is a token
+    pub vault_bump: u8,
+    /// Other `(mint_b, amount_b)` the taker may pay with instead
+    pub alternatives: Vec<(Pubkey, u64)>,
+    /// Price feed terms when `mint_b` is priced from a feed, `amount_b` then being the least asked
+    pub oracle: Option<OraclePricing>,
+    /// Auction terms when `mint_b` is sold by Dutch auction, `amount_b` then being the starting price
+    pub dutch_auction: Option<DutchAuction>,
+    /// Auction terms when `mint_a` is sold by English auction, `amount_b` then being the reserve price
+    pub english_auction: Option<EnglishAuction>,
+    /// The highest bidder and bid of an English auction, `None` before the first bid
+    pub highest_bid: Option<(Pubkey, u64)>,
+    /// Vesting schedule when `mint_a` vests after the take
+    pub vesting: Option<VestingSchedule>,
+    /// Arbiter of a goods-for-tokens escrow, `taker` then being the seller, or the optional arbiter of a milestone escrow
+    pub arbiter: Option<Pubkey>,
+    /// Approvers of an escrow released by approvals, `taker` then being the beneficiary
+    pub approval: Option<ApprovalTerms>,
+    /// The approvers that have approved so far
+    pub approved_by: Vec<Pubkey>,
+    /// Milestone amounts of a milestone escrow in release order, `taker` then being the payee and `arbiter` the optional arbiter
+    pub milestones: Vec<u64>,
+    /// Number of milestones released so far
+    pub released_milestones: u8,
+    /// SHA-256 hash of the preimage a hashlocked escrow is taken with, `expires_at` then being the timeout
+    pub hashlock: Option<[u8; 32]>,
+    /// The first bidder of an English auction, who paid the bid vault rent returned at settlement
+    pub bid_vault_payer: Option<Pubkey>,
+}
+
+impl Escrow {
+    pub const LEN: usize = 757;
+
+    /// Decodes the escrow account data, which must have the current layout (see `migrate_instruction` otherwise)
+    pub fn from_bytes(data: &[u8]) -> Result<Self, EscrowError> {
+        match crate::state::EscrowLayout::of(data) {
+            Some(crate::state::EscrowLayout::V13) => {}
+            Some(_) => return Err(EscrowError::OutdatedEscrowVersion),
+            None => return Err(EscrowError::InvalidEscrowData),
+        }
+        let taker: [u8; 32] = data[132..164].try_into().unwrap();
+        let alternatives = data[176..]
+            .chunks_exact(40)
+            .take(data[175] as usize)
+            .map(|alternative| (Pubkey::new_from_array(alternative[0..32].try_into().unwrap()), u64::from_le_bytes(alternative[32..40].try_into().unwrap())))
+            .collect();
+        let price_feed: [u8; 32] = data[296..328].try_into().unwrap();
+        let oracle = (price_feed != [0u8; 32]).then(|| OraclePricing {
+            price_feed: Pubkey::new_from_array(price_feed),
+            premium_bps: i16::from_le_bytes(data[328..330].try_into().unwrap()),
+            max_price_age: u32::from_le_bytes(data[330..334].try_into().unwrap()),
+            max_confidence_bps: u16::from_le_bytes(data[334..336].try_into().unwrap()),
+        });
+        let auction_end = i64::from_le_bytes(data[352..360].try_into().unwrap());
+        let dutch_auction = (auction_end != 0).then(|| DutchAuction {
+            end_amount_b: u64::from_le_bytes(data[336..344].try_into().unwrap()),
+            auction_start: i64::from_le_bytes(data[344..352].try_into().unwrap()),
+            auction_end,
+        });
+        let bidding_end = i64::from_le_bytes(data[368..376].try_into().unwrap());
+        let english_auction = (bidding_end != 0).then(|| EnglishAuction {
+            min_increment: u64::from_le_bytes(data[360..368].try_into().unwrap()),
+            bidding_end,
+        });
+        let highest_bidder: [u8; 32] = data[376..408].try_into().unwrap();
+        let highest_bid = (highest_bidder != [0u8; 32]).then(|| (Pubkey::new_from_array(highest_bidder), u64::from_le_bytes(data[408..416].try_into().unwrap())));
+        let vesting_duration = i64::from_le_bytes(data[424..432].try_into().unwrap());
+        let vesting = (vesting_duration != 0).then(|| VestingSchedule {
+            vesting_cliff: i64::from_le_bytes(data[416..424].try_into().unwrap()),
+            vesting_duration,
+        });
+        let arbiter: [u8; 32] = data[432..464].try_into().unwrap();
+        let approvers: Vec<Pubkey> = data[464..624]
+            .chunks_exact(32)
+            .take(data[624] as usize)
+            .map(|approver| Pubkey::new_from_array(approver.try_into().unwrap()))
+            .collect();
+        let approved_by = approvers.iter().enumerate().filter(|(index, _)| data[626] & (1 << index) != 0).map(|(_, approver)| *approver).collect();
+        let approval = (data[625] != 0).then_some(ApprovalTerms { approvers, threshold: data[625] });
+        let hashlock: [u8; 32] = data[693..725].try_into().unwrap();
+        let bid_vault_payer: [u8; 32] = data[725..757].try_into().unwrap();
+        let milestones = data[627..691]
+            .chunks_exact(8)
+            .take(data[691] as usize)
+            .map(|amount| u64::from_le_bytes(amount.try_into().unwrap()))
+            .collect();
+
+        Ok(Self {
+            maker: Pubkey::new_from_array(data[2..34].try_into().unwrap()),
+            mint_a: Pubkey::new_from_array(data[34..66].try_into().unwrap()),
+            mint_b: Pubkey::new_from_array(data[66..98].try_into().unwrap()),
+            amount_b: u64::from_le_bytes(data[98..106].try_into().unwrap()),
+            amount_a: u64::from_le_bytes(data[106..114].try_into().unwrap()),
+            remaining_a: u64::from_le_bytes(data[114..122].try_into().unwrap()),
+            expires_at: i64::from_le_bytes(data[122..130].try_into().unwrap()),
+            fee_bps: u16::from_le_bytes(data[130..132].try_into().unwrap()),
+            taker: (taker != [0u8; 32]).then(|| Pubkey::new_from_array(taker)),
+            seed: match data[172] {
+                1 => EscrowSeed::Byte(data[164]),
+                _ => EscrowSeed::Wide(u64::from_le_bytes(data[164..172].try_into().unwrap())),
+            },
+            bump: data[173],
+            vault_bump: data[174],
+            alternatives,
+            oracle,
+            dutch_auction,
+            english_auction,
+            highest_bid,
+            vesting,
+            arbiter: (arbiter != [0u8; 32]).then(|| Pubkey::new_from_array(arbiter)),
+            approval,
+            approved_by,
+            milestones,
+            released_milestones: data[692],
+            hashlock: (hashlock != [0u8; 32]).then_some(hashlock),
+            bid_vault_payer: (bid_vault_payer != [0u8; 32]).then(|| Pubkey::new_from_array(bid_vault_payer)),
+        })
+    }
+}
+
+/// Decoded basket state account
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Basket {
+    pub maker: Pubkey,
+    pub mint_b: Pubkey,
+    pub amount_b: u64,
+    /// Unix timestamp after which the basket can no longer be taken, 0 for no expiry
+    pub expires_at: i64,
+    pub fee_bps: u16,
+    pub seed: u64,
+    pub bump: u8,
+    /// `(mint_a, amount_a)` of every leg, in the basket order
+    pub legs: Vec<(Pubkey, u64)>,
+}
+
+impl Basket {
+    pub const LEN: usize = 254;
+
+    /// Decodes the basket account data
+    pub fn from_bytes(data: &[u8]) -> Result<Self, EscrowError> {
+        if data.len() != Self::LEN || data[0] != 2 || data[1] != 1 || data[93] as usize > 4 {
+            return Err(EscrowError::InvalidEscrowData);
+        }
+        let legs = data[94..]
+            .chunks_exact(40)
+            .take(data[93] as usize)
+            .map(|leg| (Pubkey::new_from_array(leg[0..32].try_into().unwrap()), u64::from_le_bytes(leg[32..40].try_into().unwrap())))
+            .collect();
+
+        Ok(Self {
+            maker: Pubkey::new_from_array(data[2..34].try_into().unwrap()),
+            mint_b: Pubkey::new_from_array(data[34..66].try_into().unwrap()),
+            amount_b: u64::from_le_bytes(data[66..74].try_into().unwrap()),
+            expires_at: i64::from_le_bytes(data[74..82].try_into().unwrap()),
+            fee_bps: u16::from_le_bytes(data[82..84].try_into().unwrap()),
+            seed: u64::from_le_bytes(data[84..92].try_into().unwrap()),
+            bump: data[92],
+            legs,
+        })
+    }
+}
+
+/// Decoded vesting account, opened by the take of a vesting escrow
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Vesting {
+    pub beneficiary: Pubkey,
+    pub escrow: Pubkey,
+    /// The vesting mint, `NATIVE_MINT` for native SOL
+    pub mint_a: Pubkey,
+    pub amount_a: u64,
+    pub claimed_a: u64,
+    /// Unix timestamp of the take, the vesting growing linearly from it
+    pub start: i64,
+    /// Unix timestamp before which nothing can be claimed
+    pub cliff: i64,
+    /// Unix timestamp from which everything has vested
+    pub end: i64,
+    pub bump: u8,
+}
+
+impl Vesting {
+    pub const LEN: usize = 139;
+
+    /// Decodes the vesting account data
+    pub fn from_bytes(data: &[u8]) -> Result<Self, EscrowError> {
+        if data.len() != Self::LEN || data[0] != 3 || data[1] != 1 {
+            return Err(EscrowError::InvalidEscrowData);
+        }
+
+        Ok(Self {
+            beneficiary: Pubkey::new_from_array(data[2..34].try_into().unwrap()),
+            escrow: Pubkey::new_from_array(data[34..66].try_into().unwrap()),
+            mint_a: Pubkey::new_from_array(data[66..98].try_into().unwrap()),
+            amount_a: u64::from_le_bytes(data[98..106].try_into().unwrap()),
+            claimed_a: u64::from_le_bytes(data[106..114].try_into().unwrap()),
+            start: i64::from_le_bytes(data[114..122].try_into().unwrap()),
+            cliff: i64::from_le_bytes(data[122..130].try_into().unwrap()),
+            end: i64::from_le_bytes(data[130..138].try_into().unwrap()),
+            bump: data[138],
+        })
+    }
+}
@@ -0,0 +1,199 @@
+use pinocchio::error::ProgramError;
+use thiserror::Error;
+
+// Create an enum for the program errors
+#[derive(Clone, Copy, Debug, Eq, Error, PartialEq)]
+pub enum EscrowError {
+    #[error("The escrow has expired")]
+    EscrowExpired = 0,
+
+    #[error("The escrow has not expired yet")]
+    EscrowNotExpired = 1,
+
+    #[error("The expiry timestamp must be in the future")]
+    InvalidExpiry = 2,
+
+    #[error("The mint has an extension the escrow does not support")]
+    UnsupportedMintExtension = 3,
+
+    #[error("Unknown instruction discriminator")]
+    InvalidInstruction = 4,
+
+    #[error("Instruction data has an invalid length")]
+    InvalidInstructionData = 5,
+
+    #[error("A required signature is missing")]
+    MissingSigner = 6,
+
+    #[error("The token program is not supported or does not own the mint")]
+    InvalidTokenProgram = 7,
+
+    #[error("The mint is not a valid token mint")]
+    InvalidMint = 8,
+
+    #[error("The token account is not a valid account of the mint's token program")]
+    InvalidTokenAccount = 9,
+
+    #[error("The token account is not owned by the expected authority")]
+    TokenAccountAuthorityMismatch = 10,
+
+    #[error("The token account does not hold the expected mint")]
+    TokenAccountMintMismatch = 11,
+
+    #[error("The account is already initialized")]
+    AccountAlreadyInitialized = 12,
+
+    #[error("Amounts must be greater than zero")]
+    ZeroAmount = 13,
+
+    #[error("The escrow address does not match the derived PDA")]
+    InvalidEscrowAddress = 14,
+
+    #[error("The escrow account data is invalid")]
+    InvalidEscrowData = 15,
+
+    #[error("The mint does not match the one stored in the escrow")]
+    MintMismatch = 16,
+
+    #[error("The fill amount exceeds what is left in the escrow")]
+    FillExceedsRemaining = 17,
+
+    #[error("Arithmetic overflow")]
+    MathOverflow = 18,
+
+    #[error("The config account is invalid")]
+    InvalidConfig = 19,
+
+    #[error("The fee exceeds the maximum of 1000 basis points")]
+    InvalidFee = 20,
+
+    #[error("The signer is not the config admin, or for INIT_CONFIG the program upgrade authority")]
+    Unauthorized = 21,
+
+    #[error("The escrow is reserved for a different taker")]
+    TakerNotAllowed = 22,
+
+    #[error("The native SOL vault address does not match the escrow")]
+    InvalidVaultAddress = 23,
+
+    #[error("The escrow terms moved past the taker's slippage limit")]
+    SlippageExceeded = 24,
+
+    #[error("The event authority or program account is invalid")]
+    InvalidEventAuthority = 25,
+
+    #[error("The escrow uses an older layout and must be migrated first")]
+    OutdatedEscrowVersion = 26,
+
+    #[error("The maker does not match the one stored in the escrow")]
+    MakerMismatch = 27,
+
+    #[error("The bump is not the canonical bump of the escrow PDA")]
+    NonCanonicalBump = 28,
+
+    #[error("The basket legs are invalid: none, too many, a duplicate mint or not the ones stored in the basket")]
+    InvalidBasketLegs = 29,
+
+    #[error("The payment alternatives are invalid: too many, a duplicate mint or the escrow mint_b")]
+    InvalidPaymentAlternatives = 30,
+
+    #[error("The price feed account is invalid or holds a non-positive price")]
+    InvalidPriceFeed = 31,
+
+    #[error("The price feed has not been updated recently enough")]
+    StalePrice = 32,
+
+    #[error("The price feed confidence interval is too wide")]
+    PriceConfidenceTooWide = 33,
+
+    #[error("The auction terms are invalid")]
+    InvalidAuctionTerms = 34,
+
+    #[error("The bid is under the reserve price or does not raise the highest bid by the minimum increment")]
+    BidTooLow = 35,
+
+    #[error("The auction has ended")]
+    AuctionEnded = 36,
+
+    #[error("The auction has not ended yet")]
+    AuctionNotEnded = 37,
+
+    #[error("The auction has a standing bid, it can only be settled")]
+    AuctionHasBid = 38,
+
+    #[error("The auction has no bid to settle")]
+    AuctionHasNoBid = 39,
+
+    #[error("The vesting schedule is invalid")]
+    InvalidVestingTerms = 40,
+
+    #[error("Nothing has vested since the last claim")]
+    NothingToClaim = 41,
+
+    #[error("The arbiter or the seller of the escrow is invalid")]
+    InvalidArbiterTerms = 42,
+
+    #[error("The escrow is settled by RELEASE or its arbiter")]
+    ArbitratedEscrow = 43,
+
+    #[error("The signer is not allowed to release the escrow")]
+    UnauthorizedRelease = 44,
+
+    #[error("The approvers, the approval threshold, the beneficiary or the expiry are invalid")]
+    InvalidApprovers = 45,
+
+    #[error("The signer is not an approver of the escrow")]
+    NotAnApprover = 46,
+
+    #[error("The approver has already approved the escrow")]
+    AlreadyApproved = 47,
+
+    #[error("The approver has not approved the escrow")]
+    NotApproved = 48,
+
+    #[error("The escrow is released by its approvers")]
+    ApprovalEscrow = 49,
+
+    #[error("The milestones, the arbiter or the payee are invalid")]
+    InvalidMilestones = 50,
+
+    #[error("The escrow is settled by RELEASE_MILESTONE or CANCEL")]
+    MilestoneEscrow = 51,
+
+    #[error("The milestone is not the next one to release")]
+    MilestoneOutOfOrder = 52,
+
+    #[error("The hashlock, the timeout or the taker are invalid")]
+    InvalidHashlock = 53,
+
+    #[error("The preimage does not match the hashlock")]
+    InvalidPreimage = 54,
+
+    #[error("The hashlocked escrow can't be updated, nor refunded before its timeout")]
+    HashlockedEscrow = 55,
+
+    #[error("The program data account is not the one of this program")]
+    InvalidProgramData = 56,
+
+    #[error("The escrow can't ask for the mint it deposits")]
+    SameMint = 57,
+
+    #[error("An oracle-priced take must bound the mint_b it pays with max_amount_b")]
+    MissingSlippageGuard = 58,
+
+    #[error("The bid vault rent must go back to the bidder who paid it")]
+    BidVaultPayerMismatch = 59,
+
+    #[error("A vesting escrow must be taken whole")]
+    PartialVestingFill = 60,
+
+    #[error("A hashlocked escrow must be taken whole")]
+    PartialHashlockFill = 61,
+}
+
+// Map the program errors to custom program error codes
+impl From<EscrowError> for ProgramError {
+    fn from(error: EscrowError) -> Self {
+        ProgramError::Custom(error as u32)
+    }
+}
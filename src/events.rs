@@ -0,0 +1,392 @@
+use pinocchio::{
+    AccountView, Address, ProgramResult,
+    cpi::{Seed, Signer, invoke_signed},
+    instruction::{InstructionAccount, InstructionView},
+};
+
+use crate::errors::EscrowError;
+
+/// Prefix of the self-CPI event instruction, Anchor's `EVENT_IX_TAG` (`sha256("anchor:event")[..8]`) in little-endian
+pub const EVENT_IX_TAG: [u8; 8] = [0xe4, 0x45, 0xa5, 0x2e, 0x51, 0xcb, 0x9a, 0x1d];
+
+/// Seed of the PDA signing the self-CPI, so only this program can emit events
+pub const EVENT_AUTHORITY_SEED: &[u8] = b"__event_authority";
+
+/// Canonical bump of the event authority PDA
+pub const EVENT_AUTHORITY_BUMP: u8 = 255;
+
+/// Largest event instruction: tag, event discriminator and the biggest event
+const MAX_EVENT_IX_LEN: usize = 256;
+
+/// An event emitted through the self-CPI event instruction.
+/// Events are borsh-encoded: fields in declaration order, addresses as 32 bytes and integers little-endian.
+pub trait Event {
+    /// `sha256("event:<Name>")[..8]`, as Anchor computes it
+    const DISCRIMINATOR: [u8; 8];
+
+    fn serialize(&self, writer: &mut EventWriter);
+}
+
+/// Fixed-size buffer the event instruction data is written into
+pub struct EventWriter {
+    data: [u8; MAX_EVENT_IX_LEN],
+    len: usize,
+}
+
+impl EventWriter {
+    fn bytes(&mut self, bytes: &[u8]) {
+        self.data[self.len..self.len + bytes.len()].copy_from_slice(bytes);
+        self.len += bytes.len();
+    }
+
+    pub fn address(&mut self, address: &Address) {
+        self.bytes(address.as_ref());
+    }
+
+    pub fn u64(&mut self, value: u64) {
+        self.bytes(&value.to_le_bytes());
+    }
+
+    pub fn i64(&mut self, value: i64) {
+        self.bytes(&value.to_le_bytes());
+    }
+
+    pub fn u8(&mut self, value: u8) {
+        self.bytes(&[value]);
+    }
+}
+
+/// Checks the event authority is the program PDA
+pub fn check_event_authority(event_authority: &AccountView) -> ProgramResult {
+    let seeds = [EVENT_AUTHORITY_SEED, &[EVENT_AUTHORITY_BUMP]];
+    let event_authority_pda = pinocchio_pubkey::derive_address_const(&seeds, None, crate::ID.as_array());
+    if event_authority_pda != event_authority.address().to_bytes() {
+        return Err(EscrowError::InvalidEventAuthority.into());
+    }
+    Ok(())
+}
+
+/// Emits the event by invoking this program with the event instruction, signed by the event authority PDA.
+/// Indexers read it back from the inner instruction data, which unlike the logs is never truncated.
+pub fn emit<E: Event>(event: &E, event_authority: &AccountView, program: &AccountView) -> ProgramResult {
+    check_event_authority(event_authority)?;
+    if program.address() != &crate::ID {
+        return Err(EscrowError::InvalidEventAuthority.into());
+    }
+
+    let mut writer = EventWriter { data: [0u8; MAX_EVENT_IX_LEN], len: 0 };
+    writer.bytes(&EVENT_IX_TAG);
+    writer.bytes(&E::DISCRIMINATOR);
+    event.serialize(&mut writer);
+
+    let instruction_accounts = [InstructionAccount::readonly_signer(event_authority.address())];
+    let instruction = InstructionView {
+        program_id: &crate::ID,
+        accounts: &instruction_accounts,
+        data: &writer.data[..writer.len],
+    };
+
+    let bump = [EVENT_AUTHORITY_BUMP];
+    let signer_seeds = [Seed::from(EVENT_AUTHORITY_SEED), Seed::from(bump.as_ref())];
+    invoke_signed(&instruction, &[event_authority], &[Signer::from(&signer_seeds)])
+}
+
+/// Emitted by MAKE once the escrow is funded
+pub struct EscrowCreated {
+    pub escrow: Address,
+    pub maker: Address,
+    pub mint_a: Address,
+    pub mint_b: Address,
+    pub taker: Address,
+    pub amount_a: u64,
+    pub amount_b: u64,
+    pub expires_at: i64,
+    pub seed: u64,
+}
+
+impl Event for EscrowCreated {
+    const DISCRIMINATOR: [u8; 8] = [0x46, 0x7f, 0x69, 0x66, 0x5c, 0x61, 0x07, 0xad];
+
+    fn serialize(&self, writer: &mut EventWriter) {
+        writer.address(&self.escrow);
+        writer.address(&self.maker);
+        writer.address(&self.mint_a);
+        writer.address(&self.mint_b);
+        writer.address(&self.taker);
+        writer.u64(self.amount_a);
+        writer.u64(self.amount_b);
+        writer.i64(self.expires_at);
+        writer.u64(self.seed);
+    }
+}
+
+/// Emitted by TAKE for every fill
+pub struct EscrowTaken {
+    pub escrow: Address,
+    pub maker: Address,
+    pub taker: Address,
+    pub mint_a: Address,
+    pub mint_b: Address,
+    pub amount_a: u64,
+    pub amount_b: u64,
+    pub fee: u64,
+    pub remaining_a: u64,
+    pub seed: u64,
+}
+
+impl Event for EscrowTaken {
+    const DISCRIMINATOR: [u8; 8] = [0xa4, 0x4f, 0x32, 0x1a, 0xae, 0x95, 0x5c, 0x9e];
+
+    fn serialize(&self, writer: &mut EventWriter) {
+        writer.address(&self.escrow);
+        writer.address(&self.maker);
+        writer.address(&self.taker);
+        writer.address(&self.mint_a);
+        writer.address(&self.mint_b);
+        writer.u64(self.amount_a);
+        writer.u64(self.amount_b);
+        writer.u64(self.fee);
+        writer.u64(self.remaining_a);
+        writer.u64(self.seed);
+    }
+}
+
+/// Emitted by REFUND, CLOSE_EXPIRED and CANCEL when the vault is returned to the maker
+pub struct EscrowRefunded {
+    pub escrow: Address,
+    pub maker: Address,
+    pub mint_a: Address,
+    pub mint_b: Address,
+    pub amount_a: u64,
+    pub seed: u64,
+}
+
+impl Event for EscrowRefunded {
+    const DISCRIMINATOR: [u8; 8] = [0x84, 0xd1, 0x31, 0x6d, 0x87, 0x8a, 0x1c, 0x51];
+
+    fn serialize(&self, writer: &mut EventWriter) {
+        writer.address(&self.escrow);
+        writer.address(&self.maker);
+        writer.address(&self.mint_a);
+        writer.address(&self.mint_b);
+        writer.u64(self.amount_a);
+        writer.u64(self.seed);
+    }
+}
+
+/// Emitted by MAKE_BASKET once every leg is deposited
+pub struct BasketCreated {
+    pub basket: Address,
+    pub maker: Address,
+    pub mint_b: Address,
+    pub amount_b: u64,
+    pub expires_at: i64,
+    pub leg_count: u8,
+    pub seed: u64,
+}
+
+impl Event for BasketCreated {
+    const DISCRIMINATOR: [u8; 8] = [0x1a, 0x92, 0x6c, 0x9b, 0xbd, 0x55, 0x08, 0x07];
+
+    fn serialize(&self, writer: &mut EventWriter) {
+        writer.address(&self.basket);
+        writer.address(&self.maker);
+        writer.address(&self.mint_b);
+        writer.u64(self.amount_b);
+        writer.i64(self.expires_at);
+        writer.u8(self.leg_count);
+        writer.u64(self.seed);
+    }
+}
+
+/// Emitted by TAKE_BASKET once every leg is settled
+pub struct BasketTaken {
+    pub basket: Address,
+    pub maker: Address,
+    pub taker: Address,
+    pub mint_b: Address,
+    pub amount_b: u64,
+    pub fee: u64,
+    pub seed: u64,
+}
+
+impl Event for BasketTaken {
+    const DISCRIMINATOR: [u8; 8] = [0x23, 0x1e, 0xfb, 0x44, 0x58, 0x80, 0x9f, 0x11];
+
+    fn serialize(&self, writer: &mut EventWriter) {
+        writer.address(&self.basket);
+        writer.address(&self.maker);
+        writer.address(&self.taker);
+        writer.address(&self.mint_b);
+        writer.u64(self.amount_b);
+        writer.u64(self.fee);
+        writer.u64(self.seed);
+    }
+}
+
+/// Emitted by REFUND_BASKET once every leg is returned to the maker
+pub struct BasketRefunded {
+    pub basket: Address,
+    pub maker: Address,
+    pub mint_b: Address,
+    pub seed: u64,
+}
+
+impl Event for BasketRefunded {
+    const DISCRIMINATOR: [u8; 8] = [0x11, 0xec, 0xc6, 0x5c, 0xcc, 0x67, 0x78, 0x35];
+
+    fn serialize(&self, writer: &mut EventWriter) {
+        writer.address(&self.basket);
+        writer.address(&self.maker);
+        writer.address(&self.mint_b);
+        writer.u64(self.seed);
+    }
+}
+
+/// Emitted by BID when a bid becomes the highest, the previous one being refunded
+pub struct BidPlaced {
+    pub escrow: Address,
+    pub bidder: Address,
+    pub amount_b: u64,
+    pub previous_bidder: Address,
+    pub previous_amount_b: u64,
+    pub seed: u64,
+}
+
+impl Event for BidPlaced {
+    const DISCRIMINATOR: [u8; 8] = [0x87, 0x35, 0xb0, 0x53, 0xc1, 0x45, 0x6c, 0x3d];
+
+    fn serialize(&self, writer: &mut EventWriter) {
+        writer.address(&self.escrow);
+        writer.address(&self.bidder);
+        writer.u64(self.amount_b);
+        writer.address(&self.previous_bidder);
+        writer.u64(self.previous_amount_b);
+        writer.u64(self.seed);
+    }
+}
+
+/// Emitted by CLAIM when vested `mint_a` is paid out to the beneficiary
+pub struct VestingClaimed {
+    pub vesting: Address,
+    pub escrow: Address,
+    pub beneficiary: Address,
+    pub mint_a: Address,
+    pub amount_a: u64,
+    pub claimed_a: u64,
+    pub total_a: u64,
+}
+
+impl Event for VestingClaimed {
+    const DISCRIMINATOR: [u8; 8] = [0xa6, 0x3e, 0x87, 0x9e, 0x89, 0x01, 0x55, 0x0f];
+
+    fn serialize(&self, writer: &mut EventWriter) {
+        writer.address(&self.vesting);
+        writer.address(&self.escrow);
+        writer.address(&self.beneficiary);
+        writer.address(&self.mint_a);
+        writer.u64(self.amount_a);
+        writer.u64(self.claimed_a);
+        writer.u64(self.total_a);
+    }
+}
+
+/// Emitted by RELEASE and DISPUTE_RESOLVE when the vault of an arbitrated escrow goes to the seller or back to the maker
+pub struct EscrowReleased {
+    pub escrow: Address,
+    pub maker: Address,
+    pub seller: Address,
+    pub released_by: Address,
+    pub mint_a: Address,
+    pub amount_a: u64,
+    pub to_seller: u8,
+    pub seed: u64,
+}
+
+impl Event for EscrowReleased {
+    const DISCRIMINATOR: [u8; 8] = [0x83, 0x07, 0x8a, 0x68, 0xa6, 0xbe, 0x71, 0x70];
+
+    fn serialize(&self, writer: &mut EventWriter) {
+        writer.address(&self.escrow);
+        writer.address(&self.maker);
+        writer.address(&self.seller);
+        writer.address(&self.released_by);
+        writer.address(&self.mint_a);
+        writer.u64(self.amount_a);
+        writer.u8(self.to_seller);
+        writer.u64(self.seed);
+    }
+}
+
+/// Emitted by APPROVE and REVOKE_APPROVAL when an approver approves an approval escrow or withdraws their approval
+pub struct ApprovalRecorded {
+    pub escrow: Address,
+    pub approver: Address,
+    pub approved: u8,
+    pub approvals: u8,
+    pub threshold: u8,
+    pub seed: u64,
+}
+
+impl Event for ApprovalRecorded {
+    const DISCRIMINATOR: [u8; 8] = [0xd2, 0x62, 0x4f, 0x13, 0xa7, 0x90, 0x91, 0x63];
+
+    fn serialize(&self, writer: &mut EventWriter) {
+        writer.address(&self.escrow);
+        writer.address(&self.approver);
+        writer.u8(self.approved);
+        writer.u8(self.approvals);
+        writer.u8(self.threshold);
+        writer.u64(self.seed);
+    }
+}
+
+/// Emitted by RELEASE_MILESTONE when a milestone of a milestone escrow is paid to the payee
+pub struct MilestoneReleased {
+    pub escrow: Address,
+    pub maker: Address,
+    pub payee: Address,
+    pub released_by: Address,
+    pub mint_a: Address,
+    pub milestone: u8,
+    pub amount_a: u64,
+    pub remaining_a: u64,
+    pub seed: u64,
+}
+
+impl Event for MilestoneReleased {
+    const DISCRIMINATOR: [u8; 8] = [0x31, 0xe1, 0x5b, 0xdf, 0x22, 0xa5, 0x6d, 0xb5];
+
+    fn serialize(&self, writer: &mut EventWriter) {
+        writer.address(&self.escrow);
+        writer.address(&self.maker);
+        writer.address(&self.payee);
+        writer.address(&self.released_by);
+        writer.address(&self.mint_a);
+        writer.u8(self.milestone);
+        writer.u64(self.amount_a);
+        writer.u64(self.remaining_a);
+        writer.u64(self.seed);
+    }
+}
+
+/// Emitted by TAKE when the taker of a hashlocked escrow reveals the preimage, so the counterparty can use it on the other chain
+pub struct HashlockRevealed {
+    pub escrow: Address,
+    pub taker: Address,
+    pub hashlock: [u8; 32],
+    pub preimage: [u8; 32],
+    pub seed: u64,
+}
+
+impl Event for HashlockRevealed {
+    const DISCRIMINATOR: [u8; 8] = [0x53, 0xfe, 0x38, 0xfd, 0x10, 0x4f, 0x64, 0x50];
+
+    fn serialize(&self, writer: &mut EventWriter) {
+        writer.address(&self.escrow);
+        writer.address(&self.taker);
+        writer.bytes(&self.hashlock);
+        writer.bytes(&self.preimage);
+        writer.u64(self.seed);
+    }
+}
@@ -0,0 +1,175 @@
+use pinocchio::{
+    AccountView, ProgramResult, cpi::{Seed, Signer}, error::ProgramError, sysvars::{Sysvar, clock::Clock},
+};
+use crate::{errors::EscrowError, events::{ApprovalRecorded, EscrowReleased, emit}, state::Escrow, token_interface::{CloseAccount, Mint, TokenAccount, TransferChecked, check_native_vault, check_token_program, check_token_vault, close_native_vault, is_native_mint, withdraw_native}};
+
+/// # Approve Instruction
+///
+/// This function allows a registered approver of an approval escrow to approve its release to the beneficiary
+///
+/// ## Business Logic:
+/// 1. Validate all accounts and verify the escrow PDA from the seeds stored in the escrow account
+/// 2. Verify the maker, mint_a and the beneficiary match the ones stored in the escrow account, that it has not expired
+///    and that the signer is a registered approver that has not approved yet
+/// 3. Record the approval and emit the ApprovalRecorded event through the self-CPI event instruction
+/// 4. Once the approval threshold is reached, transfer the whole vault of mint_a to the beneficiary (signed by the escrow PDA),
+///    its full balance for a token vault, close the vault and the escrow account, returning rent to the maker, and emit the EscrowReleased event
+///
+/// ## Accounts Expected:
+/// 0. [signer] approver - A registered approver
+/// 1. [writable] maker - The maker that created the escrow, receiving the rent
+/// 2. [writable] beneficiary - The designated taker receiving the mint_a
+/// 3. [] mint_a - The deposited mint (the system program for native SOL)
+/// 4. [writable] beneficiary_ata_a - The beneficiary ATA of the `mint_a` (the beneficiary for native SOL)
+/// 5. [writable] vault - The escrow ATA of the `mint_a` (the native vault PDA for native SOL)
+/// 6. [writable] escrow - The escrow state account recording the approvals
+/// 7. [] system_program - The system program
+/// 8. [] token_program - The token program that owns `mint_a` (SPL Token or Token-2022, the system program for native SOL)
+/// 9. [] event_authority - The event authority PDA signing the event self-CPI
+/// 10. [] program - The escrow program, invoked to emit the event
+pub fn approve(accounts: &[AccountView], _instruction_data: &[u8]) -> ProgramResult {
+
+    // Unpack accounts - Validate expected accounts
+    let [approver, maker, beneficiary, mint_a, beneficiary_ata_a, vault, escrow, _system_program, token_program, event_authority, program, _remaining @..] = accounts else {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    };
+
+    // Check if approver is signer
+    if !approver.is_signer() {
+        return Err(EscrowError::MissingSigner.into());
+    }
+
+    // Check if the token program is supported and owns mint_a
+    check_token_program(mint_a, token_program)?;
+    let native_a = is_native_mint(mint_a);
+
+    // Validate the mint_a accounts: the beneficiary receives native SOL directly, otherwise through its ATA
+    if native_a {
+        if beneficiary_ata_a.address() != beneficiary.address() {
+            return Err(EscrowError::InvalidTokenAccount.into());
+        }
+    } else {
+        // Validate the ATAs are owned by the token program of mint_a
+        if !beneficiary_ata_a.owned_by(token_program.address()) || !vault.owned_by(token_program.address()) {
+            return Err(EscrowError::InvalidTokenAccount.into());
+        }
+
+        // Validate the beneficiary ATA mint and authority
+        if TokenAccount::from_account_view(beneficiary_ata_a)?.owner() != beneficiary.address() {
+            return Err(EscrowError::TokenAccountAuthorityMismatch.into());
+        }
+        if TokenAccount::from_account_view(beneficiary_ata_a)?.mint() != mint_a.address() {
+            return Err(EscrowError::TokenAccountMintMismatch.into());
+        }
+
+        // Validate the vault is the escrow ATA of mint_a, with its mint and authority
+        check_token_vault(vault, escrow, mint_a, token_program)?;
+        if TokenAccount::from_account_view(vault)?.owner() != escrow.address() {
+            return Err(EscrowError::TokenAccountAuthorityMismatch.into());
+        }
+        if TokenAccount::from_account_view(vault)?.mint() != mint_a.address() {
+            return Err(EscrowError::TokenAccountMintMismatch.into());
+        }
+    }
+
+    // Validate escrow PDA (derive expected PDA and verify it matches provided address)
+    let escrow_account = Escrow::from_account_info_mut(escrow)?;
+    let escrow_seeds = [(b"escrow"), maker.address().as_ref(), escrow_account.seed(), escrow_account.bump.as_slice()];
+    let escrow_pda = pinocchio_pubkey::derive_address_const(&escrow_seeds, None, crate::ID.as_array());
+    if escrow_pda != escrow.address().to_bytes() {
+        return Err(EscrowError::InvalidEscrowAddress.into());
+    }
+
+    // Validate the maker, mint_a and the beneficiary are the ones stored in the escrow
+    if maker.address() != &escrow_account.maker() {
+        return Err(EscrowError::MakerMismatch.into());
+    }
+    if mint_a.address() != &escrow_account.mint_a() {
+        return Err(EscrowError::MintMismatch.into());
+    }
+    if !escrow_account.is_approval_escrow() {
+        return Err(EscrowError::InvalidApprovers.into());
+    }
+    if Some(*beneficiary.address()) != escrow_account.taker() {
+        return Err(EscrowError::TakerNotAllowed.into());
+    }
+
+    // Validate the native vault belongs to this escrow
+    if native_a {
+        check_native_vault(vault, escrow, escrow_account.vault_bump)?;
+    }
+
+    // An expired escrow goes back to the maker through CLOSE_EXPIRED
+    if escrow_account.is_expired(Clock::get()?.unix_timestamp) {
+        return Err(EscrowError::EscrowExpired.into());
+    }
+
+    // Record the approval of a registered approver, once
+    let index = escrow_account.approver_index(approver.address()).ok_or(EscrowError::NotAnApprover)?;
+    if escrow_account.has_approved(index) {
+        return Err(EscrowError::AlreadyApproved.into());
+    }
+    escrow_account.set_approved(index, true);
+
+    // Emit the ApprovalRecorded event
+    let seed = escrow_account.seed_value();
+    let approvals = escrow_account.approval_count();
+    emit(&ApprovalRecorded {
+        escrow: *escrow.address(),
+        approver: *approver.address(),
+        approved: 1,
+        approvals,
+        threshold: escrow_account.approval_threshold(),
+        seed,
+    }, event_authority, program)?;
+
+    // Keep the escrow open until the threshold is reached
+    if approvals < escrow_account.approval_threshold() {
+        return Ok(());
+    }
+
+    // Transfer the whole vault to the beneficiary, tokens sent to it on top of the deposit included so it can be closed
+    let signer_seeds = [Seed::from(b"escrow"), Seed::from(maker.address().as_ref()), Seed::from(escrow_account.seed()), Seed::from(escrow_account.bump.as_ref())];
+    let signers = Signer::from(&signer_seeds);
+    let amount_a = if native_a { escrow_account.remaining_a() } else { TokenAccount::from_account_view(vault)?.amount() };
+    if native_a {
+        withdraw_native(vault, beneficiary_ata_a, amount_a)?;
+        close_native_vault(vault, maker);
+    } else {
+        TransferChecked {
+            from: vault,
+            mint: mint_a,
+            to: beneficiary_ata_a,
+            authority: escrow,
+            amount: amount_a,
+            decimals: Mint::from_account_view(mint_a)?.decimals(),
+            token_program: token_program.address(),
+        }.invoke_signed(core::slice::from_ref(&signers))?;
+
+        // Close Vault Account
+        CloseAccount {
+            account: vault,
+            destination: maker,
+            authority: escrow,
+            token_program: token_program.address(),
+        }.invoke_signed(&[signers])?;
+    }
+
+    // Manually close the escrow account and return rent to the maker
+    maker.set_lamports(maker.lamports() + escrow.lamports());
+    escrow.set_lamports(0);
+
+    // Emit the EscrowReleased event
+    emit(&EscrowReleased {
+        escrow: *escrow.address(),
+        maker: *maker.address(),
+        seller: *beneficiary.address(),
+        released_by: *approver.address(),
+        mint_a: *mint_a.address(),
+        amount_a,
+        to_seller: 1,
+        seed,
+    }, event_authority, program)?;
+
+    Ok(())
+}
@@ -0,0 +1,187 @@
+use pinocchio::{
+    AccountView, ProgramResult, cpi::{Seed, Signer}, error::ProgramError, sysvars::{Sysvar, clock::Clock},
+};
+use pinocchio_associated_token_account::instructions::CreateIdempotent;
+use crate::{errors::EscrowError, events::{BidPlaced, emit}, state::Escrow, token_interface::{Mint, TokenAccount, TransferChecked, check_token_program, check_token_vault, is_native_mint}};
+
+/// # Bid Instruction
+///
+/// This function allows a bidder to place a bid on an English auction escrow, locking `mint_b` in the bid vault
+///
+/// ## Business Logic:
+/// 1. Validate all accounts and verify the escrow PDA from the seeds stored in the escrow account
+/// 2. Verify the maker and mint_b match the ones stored in the escrow account, that it is an English auction still taking bids
+///    and, for a private escrow, that the bidder is the designated taker
+/// 3. Check the bid reaches the reserve price (the escrow amount_b) for the first bid, or raises the highest bid by the minimum increment
+/// 4. Create the bid vault ATA with the escrow PDA as authority on the first bid, unless someone already did, recording
+///    the bidder as its rent payer, and transfer the bid from the bidder's ATA to it
+/// 5. Refund the previous highest bid from the bid vault to the previous bidder (signed by the escrow PDA), in the same instruction
+/// 6. Record the new highest bid and emit the BidPlaced event through the self-CPI event instruction
+///
+/// ## Accounts Expected:
+/// 0. [signer] bidder - The user placing the bid, paying the bid vault rent on the first bid
+/// 1. [] maker - The maker that created the escrow
+/// 2. [] mint_b - The mint of the bids
+/// 3. [writable] bidder_ata_b - The bidder ATA of the `mint_b`
+/// 4. [writable] bid_vault - The escrow ATA of the `mint_b` holding the highest bid, created on the first bid if it does not exist yet (must be the escrow ATA)
+/// 5. [writable] escrow - The escrow state account recording the highest bid
+/// 6. [] system_program - The system program for account creation
+/// 7. [] token_program_b - The token program that owns `mint_b` (SPL Token or Token-2022)
+/// 8. [] associated_token_program - The associated token program for ATA creation
+/// 9. [] event_authority - The event authority PDA signing the event self-CPI
+/// 10. [] program - The escrow program, invoked to emit the event
+/// 11. [writable] previous_bidder_ata_b - The ATA of the `mint_b` of the highest bidder, refunded, only when there is a bid already
+///
+/// ## Data Parameters:
+/// 0. [u8; 8] amount_b - The amount of mint_b bid for the whole amount_a (u64)
+pub fn bid(accounts: &[AccountView], instruction_data: &[u8]) -> ProgramResult {
+
+    // Unpack accounts - Validate expected accounts
+    let [bidder, maker, mint_b, bidder_ata_b, bid_vault, escrow, system_program, token_program_b, _associated_token_program, event_authority, program, remaining @..] = accounts else {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    };
+
+    // Check if bidder is signer
+    if !bidder.is_signer() {
+        return Err(EscrowError::MissingSigner.into());
+    }
+
+    // Check if the token program is supported and owns mint_b, bids are only locked in token vaults
+    check_token_program(mint_b, token_program_b)?;
+    if is_native_mint(mint_b) {
+        return Err(EscrowError::InvalidMint.into());
+    }
+
+    // Validate the bidder ATA mint and authority
+    if !bidder_ata_b.owned_by(token_program_b.address()) {
+        return Err(EscrowError::InvalidTokenAccount.into());
+    }
+    if TokenAccount::from_account_view(bidder_ata_b)?.owner() != bidder.address() {
+        return Err(EscrowError::TokenAccountAuthorityMismatch.into());
+    }
+    if TokenAccount::from_account_view(bidder_ata_b)?.mint() != mint_b.address() {
+        return Err(EscrowError::TokenAccountMintMismatch.into());
+    }
+
+    // Validate escrow PDA (derive expected PDA and verify it matches provided address)
+    let escrow_account = Escrow::from_account_info_mut(escrow)?;
+    let escrow_seeds = [(b"escrow"), maker.address().as_ref(), escrow_account.seed(), escrow_account.bump.as_slice()];
+    let escrow_pda = pinocchio_pubkey::derive_address_const(&escrow_seeds, None, crate::ID.as_array());
+    if escrow_pda != escrow.address().to_bytes() {
+        return Err(EscrowError::InvalidEscrowAddress.into());
+    }
+
+    // Validate the maker and mint_b are the ones stored in the escrow
+    if maker.address() != &escrow_account.maker() {
+        return Err(EscrowError::MakerMismatch.into());
+    }
+    if mint_b.address() != &escrow_account.mint_b() {
+        return Err(EscrowError::MintMismatch.into());
+    }
+
+    // Validate the escrow is an English auction still taking bids
+    if !escrow_account.is_english_auction() {
+        return Err(EscrowError::InvalidAuctionTerms.into());
+    }
+    if escrow_account.is_bidding_over(Clock::get()?.unix_timestamp) {
+        return Err(EscrowError::AuctionEnded.into());
+    }
+
+    // Validate the bidder is allowed to bid on a private escrow
+    if !escrow_account.is_taker_allowed(&bidder.address().to_bytes()) {
+        return Err(EscrowError::TakerNotAllowed.into());
+    }
+
+    // Validate data parameters
+    if instruction_data.len() != 8 {
+        return Err(EscrowError::InvalidInstructionData.into());
+    }
+
+    // Validate the bid against the reserve price or the highest bid
+    let amount_b = u64::from_le_bytes(instruction_data[0..8].try_into().unwrap());
+    if amount_b < escrow_account.min_bid().ok_or(EscrowError::MathOverflow)? {
+        return Err(EscrowError::BidTooLow.into());
+    }
+
+    // Validate the bid vault is the escrow ATA of mint_b: created by the first bid, then holding the highest bid.
+    // The ATA can be created by anyone ahead of the first bid, so an existing one is accepted rather than blocking the auction
+    check_token_vault(bid_vault, escrow, mint_b, token_program_b)?;
+    let previous_bidder = escrow_account.highest_bidder();
+    if previous_bidder.is_none() {
+        CreateIdempotent {
+            funding_account: bidder,
+            account: bid_vault,
+            wallet: escrow,
+            mint: mint_b,
+            system_program,
+            token_program: token_program_b,
+        }.invoke()?;
+        escrow_account.set_bid_vault_payer(bidder.address().to_bytes());
+    } else {
+        if !bid_vault.owned_by(token_program_b.address()) {
+            return Err(EscrowError::InvalidTokenAccount.into());
+        }
+        if TokenAccount::from_account_view(bid_vault)?.owner() != escrow.address() {
+            return Err(EscrowError::TokenAccountAuthorityMismatch.into());
+        }
+        if TokenAccount::from_account_view(bid_vault)?.mint() != mint_b.address() {
+            return Err(EscrowError::TokenAccountMintMismatch.into());
+        }
+    }
+
+    // Lock the bid in the bid vault
+    let decimals_b = Mint::from_account_view(mint_b)?.decimals();
+    TransferChecked {
+        from: bidder_ata_b,
+        mint: mint_b,
+        to: bid_vault,
+        authority: bidder,
+        amount: amount_b,
+        decimals: decimals_b,
+        token_program: token_program_b.address(),
+    }.invoke()?;
+
+    // Refund the previous highest bid, signed by the escrow PDA
+    let previous_amount_b = escrow_account.highest_bid();
+    if let Some(previous_bidder) = previous_bidder {
+        let previous_bidder_ata_b = remaining.first().ok_or(ProgramError::NotEnoughAccountKeys)?;
+
+        // Validate the previous bidder ATA mint and authority
+        if !previous_bidder_ata_b.owned_by(token_program_b.address()) {
+            return Err(EscrowError::InvalidTokenAccount.into());
+        }
+        if TokenAccount::from_account_view(previous_bidder_ata_b)?.owner() != &previous_bidder {
+            return Err(EscrowError::TokenAccountAuthorityMismatch.into());
+        }
+        if TokenAccount::from_account_view(previous_bidder_ata_b)?.mint() != mint_b.address() {
+            return Err(EscrowError::TokenAccountMintMismatch.into());
+        }
+
+        let signer_seeds = [Seed::from(b"escrow"), Seed::from(maker.address().as_ref()), Seed::from(escrow_account.seed()), Seed::from(escrow_account.bump.as_ref())];
+        let signers = Signer::from(&signer_seeds);
+        TransferChecked {
+            from: bid_vault,
+            mint: mint_b,
+            to: previous_bidder_ata_b,
+            authority: escrow,
+            amount: previous_amount_b,
+            decimals: decimals_b,
+            token_program: token_program_b.address(),
+        }.invoke_signed(&[signers])?;
+    }
+
+    // Record the new highest bid
+    escrow_account.set_highest_bid(bidder.address().to_bytes(), amount_b);
+
+    // Emit the BidPlaced event
+    emit(&BidPlaced {
+        escrow: *escrow.address(),
+        bidder: *bidder.address(),
+        amount_b,
+        previous_bidder: previous_bidder.unwrap_or_default(),
+        previous_amount_b,
+        seed: escrow_account.seed_value(),
+    }, event_authority, program)?;
+
+    Ok(())
+}
@@ -0,0 +1,151 @@
+use pinocchio::{
+    AccountView, ProgramResult, cpi::{Seed, Signer}, error::ProgramError,
+};
+use crate::{errors::EscrowError, events::{EscrowRefunded, emit}, state::Escrow, token_interface::{CloseAccount, Mint, TokenAccount, TransferChecked, check_native_vault, check_token_program, check_token_vault, close_native_vault, is_native_mint}};
+
+/// # Cancel Instruction
+///
+/// This function allows the maker (the payer) of a milestone escrow to cancel the milestones not released yet, returning their
+/// funds to the maker. When the escrow has an arbiter, the cancel is signed by the arbiter, or by the maker with the payee's consent
+///
+/// ## Business Logic:
+/// 1. Validate all accounts and verify the escrow PDA from the seeds stored in the escrow account
+/// 2. Verify the maker and mint_a match the ones stored in the escrow account and that the signer is the maker without an arbiter,
+///    otherwise the arbiter or the maker co-signed by the payee
+/// 3. Transfer the whole vault of mint_a back to the maker (signed by the escrow PDA)
+/// 4. Close the vault and the escrow account, returning rent to the maker
+/// 5. Emit the EscrowRefunded event through the self-CPI event instruction
+///
+/// ## Accounts Expected:
+/// 0. [signer] authority - The maker, or the arbiter when there is one
+/// 1. [writable] maker - The maker that created the escrow, receiving the remaining mint_a and the rent
+/// 2. [] mint_a - The deposited mint (the system program for native SOL)
+/// 3. [writable] maker_ata - The maker ATA of the `mint_a` (the maker for native SOL)
+/// 4. [writable] vault - The escrow ATA of the `mint_a` (the native vault PDA for native SOL)
+/// 5. [writable] escrow - The escrow state account
+/// 6. [] system_program - The system program
+/// 7. [] token_program - The token program that owns `mint_a` (SPL Token or Token-2022, the system program for native SOL)
+/// 8. [] event_authority - The event authority PDA signing the event self-CPI
+/// 9. [] program - The escrow program, invoked to emit the event
+/// 10. [signer] payee - The payee consenting to the cancel, only when the maker cancels an escrow with an arbiter
+pub fn cancel(accounts: &[AccountView], _instruction_data: &[u8]) -> ProgramResult {
+
+    // Unpack accounts - Validate expected accounts
+    let [authority, maker, mint_a, maker_ata, vault, escrow, _system_program, token_program, event_authority, program, remaining @..] = accounts else {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    };
+
+    // Check if authority is signer
+    if !authority.is_signer() {
+        return Err(EscrowError::MissingSigner.into());
+    }
+
+    // Check if the token program is supported and owns mint_a
+    check_token_program(mint_a, token_program)?;
+    let native_a = is_native_mint(mint_a);
+
+    // Validate the mint_a accounts: native SOL is returned to the maker directly, otherwise through its ATA
+    if native_a {
+        if maker_ata.address() != maker.address() {
+            return Err(EscrowError::InvalidTokenAccount.into());
+        }
+    } else {
+        // Validate the ATAs are owned by the token program of mint_a
+        if !maker_ata.owned_by(token_program.address()) || !vault.owned_by(token_program.address()) {
+            return Err(EscrowError::InvalidTokenAccount.into());
+        }
+
+        // Validate the maker ATA mint and authority
+        if TokenAccount::from_account_view(maker_ata)?.owner() != maker.address() {
+            return Err(EscrowError::TokenAccountAuthorityMismatch.into());
+        }
+        if TokenAccount::from_account_view(maker_ata)?.mint() != mint_a.address() {
+            return Err(EscrowError::TokenAccountMintMismatch.into());
+        }
+
+        // Validate the vault is the escrow ATA of mint_a, with its mint and authority
+        check_token_vault(vault, escrow, mint_a, token_program)?;
+        if TokenAccount::from_account_view(vault)?.owner() != escrow.address() {
+            return Err(EscrowError::TokenAccountAuthorityMismatch.into());
+        }
+        if TokenAccount::from_account_view(vault)?.mint() != mint_a.address() {
+            return Err(EscrowError::TokenAccountMintMismatch.into());
+        }
+    }
+
+    // Validate escrow PDA (derive expected PDA and verify it matches provided address)
+    let escrow_account = Escrow::from_account_info_mut(escrow)?;
+    let escrow_seeds = [(b"escrow"), maker.address().as_ref(), escrow_account.seed(), escrow_account.bump.as_slice()];
+    let escrow_pda = pinocchio_pubkey::derive_address_const(&escrow_seeds, None, crate::ID.as_array());
+    if escrow_pda != escrow.address().to_bytes() {
+        return Err(EscrowError::InvalidEscrowAddress.into());
+    }
+
+    // Validate the maker and mint_a are the ones stored in the escrow
+    if maker.address() != &escrow_account.maker() {
+        return Err(EscrowError::MakerMismatch.into());
+    }
+    if mint_a.address() != &escrow_account.mint_a() {
+        return Err(EscrowError::MintMismatch.into());
+    }
+    if !escrow_account.is_milestone_escrow() {
+        return Err(EscrowError::InvalidMilestones.into());
+    }
+
+    // Validate the signer may cancel the milestones: the maker alone without an arbiter, otherwise the arbiter or both parties
+    let authorized = match escrow_account.arbiter() {
+        None => authority.address() == maker.address(),
+        Some(arbiter) if authority.address() == &arbiter => true,
+        Some(_) => authority.address() == maker.address() && remaining.first().is_some_and(|payee| {
+            payee.is_signer() && Some(*payee.address()) == escrow_account.taker()
+        }),
+    };
+    if !authorized {
+        return Err(EscrowError::UnauthorizedRelease.into());
+    }
+
+    // Return the whole vault to the maker: native SOL straight out of the program-owned vault, tokens from the vault ATA
+    let seed = escrow_account.seed_value();
+    let amount_a = if native_a { escrow_account.remaining_a() } else { TokenAccount::from_account_view(vault)?.amount() };
+    if native_a {
+        check_native_vault(vault, escrow, escrow_account.vault_bump)?;
+        close_native_vault(vault, maker);
+    } else {
+        let signer_seeds = [Seed::from(b"escrow"), Seed::from(maker.address().as_ref()), Seed::from(escrow_account.seed()), Seed::from(escrow_account.bump.as_ref())];
+        let signers = Signer::from(&signer_seeds);
+        TransferChecked {
+            from: vault,
+            mint: mint_a,
+            to: maker_ata,
+            authority: escrow,
+            amount: amount_a,
+            decimals: Mint::from_account_view(mint_a)?.decimals(),
+            token_program: token_program.address(),
+        }.invoke_signed(core::slice::from_ref(&signers))?;
+
+        // Close Vault Account
+        CloseAccount {
+            account: vault,
+            destination: maker,
+            authority: escrow,
+            token_program: token_program.address(),
+        }.invoke_signed(&[signers])?;
+    }
+
+    // Manually close the escrow account and return rent to the maker
+    let mint_b = escrow_account.mint_b();
+    maker.set_lamports(maker.lamports() + escrow.lamports());
+    escrow.set_lamports(0);
+
+    // Emit the EscrowRefunded event
+    emit(&EscrowRefunded {
+        escrow: *escrow.address(),
+        maker: *maker.address(),
+        mint_a: *mint_a.address(),
+        mint_b,
+        amount_a,
+        seed,
+    }, event_authority, program)?;
+
+    Ok(())
+}
@@ -0,0 +1,144 @@
+use pinocchio::{
+    AccountView, ProgramResult, cpi::{Seed, Signer}, error::ProgramError, sysvars::{Sysvar, clock::Clock},
+};
+use crate::{errors::EscrowError, events::{VestingClaimed, emit}, state::Vesting, token_interface::{CloseAccount, Mint, TokenAccount, TransferChecked, check_token_program, check_token_vault, close_native_vault, is_native_mint, withdraw_native}};
+
+/// # Claim Instruction
+///
+/// This function allows the taker of a vesting escrow to claim the `mint_a` vested since their last claim
+///
+/// ## Business Logic:
+/// 1. Validate all accounts and verify the vesting PDA from the escrow, the beneficiary and the bump stored in the vesting account
+/// 2. Verify the beneficiary and mint_a match the ones stored in the vesting account
+/// 3. Record everything vested by now as claimed, failing if nothing new has vested
+/// 4. Transfer the newly vested mint_a from the vesting vault to the beneficiary (signed by the vesting PDA), the whole vesting vault
+///    balance on the final claim so it can be closed
+/// 5. Emit the VestingClaimed event through the self-CPI event instruction
+/// 6. Once everything is claimed, close the vesting vault and the vesting account, returning rent to the beneficiary
+///
+/// ## Accounts Expected:
+/// 0. [writable, signer] beneficiary - The taker the vesting account was opened for
+/// 1. [] mint_a - The vesting mint (the system program for native SOL)
+/// 2. [writable] beneficiary_ata_a - The beneficiary ATA of the `mint_a` (the beneficiary for native SOL)
+/// 3. [writable] vesting - The vesting account
+/// 4. [writable] vesting_vault - The vesting account ATA of the `mint_a` (the vesting account for native SOL)
+/// 5. [] token_program_a - The token program that owns `mint_a` (SPL Token or Token-2022, the system program for native SOL)
+/// 6. [] event_authority - The event authority PDA signing the event self-CPI
+/// 7. [] program - The escrow program, invoked to emit the event
+pub fn claim(accounts: &[AccountView], _instruction_data: &[u8]) -> ProgramResult {
+
+    // Unpack accounts - Validate expected accounts
+    let [beneficiary, mint_a, beneficiary_ata_a, vesting, vesting_vault, token_program_a, event_authority, program, _remaining @..] = accounts else {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    };
+
+    // Check if beneficiary is signer
+    if !beneficiary.is_signer() {
+        return Err(EscrowError::MissingSigner.into());
+    }
+
+    // Check if the token program is supported and owns mint_a
+    check_token_program(mint_a, token_program_a)?;
+    let native_a = is_native_mint(mint_a);
+
+    // Validate the mint_a accounts: native SOL is held by the vesting account and claimed directly, otherwise through ATAs
+    if native_a {
+        if beneficiary_ata_a.address() != beneficiary.address() || vesting_vault.address() != vesting.address() {
+            return Err(EscrowError::InvalidTokenAccount.into());
+        }
+    } else {
+        // Validate the ATAs are owned by the token program of mint_a
+        if !beneficiary_ata_a.owned_by(token_program_a.address()) || !vesting_vault.owned_by(token_program_a.address()) {
+            return Err(EscrowError::InvalidTokenAccount.into());
+        }
+
+        // Validate the beneficiary ATA mint and authority
+        if TokenAccount::from_account_view(beneficiary_ata_a)?.owner() != beneficiary.address() {
+            return Err(EscrowError::TokenAccountAuthorityMismatch.into());
+        }
+        if TokenAccount::from_account_view(beneficiary_ata_a)?.mint() != mint_a.address() {
+            return Err(EscrowError::TokenAccountMintMismatch.into());
+        }
+
+        // Validate the vesting vault is the vesting account ATA of mint_a, with its mint and authority
+        check_token_vault(vesting_vault, vesting, mint_a, token_program_a)?;
+        if TokenAccount::from_account_view(vesting_vault)?.owner() != vesting.address() {
+            return Err(EscrowError::TokenAccountAuthorityMismatch.into());
+        }
+        if TokenAccount::from_account_view(vesting_vault)?.mint() != mint_a.address() {
+            return Err(EscrowError::TokenAccountMintMismatch.into());
+        }
+    }
+
+    // Validate vesting PDA (derive expected PDA and verify it matches provided address)
+    if !vesting.owned_by(&crate::ID) {
+        return Err(EscrowError::InvalidEscrowData.into());
+    }
+    let vesting_account = Vesting::from_account_info_mut(vesting)?;
+    let escrow = vesting_account.escrow();
+    let vesting_seeds = [(b"vesting"), escrow.as_ref(), beneficiary.address().as_ref(), vesting_account.bump.as_slice()];
+    let vesting_pda = pinocchio_pubkey::derive_address_const(&vesting_seeds, None, crate::ID.as_array());
+    if vesting_pda != vesting.address().to_bytes() {
+        return Err(EscrowError::InvalidEscrowAddress.into());
+    }
+
+    // Validate the beneficiary and mint_a are the ones stored in the vesting account
+    if beneficiary.address() != &vesting_account.beneficiary() {
+        return Err(EscrowError::TakerNotAllowed.into());
+    }
+    if mint_a.address() != &vesting_account.mint_a() {
+        return Err(EscrowError::MintMismatch.into());
+    }
+
+    // Record the claim of everything vested by now
+    let amount_a = vesting_account.claim(Clock::get()?.unix_timestamp);
+    if amount_a == 0 {
+        return Err(EscrowError::NothingToClaim.into());
+    }
+
+    // Transfer the newly vested mint_a to the beneficiary, the final claim emptying the vesting vault so it can be closed
+    let signer_seeds = [Seed::from(b"vesting"), Seed::from(escrow.as_ref()), Seed::from(beneficiary.address().as_ref()), Seed::from(vesting_account.bump.as_ref())];
+    let signers = Signer::from(&signer_seeds);
+    if native_a {
+        withdraw_native(vesting, beneficiary, amount_a)?;
+    } else {
+        TransferChecked {
+            from: vesting_vault,
+            mint: mint_a,
+            to: beneficiary_ata_a,
+            authority: vesting,
+            amount: if vesting_account.is_fully_claimed() { TokenAccount::from_account_view(vesting_vault)?.amount() } else { amount_a },
+            decimals: Mint::from_account_view(mint_a)?.decimals(),
+            token_program: token_program_a.address(),
+        }.invoke_signed(core::slice::from_ref(&signers))?;
+    }
+
+    // Emit the VestingClaimed event
+    emit(&VestingClaimed {
+        vesting: *vesting.address(),
+        escrow,
+        beneficiary: *beneficiary.address(),
+        mint_a: *mint_a.address(),
+        amount_a,
+        claimed_a: vesting_account.claimed_a(),
+        total_a: vesting_account.amount_a(),
+    }, event_authority, program)?;
+
+    // Keep the vesting open until everything is claimed
+    if !vesting_account.is_fully_claimed() {
+        return Ok(());
+    }
+
+    // Close the vesting vault, then manually close the vesting account and return rent to the beneficiary
+    if !native_a {
+        CloseAccount {
+            account: vesting_vault,
+            destination: beneficiary,
+            authority: vesting,
+            token_program: token_program_a.address(),
+        }.invoke_signed(&[signers])?;
+    }
+    close_native_vault(vesting, beneficiary);
+
+    Ok(())
+}
@@ -0,0 +1,152 @@
+use pinocchio::{
+    AccountView, ProgramResult, cpi::{Seed, Signer}, error::ProgramError, sysvars::{Sysvar, clock::Clock},
+
+};
+
+use crate::{errors::EscrowError, events::{EscrowRefunded, emit}, state::Escrow, token_interface::{CloseAccount, Mint, TokenAccount, TransferChecked, check_native_vault, check_token_program, check_token_vault, close_native_vault, is_native_mint, owned_by_token_program}};
+
+/// # Close Expired Instruction
+/// 
+/// This function allows anyone to clean up an escrow once its expiry timestamp has passed
+/// 
+/// ## Business Logic:
+/// 1. Validate all accounts and verify the escrow PDA from the seeds stored in the escrow account
+/// 2. Verify the maker, mint_a and mint_b match the ones stored in the escrow account and that the escrow has expired
+/// 3. Transfer all mint_a from the vault back to the maker (signed by the escrow PDA)
+/// 4. Close the vault ATA and return rent to the maker
+///    (a native SOL vault is closed directly, returning the deposited lamports and its rent to the maker)
+/// 5. Close the escrow account and return rent to the maker
+/// 6. Emit the EscrowRefunded event through the self-CPI event instruction
+/// 
+/// ## Accounts Expected:
+/// 0. [writable] maker - The maker that created the escrow
+/// 1. [] mint_a - The mint that the maker deposited
+/// 2. [] mint_b - The mint that the maker wanted to receive
+/// 3. [writable] maker_ata - The maker ATA of the `mint_a` (the maker for native SOL)
+/// 4. [writable] vault - The ATA owned by the escrow program that is holding the `mint_a` (the native vault PDA for native SOL)
+/// 5. [writable] escrow - The escrow state account
+/// 6. [] system_program - The system program for account creation
+/// 7. [] token_program - The token program that owns `mint_a` (SPL Token or Token-2022, the system program for native SOL)
+/// 8. [] event_authority - The event authority PDA signing the event self-CPI
+/// 9. [] program - The escrow program, invoked to emit the event
+/// 
+pub fn close_expired (accounts: &[AccountView], _instruction_data: &[u8]) -> ProgramResult {
+
+    // Unpack accounts - Validate expected accounts
+    let [maker, mint_a, mint_b, maker_ata, vault, escrow, _system_program, token_program, event_authority, program, _remaining @..] = accounts else {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    };
+
+    // Check if the token program is supported and owns mint_a
+    check_token_program(mint_a, token_program)?;
+    let native_a = is_native_mint(mint_a);
+
+    // Check if mint_b is native SOL or owned by a supported token program
+    if !is_native_mint(mint_b) && !owned_by_token_program(mint_b) {
+        return Err(EscrowError::InvalidMint.into());
+    }
+
+    // Validate the mint_a accounts: native SOL is returned to the maker directly, otherwise through its ATA
+    if native_a {
+        if maker_ata.address() != maker.address() {
+            return Err(EscrowError::InvalidTokenAccount.into());
+        }
+    } else {
+        // Validate the ATAs are owned by the token program of mint_a
+        if !maker_ata.owned_by(token_program.address()) || !vault.owned_by(token_program.address()) {
+            return Err(EscrowError::InvalidTokenAccount.into());
+        }
+
+        // Validate the maker ATA mint and authority
+        if TokenAccount::from_account_view(maker_ata)?.owner() != maker.address() {
+            return Err(EscrowError::TokenAccountAuthorityMismatch.into());
+        }
+        if TokenAccount::from_account_view(maker_ata)?.mint() != mint_a.address() {
+            return Err(EscrowError::TokenAccountMintMismatch.into());
+        }
+
+        // Validate the vault is the escrow ATA of mint_a, with its mint and authority
+        check_token_vault(vault, escrow, mint_a, token_program)?;
+        if TokenAccount::from_account_view(vault)?.owner() != escrow.address() {
+            return Err(EscrowError::TokenAccountAuthorityMismatch.into());
+        }
+        if TokenAccount::from_account_view(vault)?.mint() != mint_a.address() {
+            return Err(EscrowError::TokenAccountMintMismatch.into());
+        }
+    }
+
+    // Validate escrow PDA (derive expected PDA and verify it matches provided address)
+    let escrow_account = Escrow::from_account_info_mut(escrow)?;
+    let escrow_seeds = [(b"escrow"), maker.address().as_ref(), escrow_account.seed(), escrow_account.bump.as_slice()];
+    let escrow_pda = pinocchio_pubkey::derive_address_const(&escrow_seeds, None, crate::ID.as_array());
+    if escrow_pda != escrow.address().to_bytes() {
+        return Err(EscrowError::InvalidEscrowAddress.into());
+    }
+
+    // Validate the mint_b is the same as the one in the escrow
+    if mint_b.address() != &escrow_account.mint_b() {
+        return Err(EscrowError::MintMismatch.into());
+    }
+
+    // Validate the maker and mint_a are the ones stored in the escrow
+    if maker.address() != &escrow_account.maker() {
+        return Err(EscrowError::MakerMismatch.into());
+    }
+    if mint_a.address() != &escrow_account.mint_a() {
+        return Err(EscrowError::MintMismatch.into());
+    }
+
+    // Validate the escrow has expired
+    if !escrow_account.is_expired(Clock::get()?.unix_timestamp) {
+        return Err(EscrowError::EscrowNotExpired.into());
+    }
+
+    // Return mint_a to the maker: native SOL straight out of the program-owned vault, tokens from the vault ATA
+    let seed = escrow_account.seed_value();
+    let amount_a = if native_a {
+        check_native_vault(vault, escrow, escrow_account.vault_bump)?;
+        let amount_a = escrow_account.remaining_a();
+        close_native_vault(vault, maker);
+        amount_a
+    } else {
+        // Transfer amount_a from vault back to maker
+        let signer_seeds = [Seed::from(b"escrow"), Seed::from(maker.address().as_ref()), Seed::from(escrow_account.seed()), Seed::from(escrow_account.bump.as_ref())];
+        let signers = Signer::from(&signer_seeds);
+        let amount_a = TokenAccount::from_account_view(vault)?.amount();
+        TransferChecked {
+            from: vault,
+            mint: mint_a,
+            to: maker_ata,
+            authority: escrow,
+            amount: amount_a,
+            decimals: Mint::from_account_view(mint_a)?.decimals(),
+            token_program: token_program.address(),
+        }.invoke_signed(core::slice::from_ref(&signers))?;
+
+        // Close Vault Account
+        CloseAccount {
+            account: vault,
+            destination: maker,
+            authority: escrow,
+            token_program: token_program.address(),
+        }.invoke_signed(&[signers])?;
+
+        amount_a
+    };
+
+    // Manually close the escrow account and return rent to the maker
+    maker.set_lamports(maker.lamports() + escrow.lamports());
+    escrow.set_lamports(0);
+
+    // Emit the EscrowRefunded event
+    emit(&EscrowRefunded {
+        escrow: *escrow.address(),
+        maker: *maker.address(),
+        mint_a: *mint_a.address(),
+        mint_b: *mint_b.address(),
+        amount_a,
+        seed,
+    }, event_authority, program)?;
+        
+    Ok(())
+}
\ No newline at end of file
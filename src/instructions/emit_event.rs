@@ -0,0 +1,43 @@
+use pinocchio::{
+    AccountView, ProgramResult, error::ProgramError,
+
+};
+
+use crate::{errors::EscrowError, events::{EVENT_IX_TAG, check_event_authority}};
+
+/// # Emit Event Instruction
+/// 
+/// This instruction is only invoked by the program itself (self-CPI) to record an event in the instruction data,
+/// following the Anchor `emit_cpi!` layout. It does nothing beyond checking the event authority signed.
+/// 
+/// ## Business Logic:
+/// 1. Verify the event authority PDA signed the instruction
+/// 2. Verify the data carries the event instruction tag
+/// 
+/// ## Accounts Expected:
+/// 0. [signer] event_authority - The event authority PDA (derived from `b"__event_authority"`)
+/// 
+/// ## Data Parameters:
+/// 0. [u8; 7] tag - The rest of the event instruction tag, whose first byte is the discriminator
+/// 1. [u8; 8] event_discriminator - The event discriminator
+/// 2. [u8; N] event - The borsh-encoded event
+pub fn emit_event(accounts: &[AccountView], instruction_data: &[u8]) -> ProgramResult {
+
+    // Unpack accounts - Validate expected accounts
+    let [event_authority, _remaining @..] = accounts else {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    };
+
+    // Only the program can sign for the event authority
+    if !event_authority.is_signer() {
+        return Err(EscrowError::MissingSigner.into());
+    }
+    check_event_authority(event_authority)?;
+
+    // Validate the event instruction tag
+    if !instruction_data.starts_with(&EVENT_IX_TAG[1..]) {
+        return Err(EscrowError::InvalidInstruction.into());
+    }
+
+    Ok(())
+}
@@ -0,0 +1,114 @@
+use pinocchio::{
+    AccountView, Address, ProgramResult, cpi::{Seed, Signer}, error::ProgramError, sysvars::{Sysvar, rent::Rent}
+
+};
+use pinocchio_system::instructions::CreateAccount;
+
+use crate::{errors::EscrowError, state::Config};
+
+/// The upgradeable BPF loader, owning the program data account that records the upgrade authority
+const BPF_LOADER_UPGRADEABLE_ID: Address = Address::new_from_array(five8_const::decode_32_const("BPFLoaderUpgradeab1e11111111111111111111111"));
+
+/// Length of the program data header: the account type, the deployment slot and the optional upgrade authority
+const PROGRAM_DATA_METADATA_LEN: usize = 45;
+
+/// # Init Config Instruction
+/// 
+/// This function creates the global config account holding the protocol fee, the treasury and the trusted price feed program.
+/// It can only run once, signed by the program upgrade authority, which becomes the config admin.
+/// 
+/// ## Business Logic:
+/// 1. Validate all accounts and instruction data
+/// 2. Verify the signer is the upgrade authority recorded in the program data account
+/// 3. Find the canonical config PDA and verify it matches the provided account
+/// 4. Create the config account and store the admin, treasury, price feed program and fee
+/// 
+/// ## Accounts Expected:
+/// 0. [signer] admin - The program upgrade authority, that creates the config and becomes its admin
+/// 1. [writable] config - The config account that will be created (PDA derived from `b"config"`)
+/// 2. [] system_program - The system program for account creation
+/// 3. [] program_data - The program data account of this program (PDA of the upgradeable loader derived from the program id)
+/// 
+/// ## Data Parameters:
+/// 0. [u8; 2] fee_bps - The protocol fee charged on the `mint_b` leg of TAKE, in basis points, at most 1000 (u16)
+/// 1. [u8; 32] treasury - The owner of the token accounts receiving the protocol fee
+/// 2. [u8; 32] price_feed_program - The program trusted to own the `PriceFeed` accounts of oracle-priced escrows
+pub fn init_config(accounts: &[AccountView], instruction_data: &[u8]) -> ProgramResult {
+
+    // Unpack accounts - Validate expected accounts
+    let [admin, config, _system_program, program_data, _remaining @..] = accounts else {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    };
+
+    // Check if admin is signer
+    if !admin.is_signer() {
+        return Err(EscrowError::MissingSigner.into());
+    }
+
+    // Only the upgrade authority can create the config, so nobody else can front-run the deployment and become admin
+    if upgrade_authority(program_data)? != Some(*admin.address()) {
+        return Err(EscrowError::Unauthorized.into());
+    }
+
+    // Check if the config is not initialized (if it is owned by the system program)
+    if !config.owned_by(&pinocchio_system::ID) {
+        return Err(EscrowError::AccountAlreadyInitialized.into());
+    }
+
+    // Validate data parameters
+    if instruction_data.len() != 66 {
+        return Err(EscrowError::InvalidInstructionData.into());
+    }
+
+    // Unpack data
+    let fee_bps = u16::from_le_bytes(instruction_data[0..2].try_into().unwrap());
+    let treasury: [u8; 32] = instruction_data[2..34].try_into().unwrap();
+    let price_feed_program: [u8; 32] = instruction_data[34..66].try_into().unwrap();
+
+    // Validate the fee
+    if fee_bps > Config::MAX_FEE_BPS {
+        return Err(EscrowError::InvalidFee.into());
+    }
+
+    // Validate config PDA (only the canonical bump is accepted so there is a single config)
+    let (config_pda, config_bump) = Address::find_program_address(&[b"config"], &crate::ID);
+    if &config_pda != config.address() {
+        return Err(EscrowError::InvalidConfig.into());
+    }
+
+    // Create Config account
+    let config_bump = [config_bump];
+    let signer_seeds = [Seed::from(b"config"), Seed::from(config_bump.as_ref())];
+    let signers = Signer::from(&signer_seeds);
+    CreateAccount {
+        from: admin,
+        to: config,
+        lamports: Rent::get()?.minimum_balance_unchecked(Config::LEN),
+        space: Config::LEN as u64,
+        owner: &crate::ID,
+    }.invoke_signed(&[signers])?;
+    let config_account = Config::from_account_info_mut_uninitialized(config)?;
+    config_account.set_inner(admin.address().to_bytes(), treasury, price_feed_program, fee_bps.to_le_bytes(), config_bump);
+
+    Ok(())
+}
+
+/// The upgrade authority of this program, read from its program data account, `None` once the program is immutable
+fn upgrade_authority(program_data: &AccountView) -> Result<Option<Address>, ProgramError> {
+
+    // Validate the program data PDA of this program
+    let (program_data_pda, _) = Address::find_program_address(&[crate::ID.as_ref()], &BPF_LOADER_UPGRADEABLE_ID);
+    if &program_data_pda != program_data.address() || !program_data.owned_by(&BPF_LOADER_UPGRADEABLE_ID) {
+        return Err(EscrowError::InvalidProgramData.into());
+    }
+
+    // Program data layout: [3 (u32 tag), slot (u64), authority (Option<Address>)], then the program
+    let data = program_data.try_borrow()?;
+    if data.len() < PROGRAM_DATA_METADATA_LEN || data[0..4] != [3, 0, 0, 0] {
+        return Err(EscrowError::InvalidProgramData.into());
+    }
+    if data[12] == 0 {
+        return Ok(None);
+    }
+    Ok(Some(Address::new_from_array(data[13..45].try_into().unwrap())))
+}
\ No newline at end of file
@@ -1,93 +1,639 @@
 use pinocchio::{
-    AccountView, ProgramResult, cpi::{Seed, Signer}, error::ProgramError, sysvars::{Sysvar, rent::Rent}
+    AccountView, Address, ProgramResult, cpi::{Seed, Signer}, error::ProgramError, sysvars::{Sysvar, clock::Clock, rent::Rent}
 
 };
 use pinocchio_associated_token_account::instructions::Create;
 use pinocchio_system::instructions::CreateAccount;
-use pinocchio_token::{instructions::TransferChecked, state::Mint};
-
-use crate::state::Escrow;
+use crate::{errors::EscrowError, events::{EscrowCreated, emit}, oracle::Price, state::{Config, Escrow, MAX_APPROVERS, MAX_MILESTONES, MAX_PAYMENT_ALTERNATIVES}, token_interface::{NATIVE_VAULT_SEED, Mint, TransferChecked, check_mint_extensions, check_token_program, is_native_mint, owned_by_token_program}};
 
 /// # Make Instruction
 /// 
 /// This function allows an user to create an escrow
 /// 
 /// ## Business Logic:
-/// 1. Validate all accounts and instruction data
-/// 2. Derive and verify the escrow PDA from the provided seeds
-/// 3. Create the escrow state account
-/// 4. Create the vault ATA with the escrow PDA as authority
-/// 5. Transfer amount_a of mint_a from the maker's ATA to the vault
+/// 1. Validate all accounts and instruction data (mints can belong to SPL Token or Token-2022, or be native SOL)
+/// 2. Derive the escrow PDA and its canonical bump from the maker and the seed, and verify the escrow account matches it
+/// 3. Create the escrow state account recording the maker, both mints and amounts, snapshotting the protocol fee from the config and storing the optional designated taker
+///    and payment alternatives
+/// 4. Create the vault ATA with the escrow PDA as authority and transfer amount_a of mint_a from the maker's ATA to the vault,
+///    or for native SOL create the program-owned vault PDA funded with amount_a lamports
+/// 5. Emit the EscrowCreated event through the self-CPI event instruction
+///
+/// Native SOL is passed as the system program in place of `mint_a` or `mint_b`. For a native `mint_a`, `token_program`
+/// is the system program, `maker_ata` is the maker and `vault` is the PDA `["vault", escrow, bump]`.
 /// 
 /// ## Accounts Expected:
 /// 0. [signer] maker - The user that creates the escrow
-/// 1. [] mint_a - The mint that the maker gives in exchange
-/// 2. [] mint_b - The mint that the maker wants to receive
-/// 3. [writable] maker_ata - The maker ATA of the `mint_a`
-/// 4. [writable] vault - The ATA owned by the escrow program to hold the maker `mint_a` until the exchange completes
+/// 1. [] mint_a - The mint that the maker gives in exchange (the system program for native SOL)
+/// 2. [] mint_b - The mint that the maker wants to receive (the system program for native SOL)
+/// 3. [writable] maker_ata - The maker ATA of the `mint_a` (the maker for native SOL)
+/// 4. [writable] vault - The ATA owned by the escrow program to hold the maker `mint_a` until the exchange completes (the native vault PDA for native SOL)
 /// 5. [writable] escrow - The escrow state account that will be created (PDA derived from seeds and maker pubkey)
 /// 6. [] system_program - The system program for account creation
-/// 7. [] token_program - The token program for token managing
+/// 7. [] token_program - The token program that owns `mint_a` (SPL Token or Token-2022, the system program for native SOL)
 /// 8. [] associated_token_program - The associated token program for ATA creation
+/// 9. [] config - The global config holding the protocol fee snapshotted into the escrow
+/// 10. [] event_authority - The event authority PDA signing the event self-CPI
+/// 11. [] program - The escrow program, invoked to emit the event
+///
+/// The legacy MAKE still accepts the v1 account list followed by the config, accounts 0 to 9: without the event accounts
+/// no event is emitted. The config is always required, so every escrow snapshots the protocol fee in effect.
 /// 
 /// ## Data Parameters:
 /// 0. [u8; 8] amount_a - The amount of mint_a that the maker gives for the exchange (u64)
 /// 1. [u8; 8] amount_b - The amount of mint_b that the maker wants to receive in the exchange (u64)
 /// 2. [u8; 1] seed - The seed to derive the escrow PDA (u8)
-/// 3. [u8; 1] escrow_bump - The bump of the escrow account
+/// 3. [u8; 1] escrow_bump - The bump of the escrow account, which must be the canonical one
+/// 4. [u8; 8] expires_at - Optional unix timestamp after which the escrow can no longer be taken, omitted (or 0) for no expiry (i64)
+/// 5. [u8; 32] taker - Optional designated taker after an expiry, omitted (or all zeros) for a public offer
 pub fn make(accounts: &[AccountView], instruction_data: &[u8]) -> ProgramResult {
-    
+
+    // Validate data parameters: the original 18-byte data has no expiry
+    if instruction_data.len() != 18 && instruction_data.len() != 26 && instruction_data.len() != 58 {
+        return Err(EscrowError::InvalidInstructionData.into());
+    }
+
+    // Drop the bump byte to get the MAKE_V2 data, only keeping the bump to check it is the canonical one,
+    // and zero the expiry the original data leaves out
+    let mut data = [0u8; 57];
+    let len = instruction_data.len().max(26) - 1;
+    data[..17].copy_from_slice(&instruction_data[..17]);
+    data[17..instruction_data.len() - 1].copy_from_slice(&instruction_data[18..]);
+
+    process_make(accounts, &data[..len], 1, Some(instruction_data[17]), Mode::Fixed)
+}
+
+/// # Make V2 Instruction
+///
+/// Same as MAKE, without the bump in the instruction data: the program derives the canonical bump itself
+///
+/// ## Accounts Expected:
+/// Same as MAKE
+///
+/// ## Data Parameters:
+/// 0. [u8; 8] amount_a - The amount of mint_a that the maker gives for the exchange (u64)
+/// 1. [u8; 8] amount_b - The amount of mint_b that the maker wants to receive in the exchange (u64)
+/// 2. [u8; 1] seed - The seed to derive the escrow PDA (u8)
+/// 3. [u8; 8] expires_at - The unix timestamp after which the escrow can no longer be taken, 0 for no expiry (i64)
+/// 4. [u8; 32] taker - Optional designated taker, omitted (or all zeros) for a public offer
+/// 5. [u8; 8] amount_b - For every payment alternative, the amount of its mint asked instead of amount_b (u64), after a taker (all zeros for a public offer)
+///
+/// The mints of the payment alternatives are passed after `program`, one account each in the order of their amounts
+pub fn make_v2(accounts: &[AccountView], instruction_data: &[u8]) -> ProgramResult {
+    process_make(accounts, instruction_data, 1, None, Mode::Fixed)
+}
+
+/// # Make Wide Instruction
+///
+/// Same as MAKE_V2 with a `u64` seed, so a maker can have more than 256 escrows open.
+/// The escrow PDA is derived from `["escrow", maker, seed (u64 LE)]`.
+///
+/// ## Accounts Expected:
+/// Same as MAKE
+///
+/// ## Data Parameters:
+/// 0. [u8; 8] amount_a - The amount of mint_a that the maker gives for the exchange (u64)
+/// 1. [u8; 8] amount_b - The amount of mint_b that the maker wants to receive in the exchange (u64)
+/// 2. [u8; 8] seed - The seed to derive the escrow PDA (u64)
+/// 3. [u8; 8] expires_at - The unix timestamp after which the escrow can no longer be taken, 0 for no expiry (i64)
+/// 4. [u8; 32] taker - Optional designated taker, omitted (or all zeros) for a public offer
+/// 5. [u8; 8] amount_b - Same payment alternatives as MAKE_V2
+pub fn make_wide(accounts: &[AccountView], instruction_data: &[u8]) -> ProgramResult {
+    process_make(accounts, instruction_data, 8, None, Mode::Fixed)
+}
+
+/// # Make Oracle Instruction
+///
+/// Same as MAKE_WIDE with `mint_b` priced from a price feed instead of a fixed amount: takers paying with `mint_b` pay the feed
+/// price moved by a premium (or discount), and `amount_b` becomes the least the maker accepts for the whole of `amount_a`
+///
+/// ## Accounts Expected:
+/// Same as MAKE, followed by:
+/// 12. [] price_feed - The feed pricing one whole mint_a in whole mint_b (a `PriceFeed` account owned by the price feed program of the config,
+///     or a Pyth `PriceUpdateV2` account with the `pyth` feature, the escrow then recording its feed id rather than the account)
+///
+/// The mints of the payment alternatives, which keep a fixed price, follow the price feed
+///
+/// ## Data Parameters:
+/// 0. [u8; 2] premium_bps - The premium over the feed price in basis points, a discount when negative (i16, above -10000)
+/// 1. [u8; 4] max_price_age - The oldest the feed price may be when taken, in seconds (u32)
+/// 2. [u8; 2] max_confidence_bps - The widest the feed confidence interval may be when taken, in basis points of the price (u16)
+/// 3. The MAKE_WIDE data, amount_b being the least accepted for the whole amount_a
+pub fn make_oracle(accounts: &[AccountView], instruction_data: &[u8]) -> ProgramResult {
+
+    // Validate data parameters
+    if instruction_data.len() < 8 {
+        return Err(EscrowError::InvalidInstructionData.into());
+    }
+
+    // Unpack the oracle terms, the discount can't reach the whole price
+    let oracle = OracleTerms {
+        premium_bps: i16::from_le_bytes(instruction_data[0..2].try_into().unwrap()),
+        max_price_age: u32::from_le_bytes(instruction_data[2..6].try_into().unwrap()),
+        max_confidence_bps: u16::from_le_bytes(instruction_data[6..8].try_into().unwrap()),
+    };
+    if oracle.premium_bps <= -10_000 {
+        return Err(EscrowError::InvalidInstructionData.into());
+    }
+
+    process_make(accounts, &instruction_data[8..], 8, None, Mode::Oracle(oracle))
+}
+
+/// # Make Dutch Instruction
+///
+/// Same as MAKE_WIDE with `mint_b` sold by Dutch auction: the amount_b asked for the whole of amount_a starts at `amount_b`
+/// and decays linearly to `end_amount_b` between `auction_start` and `auction_end`, staying there until the escrow expires.
+/// Takers pay the price at the time they take, so the first one to accept it wins.
+///
+/// ## Accounts Expected:
+/// Same as MAKE
+///
+/// ## Data Parameters:
+/// 0. [u8; 8] end_amount_b - The amount of mint_b asked for the whole amount_a once the auction ends, at most amount_b (u64)
+/// 1. [u8; 8] auction_start - The unix timestamp at which the price starts decaying (i64)
+/// 2. [u8; 8] auction_end - The unix timestamp at which the price reaches end_amount_b, after auction_start and in the future (i64)
+/// 3. The MAKE_WIDE data, amount_b being the starting price for the whole amount_a
+pub fn make_dutch(accounts: &[AccountView], instruction_data: &[u8]) -> ProgramResult {
+
+    // Validate data parameters
+    if instruction_data.len() < 24 {
+        return Err(EscrowError::InvalidInstructionData.into());
+    }
+
+    // Unpack the auction terms, they are validated against amount_b once it is unpacked
+    let auction = DutchTerms {
+        end_amount_b: u64::from_le_bytes(instruction_data[0..8].try_into().unwrap()),
+        auction_start: i64::from_le_bytes(instruction_data[8..16].try_into().unwrap()),
+        auction_end: i64::from_le_bytes(instruction_data[16..24].try_into().unwrap()),
+    };
+
+    process_make(accounts, &instruction_data[24..], 8, None, Mode::Dutch(auction))
+}
+
+/// # Make Auction Instruction
+///
+/// Same as MAKE_WIDE with `mint_a` sold by English auction: bidders lock `mint_b` in a bid vault with BID, each bid refunding
+/// the previous one, and once `bidding_end` has passed anyone can SETTLE_AUCTION, sending `amount_a` to the highest bidder
+/// and the bid to the maker. `mint_b` must be a token, and the escrow takes no payment alternatives and no expiry.
+///
+/// ## Accounts Expected:
+/// Same as MAKE
+///
+/// ## Data Parameters:
+/// 0. [u8; 8] min_increment - The least each bid must raise the highest bid by, greater than 0 (u64)
+/// 1. [u8; 8] bidding_end - The unix timestamp after which no more bids are taken and the auction can be settled, in the future (i64)
+/// 2. The MAKE_WIDE data, amount_b being the reserve price for the whole amount_a and expires_at 0
+pub fn make_auction(accounts: &[AccountView], instruction_data: &[u8]) -> ProgramResult {
+
+    // Validate data parameters
+    if instruction_data.len() < 16 {
+        return Err(EscrowError::InvalidInstructionData.into());
+    }
+
+    // Unpack the auction terms, they are validated once the rest of the data is unpacked
+    let auction = EnglishTerms {
+        min_increment: u64::from_le_bytes(instruction_data[0..8].try_into().unwrap()),
+        bidding_end: i64::from_le_bytes(instruction_data[8..16].try_into().unwrap()),
+    };
+
+    process_make(accounts, &instruction_data[16..], 8, None, Mode::English(auction))
+}
+
+/// # Make Vesting Instruction
+///
+/// Same as MAKE_WIDE with `mint_a` delivered over time: the taker pays `mint_b` to the maker right away, while the `mint_a`
+/// moves into a vesting account they CLAIM it from as it vests, nothing for `vesting_cliff` seconds after the take and
+/// then linearly until `vesting_duration` seconds after it. The escrow is taken whole, a taker having one vesting account per escrow
+///
+/// ## Accounts Expected:
+/// Same as MAKE
+///
+/// ## Data Parameters:
+/// 0. [u8; 8] vesting_cliff - The seconds after the take before anything can be claimed, at most vesting_duration (i64)
+/// 1. [u8; 8] vesting_duration - The seconds after the take until everything has vested, greater than 0 (i64)
+/// 2. The MAKE_WIDE data
+pub fn make_vesting(accounts: &[AccountView], instruction_data: &[u8]) -> ProgramResult {
+
+    // Validate data parameters
+    if instruction_data.len() < 16 {
+        return Err(EscrowError::InvalidInstructionData.into());
+    }
+
+    // Unpack the vesting schedule, it is validated once the rest of the data is unpacked
+    let vesting = VestingTerms {
+        vesting_cliff: i64::from_le_bytes(instruction_data[0..8].try_into().unwrap()),
+        vesting_duration: i64::from_le_bytes(instruction_data[8..16].try_into().unwrap()),
+    };
+
+    process_make(accounts, &instruction_data[16..], 8, None, Mode::Vesting(vesting))
+}
+
+/// # Make Arbiter Instruction
+///
+/// Same as MAKE_WIDE for a goods-for-tokens deal: the maker is the buyer depositing `amount_a` and the designated taker is
+/// the seller. Instead of being taken, the vault is released to the seller by the buyer, or back to the buyer by the seller,
+/// with RELEASE, and the `arbiter` settles a dispute either way with DISPUTE_RESOLVE. `mint_b` and `amount_b` only record
+/// what the buyer pays for. The escrow takes no payment alternatives and no expiry, and can't be refunded or updated.
+///
+/// ## Accounts Expected:
+/// Same as MAKE
+///
+/// ## Data Parameters:
+/// 0. [u8; 32] arbiter - The arbiter settling disputes, neither the maker nor the seller
+/// 1. The MAKE_WIDE data, the taker being the seller and expires_at 0
+pub fn make_arbiter(accounts: &[AccountView], instruction_data: &[u8]) -> ProgramResult {
+
+    // Validate data parameters
+    if instruction_data.len() < 32 {
+        return Err(EscrowError::InvalidInstructionData.into());
+    }
+
+    // Unpack the arbiter, it is validated once the rest of the data is unpacked
+    let arbiter = ArbiterTerms {
+        arbiter: instruction_data[0..32].try_into().unwrap(),
+    };
+
+    process_make(accounts, &instruction_data[32..], 8, None, Mode::Arbiter(arbiter))
+}
+
+/// # Make Approval Instruction
+///
+/// Same as MAKE_WIDE for an escrow released by approvals: the vault goes to the designated taker, the beneficiary, once
+/// `threshold` of the registered approvers have each sent APPROVE, and until then an approver can REVOKE_APPROVAL.
+/// `mint_b` and `amount_b` only record what the beneficiary is paid for. The escrow takes no payment alternatives and can't be
+/// taken, refunded or updated, so its required expiry is what lets CLOSE_EXPIRED return the vault to the maker when the approvals never come.
+///
+/// ## Accounts Expected:
+/// Same as MAKE
+///
+/// ## Data Parameters:
+/// 0. [u8; 1] threshold - The number of approvals releasing the vault, between 1 and approver_count (u8)
+/// 1. [u8; 1] approver_count - The number of approvers, at most 5 (u8)
+/// 2. [u8; 32 * approver_count] approvers - The distinct approvers
+/// 3. The MAKE_WIDE data, the taker being the beneficiary and expires_at being required
+pub fn make_approval(accounts: &[AccountView], instruction_data: &[u8]) -> ProgramResult {
+
+    // Validate data parameters
+    let [threshold, approver_count, ..] = *instruction_data else {
+        return Err(EscrowError::InvalidInstructionData.into());
+    };
+    let approver_count = approver_count as usize;
+    if approver_count > MAX_APPROVERS {
+        return Err(EscrowError::InvalidApprovers.into());
+    }
+    let make_data_offset = 2 + 32 * approver_count;
+    if instruction_data.len() < make_data_offset {
+        return Err(EscrowError::InvalidInstructionData.into());
+    }
+
+    // Unpack the approvers, they are validated once the rest of the data is unpacked
+    let mut approval = ApprovalTerms {
+        approvers: [[0u8; 32]; MAX_APPROVERS],
+        approver_count,
+        threshold,
+    };
+    for (approver, data) in approval.approvers.iter_mut().zip(instruction_data[2..make_data_offset].chunks_exact(32)) {
+        *approver = data.try_into().unwrap();
+    }
+
+    process_make(accounts, &instruction_data[make_data_offset..], 8, None, Mode::Approval(approval))
+}
+
+/// # Make Milestone Instruction
+///
+/// Same as MAKE_WIDE for a payment split into ordered milestones: the maker is the payer depositing `amount_a`, the sum of the
+/// milestone amounts, and the designated taker is the payee. Instead of being taken, the milestones are paid to the payee one at
+/// a time with RELEASE_MILESTONE, by the payer or the optional `arbiter`, and CANCEL returns the milestones left to the payer,
+/// signed by the payer alone without an arbiter, otherwise by the arbiter or by the payer with the payee's consent. `mint_b` and `amount_b` only record what the payer pays for. The escrow takes no payment
+/// alternatives and no expiry, and can't be refunded or updated.
+///
+/// ## Accounts Expected:
+/// Same as MAKE
+///
+/// ## Data Parameters:
+/// 0. [u8; 32] arbiter - The arbiter who may also release and cancel, neither the maker nor the payee, all zeros for none
+/// 1. [u8; 1] milestone_count - The number of milestones, between 1 and 8 (u8)
+/// 2. [u8; 8 * milestone_count] amounts - The amount of mint_a each milestone pays, greater than 0 and in release order (u64)
+/// 3. The MAKE_WIDE data, the taker being the payee, amount_a the sum of the milestone amounts and expires_at 0
+pub fn make_milestone(accounts: &[AccountView], instruction_data: &[u8]) -> ProgramResult {
+
+    // Validate data parameters
+    if instruction_data.len() < 33 {
+        return Err(EscrowError::InvalidInstructionData.into());
+    }
+    let milestone_count = instruction_data[32] as usize;
+    if milestone_count == 0 || milestone_count > MAX_MILESTONES {
+        return Err(EscrowError::InvalidMilestones.into());
+    }
+    let make_data_offset = 33 + 8 * milestone_count;
+    if instruction_data.len() < make_data_offset {
+        return Err(EscrowError::InvalidInstructionData.into());
+    }
+
+    // Unpack the milestones, they are validated once the rest of the data is unpacked
+    let mut milestones = MilestoneTerms {
+        arbiter: instruction_data[0..32].try_into().unwrap(),
+        amounts: [0u64; MAX_MILESTONES],
+        milestone_count,
+    };
+    for (amount, data) in milestones.amounts.iter_mut().zip(instruction_data[33..make_data_offset].chunks_exact(8)) {
+        *amount = u64::from_le_bytes(data.try_into().unwrap());
+    }
+
+    process_make(accounts, &instruction_data[make_data_offset..], 8, None, Mode::Milestone(milestones))
+}
+
+/// # Make Hashlock Instruction
+///
+/// Same as MAKE_WIDE for one leg of a cross-chain atomic swap (a hashed timelock contract): the designated taker can only TAKE
+/// the escrow by revealing the preimage of the SHA-256 `hashlock`, which TAKE emits for the counterparty to unlock the other
+/// chain, until `expires_at`, the timeout. The maker can't update the escrow, nor refund it before the timeout.
+///
+/// ## Accounts Expected:
+/// Same as MAKE
+///
+/// ## Data Parameters:
+/// 0. [u8; 32] hashlock - The SHA-256 hash of the 32-byte preimage, not all zeros
+/// 1. The MAKE_WIDE data, the taker being required and expires_at being the timeout, required
+pub fn make_hashlock(accounts: &[AccountView], instruction_data: &[u8]) -> ProgramResult {
+
+    // Validate data parameters
+    if instruction_data.len() < 32 {
+        return Err(EscrowError::InvalidInstructionData.into());
+    }
+
+    // Unpack the hashlock, it is validated once the rest of the data is unpacked
+    let hashlock = HashlockTerms {
+        hashlock: instruction_data[0..32].try_into().unwrap(),
+    };
+
+    process_make(accounts, &instruction_data[32..], 8, None, Mode::Hashlock(hashlock))
+}
+
+/// Pricing terms of an escrow whose `mint_b` is priced from a feed
+#[derive(Clone, Copy)]
+struct OracleTerms {
+    premium_bps: i16,
+    max_price_age: u32,
+    max_confidence_bps: u16,
+}
+
+/// Terms of an escrow whose `mint_b` is sold by Dutch auction
+#[derive(Clone, Copy)]
+struct DutchTerms {
+    end_amount_b: u64,
+    auction_start: i64,
+    auction_end: i64,
+}
+
+/// Terms of an escrow whose `mint_a` is sold by English auction
+#[derive(Clone, Copy)]
+struct EnglishTerms {
+    min_increment: u64,
+    bidding_end: i64,
+}
+
+/// Delivery schedule of an escrow whose `mint_a` vests after the take
+#[derive(Clone, Copy)]
+struct VestingTerms {
+    vesting_cliff: i64,
+    vesting_duration: i64,
+}
+
+/// Arbiter of a goods-for-tokens escrow, settled between the maker and the seller
+#[derive(Clone, Copy)]
+struct ArbiterTerms {
+    arbiter: [u8; 32],
+}
+
+/// Approvers of an escrow released to the designated taker by approvals
+#[derive(Clone, Copy)]
+struct ApprovalTerms {
+    approvers: [[u8; 32]; MAX_APPROVERS],
+    approver_count: usize,
+    threshold: u8,
+}
+
+/// Milestones of an escrow paid to the designated taker one at a time
+#[derive(Clone, Copy)]
+struct MilestoneTerms {
+    arbiter: [u8; 32],
+    amounts: [u64; MAX_MILESTONES],
+    milestone_count: usize,
+}
+
+impl MilestoneTerms {
+    /// The sum of the milestone amounts, `None` on overflow
+    fn total(&self) -> Option<u64> {
+        self.amounts[..self.milestone_count].iter().try_fold(0u64, |total, amount| total.checked_add(*amount))
+    }
+}
+
+/// Hashlock of an escrow only taken with the preimage
+#[derive(Clone, Copy)]
+struct HashlockTerms {
+    hashlock: [u8; 32],
+}
+
+/// How the escrow prices `amount_b` or delivers `mint_a`
+#[derive(Clone, Copy)]
+enum Mode {
+    /// A fixed `amount_b`
+    Fixed,
+    /// From the feed following the MAKE accounts (MAKE_ORACLE)
+    Oracle(OracleTerms),
+    /// By Dutch auction (MAKE_DUTCH)
+    Dutch(DutchTerms),
+    /// By English auction (MAKE_AUCTION)
+    English(EnglishTerms),
+    /// At a fixed `amount_b`, `mint_a` vesting after the take (MAKE_VESTING)
+    Vesting(VestingTerms),
+    /// Released to the designated taker or back to the maker, instead of being taken (MAKE_ARBITER)
+    Arbiter(ArbiterTerms),
+    /// Released to the designated taker by approvals, instead of being taken (MAKE_APPROVAL)
+    Approval(ApprovalTerms),
+    /// Paid to the designated taker milestone by milestone, instead of being taken (MAKE_MILESTONE)
+    Milestone(MilestoneTerms),
+    /// At a fixed `amount_b`, only taken with the preimage of the hashlock (MAKE_HASHLOCK)
+    Hashlock(HashlockTerms),
+}
+
+/// Creates the escrow from the MAKE_V2 data, with a seed of `seed_len` bytes (1, or 8 for MAKE_WIDE),
+/// checking `expected_bump` (given by the legacy MAKE) is the canonical bump and recording the `mode` of the escrow
+fn process_make(accounts: &[AccountView], instruction_data: &[u8], seed_len: usize, expected_bump: Option<u8>, mode: Mode) -> ProgramResult {
+
     // Unpack accounts - Validate expected accounts
-    let [maker, mint_a, mint_b, maker_ata, vault, escrow, system_program, token_program, _associated_token_program, _remaining @..] = accounts else {
+    let [maker, mint_a, mint_b, maker_ata, vault, escrow, system_program, token_program, _associated_token_program, remaining @..] = accounts else {
         return Err(ProgramError::NotEnoughAccountKeys);
     };
 
+    // The legacy MAKE (checking the bump) may leave out the event accounts, never the config charging the protocol fee
+    let (config, events, remaining) = match remaining {
+        [config] if expected_bump.is_some() => (config, None, &[][..]),
+        [config, event_authority, program, remaining @..] => (config, Some((event_authority, program)), remaining),
+        _ => return Err(ProgramError::NotEnoughAccountKeys),
+    };
+
+    // The price feed of an oracle escrow comes first, then the payment alternatives
+    let (price_feed, alternative_mints) = match mode {
+        Mode::Oracle(_) => {
+            let (price_feed, alternative_mints) = remaining.split_first().ok_or(ProgramError::NotEnoughAccountKeys)?;
+            (Some(price_feed), alternative_mints)
+        }
+        _ => (None, remaining),
+    };
+
     // Check if maker is signer
     if !maker.is_signer() {
-        return Err(ProgramError::InvalidAccountOwner);
+        return Err(EscrowError::MissingSigner.into());
+    }
+
+    // Check if the token program is supported and owns mint_a
+    check_token_program(mint_a, token_program)?;
+    let native_a = is_native_mint(mint_a);
+
+    // Check if mint_b is native SOL or owned by a supported token program
+    if !is_native_mint(mint_b) && !owned_by_token_program(mint_b) {
+        return Err(EscrowError::InvalidMint.into());
+    }
+
+    // Check the maker does not swap a mint for itself (native SOL included)
+    if mint_a.address() == mint_b.address() {
+        return Err(EscrowError::SameMint.into());
     }
 
-    // Check if mint accounts are owned by the token program
-    if !mint_a.owned_by(&pinocchio_token::ID) || !mint_b.owned_by(&pinocchio_token::ID) {
-        return Err(ProgramError::InvalidAccountOwner);
+    // Reject mints with extensions that would change the amounts or block the transfers
+    check_mint_extensions(mint_a)?;
+    check_mint_extensions(mint_b)?;
+
+    // Validate the maker ATA (the maker itself pays native SOL)
+    if native_a && maker_ata.address() != maker.address() {
+        return Err(EscrowError::InvalidTokenAccount.into())
+    }
+    if !native_a && !maker_ata.owned_by(token_program.address()) {
+        return Err(EscrowError::InvalidTokenAccount.into())
     }
 
-    // Validate the maker ATA
-    // TBD: is this correct and what else is needed?
-    if !maker_ata.owned_by(&pinocchio_token::ID) {
-        return Err(ProgramError::InvalidAccountOwner)
+    // Validate the price feed layout and owner, its price is only checked when taken
+    if let Some(price_feed) = price_feed {
+        let price_feed_program = Address::new_from_array(Config::from_account_info(config)?.price_feed_program);
+        Price::from_account_view(price_feed, &price_feed_program)?;
     }
 
     // Check if the vault and escrow are not initialized (if are owned by the system program)
     if !escrow.owned_by(&pinocchio_system::ID) || !vault.owned_by(&pinocchio_system::ID) {
-        return Err(ProgramError::InvalidAccountOwner);
+        return Err(EscrowError::AccountAlreadyInitialized.into());
     }
     
-    // Validate data parameters
-    if instruction_data.len() != 18 {
-        return Err(ProgramError::InvalidInstructionData);
+    // Validate data parameters: the payment alternatives follow the taker
+    let expires_at_offset = 16 + seed_len;
+    let taker_offset = expires_at_offset + 8;
+    let alternatives_offset = taker_offset + 32;
+    if instruction_data.len() != taker_offset &&
+        (instruction_data.len() < alternatives_offset || !(instruction_data.len() - alternatives_offset).is_multiple_of(8))
+    {
+        return Err(EscrowError::InvalidInstructionData.into());
+    }
+    let alternative_amounts = instruction_data.get(alternatives_offset..).unwrap_or_default();
+    if alternative_amounts.len() / 8 > MAX_PAYMENT_ALTERNATIVES {
+        return Err(EscrowError::InvalidPaymentAlternatives.into());
+    }
+    if alternative_mints.len() < alternative_amounts.len() / 8 {
+        return Err(ProgramError::NotEnoughAccountKeys);
     }
 
     // Unpack data
     let amount_a = u64::from_le_bytes(instruction_data[0..8].try_into().unwrap());
     let amount_b = u64::from_le_bytes(instruction_data[8..16].try_into().unwrap());
-    let seed = unsafe { *(instruction_data.as_ptr().add(16) as *const u8)}.to_le_bytes();
-    let escrow_bump = unsafe { *(instruction_data.as_ptr().add(17) as *const u8) }.to_le_bytes();
+    let seed = &instruction_data[16..expires_at_offset];
+    let expires_at = i64::from_le_bytes(instruction_data[expires_at_offset..taker_offset].try_into().unwrap());
+    let taker: [u8; 32] = instruction_data.get(taker_offset..taker_offset + 32).map_or([0u8; 32], |taker| taker.try_into().unwrap());
 
     // Validate if amount values are greater than 0
     if amount_a == 0 || amount_b == 0 {
-        return Err(ProgramError::InvalidInstructionData);
+        return Err(EscrowError::ZeroAmount.into());
     }
 
-    // Validate escrow PDA (derive expected PDA and verify it matches provided address)
-    let escrow_seeds = [(b"escrow"), maker.address().as_ref(), seed.as_slice(), escrow_bump.as_slice()];
-    let escrow_pda = pinocchio_pubkey::derive_address_const(&escrow_seeds, None, &crate::ID.as_array());
-    if escrow_pda != escrow.address().to_bytes() {
-        return Err(ProgramError::InvalidAccountOwner);
+    // Validate the expiry is in the future if one is set
+    let now = Clock::get()?.unix_timestamp;
+    if expires_at != 0 && expires_at <= now {
+        return Err(EscrowError::InvalidExpiry.into());
     }
 
+    match mode {
+        // Validate the auction decays from amount_b to a non-zero price over a window that has not ended
+        Mode::Dutch(auction)
+            if auction.end_amount_b == 0 || auction.end_amount_b > amount_b || auction.auction_start >= auction.auction_end || auction.auction_end <= now =>
+        {
+            return Err(EscrowError::InvalidAuctionTerms.into());
+        }
+        // Validate the bids are raised over a window that has not ended, the bid vault holding a single token mint_b
+        Mode::English(auction) => {
+            if auction.min_increment == 0 || auction.bidding_end <= now || expires_at != 0 || !alternative_amounts.is_empty() {
+                return Err(EscrowError::InvalidAuctionTerms.into());
+            }
+            if is_native_mint(mint_b) {
+                return Err(EscrowError::InvalidMint.into());
+            }
+        }
+        // Validate the cliff falls within the vesting
+        Mode::Vesting(vesting) if vesting.vesting_duration <= 0 || vesting.vesting_cliff < 0 || vesting.vesting_cliff > vesting.vesting_duration => {
+            return Err(EscrowError::InvalidVestingTerms.into());
+        }
+        // Validate the seller is designated and the arbiter is a third party, the deal staying open until released
+        Mode::Arbiter(terms)
+            if terms.arbiter == [0u8; 32] || taker == [0u8; 32] || terms.arbiter == maker.address().to_bytes() || terms.arbiter == taker ||
+                expires_at != 0 || !alternative_amounts.is_empty() =>
+        {
+            return Err(EscrowError::InvalidArbiterTerms.into());
+        }
+        // Validate the beneficiary is designated, the threshold can be reached and the expiry is set, the only way back to the maker
+        Mode::Approval(approval)
+            if taker == [0u8; 32] || approval.threshold == 0 || approval.threshold as usize > approval.approver_count || expires_at == 0 ||
+                !alternative_amounts.is_empty() =>
+        {
+            return Err(EscrowError::InvalidApprovers.into());
+        }
+        // Validate the payee is designated, the optional arbiter is a third party and the milestones add up to amount_a
+        Mode::Milestone(milestones)
+            if taker == [0u8; 32] || milestones.arbiter == maker.address().to_bytes() || (milestones.arbiter != [0u8; 32] && milestones.arbiter == taker) ||
+                milestones.total() != Some(amount_a) || expires_at != 0 || !alternative_amounts.is_empty() =>
+        {
+            return Err(EscrowError::InvalidMilestones.into());
+        }
+        // Validate the counterparty is designated and the timeout is set
+        Mode::Hashlock(hashlock) if hashlock.hashlock == [0u8; 32] || taker == [0u8; 32] || expires_at == 0 => {
+            return Err(EscrowError::InvalidHashlock.into());
+        }
+        _ => {}
+    }
+
+    // Validate escrow PDA, deriving its canonical bump so there is a single escrow per maker and seed
+    let (escrow_pda, escrow_bump) = Address::find_program_address(&[b"escrow", maker.address().as_ref(), seed], &crate::ID);
+    if expected_bump.is_some_and(|bump| bump != escrow_bump) {
+        return Err(EscrowError::NonCanonicalBump.into());
+    }
+    if &escrow_pda != escrow.address() {
+        return Err(EscrowError::InvalidEscrowAddress.into());
+    }
+    let escrow_bump = [escrow_bump];
+
+    // Validate the native vault PDA, deriving its canonical bump
+    let vault_bump = if native_a {
+        let (vault_pda, vault_bump) = Address::find_program_address(&[NATIVE_VAULT_SEED, escrow.address().as_ref()], &crate::ID);
+        if &vault_pda != vault.address() {
+            return Err(EscrowError::InvalidVaultAddress.into());
+        }
+        [vault_bump]
+    } else {
+        [0]
+    };
+
+    // Read the protocol fee in effect from the config
+    let fee_bps = Config::from_account_info(config)?.fee_bps;
+
     // Create Escrow account
-    let signer_seeds = [Seed::from(b"escrow"), Seed::from(maker.address().as_ref()), Seed::from(seed.as_ref()), Seed::from(escrow_bump.as_ref())];
+    let signer_seeds = [Seed::from(b"escrow"), Seed::from(maker.address().as_ref()), Seed::from(seed), Seed::from(escrow_bump.as_ref())];
     let signers = Signer::from(&signer_seeds);
     CreateAccount {
         from: maker,
@@ -96,29 +642,110 @@ pub fn make(accounts: &[AccountView], instruction_data: &[u8]) -> ProgramResult
         space: Escrow::LEN as u64,
         owner: &crate::ID,
     }.invoke_signed(&[signers])?;
-    let escrow_account = Escrow::from_account_info_mut(escrow)?;
-    escrow_account.set_inner(mint_b.address().to_bytes(), amount_b.to_le_bytes(), seed, escrow_bump);
-
-    // Create Vault account
-    Create {
-        funding_account: maker,
-        account: vault,
-        wallet: escrow,
-        mint: mint_a,
-        system_program: system_program,
-        token_program: token_program,
-    }.invoke()?;
-
-    // Transfer amount_a to vault
-    let decimals = Mint::from_account_view(mint_a)?.decimals();
-    TransferChecked {
-        from: maker_ata,
-        mint: mint_a,
-        to: vault,
-        authority: maker,
-        amount: amount_a,
-        decimals,
-    }.invoke()?;
+    let escrow_account = Escrow::from_account_info_mut_uninitialized(escrow)?;
+    escrow_account.set_inner(maker.address().to_bytes(), mint_a.address().to_bytes(), mint_b.address().to_bytes(), amount_b.to_le_bytes(), amount_a.to_le_bytes(), expires_at.to_le_bytes(), fee_bps, taker, seed, escrow_bump, vault_bump);
+
+    // Record the payment alternatives, each a native SOL or token mint like mint_b
+    for (alternative_mint, amount) in alternative_mints.iter().zip(alternative_amounts.chunks_exact(8)) {
+        if !is_native_mint(alternative_mint) && !owned_by_token_program(alternative_mint) {
+            return Err(EscrowError::InvalidMint.into());
+        }
+        if alternative_mint.address() == mint_a.address() {
+            return Err(EscrowError::SameMint.into());
+        }
+        check_mint_extensions(alternative_mint)?;
+        let amount = u64::from_le_bytes(amount.try_into().unwrap());
+        if amount == 0 {
+            return Err(EscrowError::ZeroAmount.into());
+        }
+        escrow_account.push_alternative(alternative_mint.address().to_bytes(), amount)?;
+    }
+
+    // Record the price feed and its terms, the auction terms, the vesting schedule, the arbiter, the approvers, the milestones or the hashlock
+    match (mode, price_feed) {
+        (Mode::Oracle(oracle), Some(price_feed)) => {
+            escrow_account.set_oracle(Price::feed_id(price_feed)?.to_bytes(), oracle.premium_bps, oracle.max_price_age, oracle.max_confidence_bps);
+        }
+        (Mode::Dutch(auction), _) => {
+            escrow_account.set_dutch_auction(auction.end_amount_b, auction.auction_start, auction.auction_end);
+        }
+        (Mode::English(auction), _) => {
+            escrow_account.set_english_auction(auction.min_increment, auction.bidding_end);
+        }
+        (Mode::Vesting(vesting), _) => {
+            escrow_account.set_vesting(vesting.vesting_cliff, vesting.vesting_duration);
+        }
+        (Mode::Arbiter(terms), _) => {
+            escrow_account.set_arbiter(terms.arbiter);
+        }
+        (Mode::Approval(approval), _) => {
+            for approver in &approval.approvers[..approval.approver_count] {
+                escrow_account.push_approver(*approver)?;
+            }
+            escrow_account.set_approval_threshold(approval.threshold);
+        }
+        (Mode::Milestone(milestones), _) => {
+            for amount in &milestones.amounts[..milestones.milestone_count] {
+                escrow_account.push_milestone(*amount)?;
+            }
+            escrow_account.set_arbiter(milestones.arbiter);
+        }
+        (Mode::Hashlock(hashlock), _) => {
+            escrow_account.set_hashlock(hashlock.hashlock);
+        }
+        _ => {}
+    }
+
+    // Create the native vault holding amount_a lamports on top of its rent
+    if native_a {
+        let vault_signer_seeds = [Seed::from(NATIVE_VAULT_SEED), Seed::from(escrow.address().as_ref()), Seed::from(vault_bump.as_ref())];
+        let vault_signers = Signer::from(&vault_signer_seeds);
+        let lamports = Rent::get()?.minimum_balance_unchecked(0).checked_add(amount_a).ok_or(EscrowError::MathOverflow)?;
+        CreateAccount {
+            from: maker,
+            to: vault,
+            lamports,
+            space: 0,
+            owner: &crate::ID,
+        }.invoke_signed(&[vault_signers])?;
+    } else {
+        // Create Vault account
+        Create {
+            funding_account: maker,
+            account: vault,
+            wallet: escrow,
+            mint: mint_a,
+            system_program,
+            token_program,
+        }.invoke()?;
+
+        // Transfer amount_a to vault
+        let decimals = Mint::from_account_view(mint_a)?.decimals();
+        TransferChecked {
+            from: maker_ata,
+            mint: mint_a,
+            to: vault,
+            authority: maker,
+            amount: amount_a,
+            decimals,
+            token_program: token_program.address(),
+        }.invoke()?;
+    }
+
+    // Emit the EscrowCreated event, unless the v1 account list left out the event accounts
+    if let Some((event_authority, program)) = events {
+        emit(&EscrowCreated {
+            escrow: *escrow.address(),
+            maker: *maker.address(),
+            mint_a: *mint_a.address(),
+            mint_b: *mint_b.address(),
+            taker: Address::new_from_array(taker),
+            amount_a,
+            amount_b,
+            expires_at,
+            seed: escrow_account.seed_value(),
+        }, event_authority, program)?;
+    }
     
     Ok(())
 }
\ No newline at end of file
@@ -0,0 +1,171 @@
+use pinocchio::{
+    AccountView, Address, ProgramResult, cpi::{Seed, Signer}, error::ProgramError, sysvars::{Sysvar, clock::Clock, rent::Rent},
+};
+use pinocchio_associated_token_account::instructions::Create;
+use pinocchio_system::instructions::CreateAccount;
+use crate::{errors::EscrowError, events::{BasketCreated, emit}, state::{Basket, Config, MAX_BASKET_LEGS}, token_interface::{Mint, TransferChecked, check_mint_extensions, check_token_program, is_native_mint, owned_by_token_program}};
+
+/// # Make Basket Instruction
+///
+/// This function allows an user to create a basket escrow, offering up to `MAX_BASKET_LEGS` mints at once for a single `mint_b` amount
+///
+/// ## Business Logic:
+/// 1. Validate all accounts and instruction data (the legs can belong to SPL Token or Token-2022, `mint_b` can also be native SOL)
+/// 2. Derive the basket PDA and its canonical bump from the maker and the seed, and verify the basket account matches it
+/// 3. Create the basket state account, snapshotting the protocol fee from the config
+/// 4. For every leg, create the vault ATA with the basket PDA as authority, transfer amount_a of mint_a from the maker's ATA to it and record the leg
+/// 5. Emit the BasketCreated event through the self-CPI event instruction
+///
+/// ## Accounts Expected:
+/// 0. [signer] maker - The user that creates the basket
+/// 1. [] mint_b - The mint that the maker wants to receive (the system program for native SOL)
+/// 2. [writable] basket - The basket state account that will be created (PDA derived from `["basket", maker, seed]`)
+/// 3. [] system_program - The system program for account creation
+/// 4. [] associated_token_program - The associated token program for ATA creation
+/// 5. [] config - The global config holding the protocol fee snapshotted into the basket
+/// 6. [] event_authority - The event authority PDA signing the event self-CPI
+/// 7. [] program - The escrow program, invoked to emit the event
+///
+/// Then, for every leg:
+/// 0. [] mint_a - The mint that the maker gives in exchange
+/// 1. [writable] maker_ata - The maker ATA of the `mint_a`
+/// 2. [writable] vault - The ATA owned by the basket to hold the `mint_a`
+/// 3. [] token_program - The token program that owns `mint_a` (SPL Token or Token-2022)
+///
+/// ## Data Parameters:
+/// 0. [u8; 8] amount_b - The amount of mint_b that the maker wants to receive for the whole basket (u64)
+/// 1. [u8; 8] seed - The seed to derive the basket PDA (u64)
+/// 2. [u8; 8] expires_at - The unix timestamp after which the basket can no longer be taken, 0 for no expiry (i64)
+/// 3. [u8; 8] amount_a - The amount of mint_a of every leg, in the order of the leg accounts (u64)
+pub fn make_basket(accounts: &[AccountView], instruction_data: &[u8]) -> ProgramResult {
+
+    // Unpack accounts - Validate expected accounts
+    let [maker, mint_b, basket, system_program, _associated_token_program, config, event_authority, program, leg_accounts @..] = accounts else {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    };
+
+    // Check if maker is signer
+    if !maker.is_signer() {
+        return Err(EscrowError::MissingSigner.into());
+    }
+
+    // Check if mint_b is native SOL or owned by a supported token program
+    if !is_native_mint(mint_b) && !owned_by_token_program(mint_b) {
+        return Err(EscrowError::InvalidMint.into());
+    }
+    check_mint_extensions(mint_b)?;
+
+    // Check if the basket is not initialized (if it is owned by the system program)
+    if !basket.owned_by(&pinocchio_system::ID) {
+        return Err(EscrowError::AccountAlreadyInitialized.into());
+    }
+
+    // Validate data parameters: one amount per leg
+    if instruction_data.len() < 24 || !instruction_data.len().is_multiple_of(8) {
+        return Err(EscrowError::InvalidInstructionData.into());
+    }
+    let leg_count = (instruction_data.len() - 24) / 8;
+    if leg_count == 0 || leg_count > MAX_BASKET_LEGS || leg_accounts.len() < leg_count * 4 {
+        return Err(EscrowError::InvalidBasketLegs.into());
+    }
+
+    // Unpack data
+    let amount_b = u64::from_le_bytes(instruction_data[0..8].try_into().unwrap());
+    let seed: [u8; 8] = instruction_data[8..16].try_into().unwrap();
+    let expires_at = i64::from_le_bytes(instruction_data[16..24].try_into().unwrap());
+
+    // Validate amount_b is greater than 0
+    if amount_b == 0 {
+        return Err(EscrowError::ZeroAmount.into());
+    }
+
+    // Validate the expiry is in the future if one is set
+    if expires_at != 0 && expires_at <= Clock::get()?.unix_timestamp {
+        return Err(EscrowError::InvalidExpiry.into());
+    }
+
+    // Validate basket PDA, deriving its canonical bump
+    let (basket_pda, basket_bump) = Address::find_program_address(&[b"basket", maker.address().as_ref(), seed.as_slice()], &crate::ID);
+    if &basket_pda != basket.address() {
+        return Err(EscrowError::InvalidEscrowAddress.into());
+    }
+    let basket_bump = [basket_bump];
+
+    // Read the protocol fee in effect from the config
+    let fee_bps = Config::from_account_info(config)?.fee_bps;
+
+    // Create Basket account
+    let signer_seeds = [Seed::from(b"basket"), Seed::from(maker.address().as_ref()), Seed::from(seed.as_ref()), Seed::from(basket_bump.as_ref())];
+    let signers = Signer::from(&signer_seeds);
+    CreateAccount {
+        from: maker,
+        to: basket,
+        lamports: Rent::get()?.minimum_balance_unchecked(Basket::LEN),
+        space: Basket::LEN as u64,
+        owner: &crate::ID,
+    }.invoke_signed(&[signers])?;
+    let basket_account = Basket::from_account_info_mut_uninitialized(basket)?;
+    basket_account.set_inner(maker.address().to_bytes(), mint_b.address().to_bytes(), amount_b.to_le_bytes(), expires_at.to_le_bytes(), fee_bps, seed, basket_bump);
+
+    // Fund every leg
+    for (leg, amount_a) in leg_accounts.chunks_exact(4).zip(instruction_data[24..].chunks_exact(8)) {
+        let [mint_a, maker_ata, vault, token_program] = leg else {
+            return Err(ProgramError::NotEnoughAccountKeys);
+        };
+        let amount_a = u64::from_le_bytes(amount_a.try_into().unwrap());
+
+        // Validate the leg: a token mint other than mint_b (native SOL is only supported as mint_b) under its token program
+        if is_native_mint(mint_a) {
+            return Err(EscrowError::InvalidMint.into());
+        }
+        if mint_a.address() == mint_b.address() {
+            return Err(EscrowError::SameMint.into());
+        }
+        check_token_program(mint_a, token_program)?;
+        check_mint_extensions(mint_a)?;
+        if !maker_ata.owned_by(token_program.address()) {
+            return Err(EscrowError::InvalidTokenAccount.into());
+        }
+        if !vault.owned_by(&pinocchio_system::ID) {
+            return Err(EscrowError::AccountAlreadyInitialized.into());
+        }
+        if amount_a == 0 {
+            return Err(EscrowError::ZeroAmount.into());
+        }
+        basket_account.push_leg(mint_a.address().to_bytes(), amount_a)?;
+
+        // Create the leg vault
+        Create {
+            funding_account: maker,
+            account: vault,
+            wallet: basket,
+            mint: mint_a,
+            system_program,
+            token_program,
+        }.invoke()?;
+
+        // Transfer amount_a to the leg vault
+        TransferChecked {
+            from: maker_ata,
+            mint: mint_a,
+            to: vault,
+            authority: maker,
+            amount: amount_a,
+            decimals: Mint::from_account_view(mint_a)?.decimals(),
+            token_program: token_program.address(),
+        }.invoke()?;
+    }
+
+    // Emit the BasketCreated event
+    emit(&BasketCreated {
+        basket: *basket.address(),
+        maker: *maker.address(),
+        mint_b: *mint_b.address(),
+        amount_b,
+        expires_at,
+        leg_count: leg_count as u8,
+        seed: u64::from_le_bytes(seed),
+    }, event_authority, program)?;
+
+    Ok(())
+}
@@ -0,0 +1,150 @@
+use pinocchio::{
+    AccountView, ProgramResult, error::ProgramError, sysvars::{Sysvar, rent::Rent},
+};
+use pinocchio_system::instructions::Transfer;
+
+use crate::{errors::EscrowError, state::{Escrow, EscrowLayout}, token_interface::{NATIVE_MINT, TokenAccount, check_native_vault}};
+
+/// # Migrate Instruction
+/// 
+/// This function allows the maker to upgrade an escrow created with an older account layout to the current one in place,
+/// so open orders keep working across layout changes. An escrow from `V3` on stores its maker and only needs zero-extending,
+/// so anyone can migrate it (a taker hitting `OutdatedEscrowVersion` can migrate it in the same transaction as its take)
+/// 
+/// ## Business Logic:
+/// 1. Detect the layout of the escrow account, doing nothing if it is already current
+/// 2. Decode the old fields and verify the escrow PDA from the maker and the stored seed and bump
+///    (an escrow from `V3` on is checked against its stored maker and only extended with the empty fields it lacks)
+/// 3. Recover mint_a from the vault (and, for the 42-byte layout, the amount left to take)
+/// 4. Top up the rent and reallocate the escrow account to the current size
+/// 5. Write the current layout, recording the maker and mint_a
+/// 
+/// ## Accounts Expected:
+/// 0. [writable, signer] maker - The maker that created the escrow (anyone for an escrow from `V3` on), paying for the extra rent
+/// 1. [writable] escrow - The escrow state account to migrate
+/// 2. [] vault - The vault holding the `mint_a`, read to recover `mint_a` and the amounts the older layouts didn't store
+/// 3. [] system_program - The system program for the rent top up
+pub fn migrate(accounts: &[AccountView], _instruction_data: &[u8]) -> ProgramResult {
+
+    // Unpack accounts - Validate expected accounts
+    let [maker, escrow, vault, _system_program, _remaining @..] = accounts else {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    };
+
+    // Check if maker is signer, since it pays the rent and the layouts before `V3` are upgraded from what the vault holds
+    if !maker.is_signer() {
+        return Err(EscrowError::MissingSigner.into());
+    }
+
+    // Detect the escrow layout
+    if !escrow.owned_by(&crate::ID) {
+        return Err(EscrowError::InvalidEscrowData.into());
+    }
+    let layout = EscrowLayout::of(&escrow.try_borrow()?).ok_or(EscrowError::InvalidEscrowData)?;
+    if layout == EscrowLayout::V13 {
+        return Ok(());
+    }
+
+    // The later layouts only append the payment alternatives, the oracle, auction and vesting terms, the arbiter, the approvers, the milestones, the hashlock and the bid vault payer to `V3`, so the fields stay in place
+    // and the new ones start empty. Nothing is taken from the signer, so the PDA is checked against the stored maker
+    if matches!(layout, EscrowLayout::V3 | EscrowLayout::V4 | EscrowLayout::V5 | EscrowLayout::V6 | EscrowLayout::V7 | EscrowLayout::V8 | EscrowLayout::V9 | EscrowLayout::V10 | EscrowLayout::V11 | EscrowLayout::V12) {
+        {
+            let data = escrow.try_borrow()?;
+            let seed = &data[164..164 + data[172] as usize];
+            let escrow_seeds = [(b"escrow"), &data[2..34], seed, &data[173..174]];
+            let escrow_pda = pinocchio_pubkey::derive_address_const(&escrow_seeds, None, crate::ID.as_array());
+            if escrow_pda != escrow.address().to_bytes() {
+                return Err(EscrowError::InvalidEscrowAddress.into());
+            }
+        }
+        let old_len = escrow.data_len();
+        reallocate(maker, escrow)?;
+        let mut data = escrow.try_borrow_mut()?;
+        data[old_len..].fill(0);
+        data[1] = Escrow::VERSION;
+        return Ok(());
+    }
+
+    // Decode the old fields: the 42-byte layout only has the terms and the PDA seeds, the later ones
+    // share the fields of `Unversioned101`, behind the header for `V1` and behind maker and mint_a for `V2`
+    let (mint_b, amount_b, fields, seed, bump, vault_bump) = {
+        let data = escrow.try_borrow()?;
+        let data = match layout {
+            EscrowLayout::V1 => &data[2..],
+            EscrowLayout::V2 => &data[66..],
+            _ => &data[..],
+        };
+        let mint_b: [u8; 32] = data[0..32].try_into().unwrap();
+        let amount_b: [u8; 8] = data[32..40].try_into().unwrap();
+        let fields = match layout {
+            EscrowLayout::Unversioned42 => None,
+            _ => Some((
+                <[u8; 8]>::try_from(&data[40..48]).unwrap(),
+                <[u8; 8]>::try_from(&data[48..56]).unwrap(),
+                <[u8; 8]>::try_from(&data[56..64]).unwrap(),
+                <[u8; 2]>::try_from(&data[64..66]).unwrap(),
+                <[u8; 32]>::try_from(&data[66..98]).unwrap(),
+            )),
+        };
+        let (seed, bump, vault_bump) = match layout {
+            EscrowLayout::Unversioned42 => ([data[40]], [data[41]], [0]),
+            _ => ([data[98]], [data[99]], [data[100]]),
+        };
+        (mint_b, amount_b, fields, seed, bump, vault_bump)
+    };
+
+    // Validate escrow PDA (derive expected PDA and verify it matches provided address)
+    let escrow_seeds = [(b"escrow"), maker.address().as_ref(), seed.as_slice(), bump.as_slice()];
+    let escrow_pda = pinocchio_pubkey::derive_address_const(&escrow_seeds, None, crate::ID.as_array());
+    if escrow_pda != escrow.address().to_bytes() {
+        return Err(EscrowError::InvalidEscrowAddress.into());
+    }
+
+    // Recover mint_a from the vault, which none of the older layouts stored
+    let (mint_a, vault_amount) = if vault.owned_by(&crate::ID) {
+        check_native_vault(vault, escrow, vault_bump)?;
+        (NATIVE_MINT, 0)
+    } else {
+        let vault_account = TokenAccount::from_account_view(vault)?;
+        if vault_account.owner() != escrow.address() {
+            return Err(EscrowError::TokenAccountAuthorityMismatch.into());
+        }
+        (*vault_account.mint(), vault_account.amount())
+    };
+
+    // The 42-byte layout only supported taking the whole vault at once, so that is what is left to take
+    let (amount_a, remaining_a, expires_at, fee_bps, taker) = match fields {
+        Some(fields) => fields,
+        None => {
+            if mint_a == NATIVE_MINT {
+                return Err(EscrowError::InvalidTokenAccount.into());
+            }
+            let amount_a = vault_amount.to_le_bytes();
+            (amount_a, amount_a, [0; 8], [0; 2], [0; 32])
+        }
+    };
+
+    // Top up the rent for the current size and reallocate
+    reallocate(maker, escrow)?;
+    escrow.try_borrow_mut()?.fill(0);
+
+    // Write the current layout
+    let escrow_account = Escrow::from_account_info_mut_uninitialized(escrow)?;
+    escrow_account.set_inner(maker.address().to_bytes(), mint_a.to_bytes(), mint_b, amount_b, amount_a, expires_at, fee_bps, taker, &seed, bump, vault_bump);
+    escrow_account.remaining_a = remaining_a;
+
+    Ok(())
+}
+
+/// Tops up the escrow rent for the current size, paid by the signer, and reallocates it
+fn reallocate(payer: &AccountView, escrow: &AccountView) -> ProgramResult {
+    let rent = Rent::get()?.minimum_balance_unchecked(Escrow::LEN);
+    if escrow.lamports() < rent {
+        Transfer {
+            from: payer,
+            to: escrow,
+            lamports: rent - escrow.lamports(),
+        }.invoke()?;
+    }
+    escrow.resize(Escrow::LEN)
+}
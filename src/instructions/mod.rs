@@ -1,17 +1,54 @@
 pub mod make;
 pub mod take;
 pub mod refund;
+pub mod close_expired;
+pub mod init_config;
+pub mod update_config;
+pub mod update;
+pub mod emit_event;
+pub mod migrate;
+pub mod make_basket;
+pub mod take_basket;
+pub mod refund_basket;
+pub mod bid;
+pub mod settle_auction;
+pub mod claim;
+pub mod release;
+pub mod approve;
+pub mod revoke_approval;
+pub mod release_milestone;
+pub mod cancel;
 pub use make::*;
 pub use take::*;
 pub use refund::*;
+pub use close_expired::*;
+pub use init_config::*;
+pub use update_config::*;
+pub use update::*;
+pub use emit_event::*;
+pub use migrate::*;
+pub use make_basket::*;
+pub use take_basket::*;
+pub use refund_basket::*;
+pub use bid::*;
+pub use settle_auction::*;
+pub use claim::*;
+pub use release::*;
+pub use approve::*;
+pub use revoke_approval::*;
+pub use release_milestone::*;
+pub use cancel::*;
 
 use shank::ShankInstruction;
 
 use pinocchio::error::ProgramError;
 
+use crate::errors::EscrowError;
+
 // Create an enum for the instructions
 #[derive(ShankInstruction)]
 #[rustfmt::skip]
+#[allow(clippy::upper_case_acronyms, non_camel_case_types)]
 pub enum EscrowInstructions {
     #[account(0, writable, signer, name="maker", desc="The user that creates the escrow")]
     #[account(1, name="mint_a", desc="The mint that the maker gives in exchange")]
@@ -20,21 +57,32 @@ pub enum EscrowInstructions {
     #[account(4, writable, name="vault", desc="The ATA owned by the escrow program to hold the maker `mint_a` until the exchange completes")]
     #[account(5, writable, name="escrow", desc="The escrow state account that will be created (PDA derived from seeds and maker pubkey)")]
     #[account(6, name="system_program", desc="The system program for account creation")]
-    #[account(7, name="token_program", desc="The token program for token managing")]
+    #[account(7, name="token_program", desc="The token program that owns `mint_a` (SPL Token or Token-2022)")]
     #[account(8, name="associated_token_program", desc="The associated token program for ATA creation")]
+    #[account(9, name="config", desc="The global config holding the protocol fee snapshotted into the escrow")]
+    #[account(10, name="event_authority", desc="The event authority PDA signing the event self-CPI")]
+    #[account(11, name="program", desc="The escrow program, invoked to emit the event")]
     MAKE = 0,
     
     #[account(0, writable, signer, name="taker", desc="The taker that takes the escrow")]
     #[account(1, name="maker", desc="The maker that created the escrow")]
     #[account(2, name="mint_a", desc="The mint that the taker will get from the maker")]
-    #[account(3, name="mint_b", desc="The mint that the taker will give to the maker")]
+    #[account(3, name="mint_b", desc="The mint that the taker will give to the maker, `mint_b` or one of the payment alternatives")]
     #[account(4, writable, name="taker_ata_a", desc="The taker ATA of the mint_a")]
     #[account(5, writable, name="taker_ata_b", desc="The taker ATA of the mint_b")]
     #[account(6, writable, name="vault", desc="The ATA owned by the escrow program that is holding the `mint_a`")]
     #[account(7, writable, name="maker_ata_b", desc="The maker ATA of the `mint_b` to receive from the taker")]
     #[account(8, writable, name="escrow", desc="The escrow state account")]
     #[account(9, name="system_program", desc="The system program for account creation")]
-    #[account(10, name="token_program", desc="The token program for token managing")]
+    #[account(10, name="token_program_a", desc="The token program that owns `mint_a` (SPL Token or Token-2022)")]
+    #[account(11, name="token_program_b", desc="The token program that owns `mint_b` (SPL Token or Token-2022)")]
+    #[account(12, name="config", desc="The global config holding the treasury")]
+    #[account(13, writable, name="treasury_ata_b", desc="The treasury ATA of the `mint_b` to receive the protocol fee, unchecked when no fee is charged")]
+    #[account(14, name="event_authority", desc="The event authority PDA signing the event self-CPI")]
+    #[account(15, name="program", desc="The escrow program, invoked to emit the event")]
+    #[account(16, optional, writable, name="price_feed_or_vesting", desc="Only when paying an oracle-priced `mint_b`: the price feed stored in the escrow (a price update of its feed id for Pyth), read-only. Only for a vesting escrow, which has no price feed: the vesting account of the taker to create")]
+    #[account(17, optional, writable, name="vesting_vault", desc="The ATA owned by the vesting account to hold the `mint_a`, only for a vesting escrow")]
+    #[account(18, optional, name="associated_token_program", desc="The associated token program for ATA creation, only for a vesting escrow")]
     TAKE = 1,
     
     #[account(0, writable, signer, name="maker", desc="The maker that created the escrow")]
@@ -44,8 +92,360 @@ pub enum EscrowInstructions {
     #[account(4, writable, name="vault", desc="The ATA owned by the escrow program that is holding the `mint_a`")]
     #[account(5, writable, name="escrow", desc="The escrow state account")]
     #[account(6, name="system_program", desc="The system program for account creation")]
-    #[account(7, name="token_program", desc="The token program for token managing")]
+    #[account(7, name="token_program", desc="The token program that owns `mint_a` (SPL Token or Token-2022)")]
+    #[account(8, name="event_authority", desc="The event authority PDA signing the event self-CPI")]
+    #[account(9, name="program", desc="The escrow program, invoked to emit the event")]
     REFUND = 2,
+
+    #[account(0, writable, name="maker", desc="The maker that created the escrow")]
+    #[account(1, name="mint_a", desc="The mint that the maker deposited")]
+    #[account(2, name="mint_b", desc="The mint that the maker wanted to receive")]
+    #[account(3, writable, name="maker_ata", desc="The maker ATA of the `mint_a`")]
+    #[account(4, writable, name="vault", desc="The ATA owned by the escrow program that is holding the `mint_a`")]
+    #[account(5, writable, name="escrow", desc="The escrow state account")]
+    #[account(6, name="system_program", desc="The system program for account creation")]
+    #[account(7, name="token_program", desc="The token program that owns `mint_a` (SPL Token or Token-2022)")]
+    #[account(8, name="event_authority", desc="The event authority PDA signing the event self-CPI")]
+    #[account(9, name="program", desc="The escrow program, invoked to emit the event")]
+    CLOSE_EXPIRED = 3,
+
+    #[account(0, writable, signer, name="admin", desc="The program upgrade authority, that creates the config and becomes its admin")]
+    #[account(1, writable, name="config", desc="The config account that will be created (PDA derived from `b\"config\"`)")]
+    #[account(2, name="system_program", desc="The system program for account creation")]
+    #[account(3, name="program_data", desc="The program data account of this program (PDA of the upgradeable loader derived from the program id)")]
+    INIT_CONFIG = 4,
+
+    #[account(0, signer, name="admin", desc="The current config admin")]
+    #[account(1, writable, name="config", desc="The config account")]
+    UPDATE_CONFIG = 5,
+
+    #[account(0, writable, signer, name="maker", desc="The maker that created the escrow")]
+    #[account(1, name="mint_a", desc="The mint that the maker deposited")]
+    #[account(2, writable, name="maker_ata", desc="The maker ATA of the `mint_a`")]
+    #[account(3, writable, name="vault", desc="The ATA owned by the escrow program that is holding the `mint_a`")]
+    #[account(4, writable, name="escrow", desc="The escrow state account")]
+    #[account(5, name="system_program", desc="The system program for native SOL transfers")]
+    #[account(6, name="token_program", desc="The token program that owns `mint_a` (SPL Token or Token-2022)")]
+    UPDATE = 6,
+
+    #[account(0, writable, signer, name="maker", desc="The maker that created the escrow (anyone for an escrow from V3 on), paying for the extra rent")]
+    #[account(1, writable, name="escrow", desc="The escrow state account to migrate")]
+    #[account(2, name="vault", desc="The vault holding the `mint_a`, read to recover `mint_a` and the amounts the older layouts didn't store")]
+    #[account(3, name="system_program", desc="The system program for the rent top up")]
+    MIGRATE = 7,
+
+    #[account(0, writable, signer, name="maker", desc="The user that creates the escrow")]
+    #[account(1, name="mint_a", desc="The mint that the maker gives in exchange")]
+    #[account(2, name="mint_b", desc="The mint that the maker wants to receive")]
+    #[account(3, writable, name="maker_ata", desc="The maker ATA of the `mint_a`")]
+    #[account(4, writable, name="vault", desc="The ATA owned by the escrow program to hold the maker `mint_a` until the exchange completes")]
+    #[account(5, writable, name="escrow", desc="The escrow state account that will be created (PDA derived from seeds and maker pubkey)")]
+    #[account(6, name="system_program", desc="The system program for account creation")]
+    #[account(7, name="token_program", desc="The token program that owns `mint_a` (SPL Token or Token-2022)")]
+    #[account(8, name="associated_token_program", desc="The associated token program for ATA creation")]
+    #[account(9, name="config", desc="The global config holding the protocol fee snapshotted into the escrow")]
+    #[account(10, name="event_authority", desc="The event authority PDA signing the event self-CPI")]
+    #[account(11, name="program", desc="The escrow program, invoked to emit the event")]
+    #[account(12, optional, name="alternative_mint", desc="The mint of a payment alternative, repeated for every alternative in the order of their amounts")]
+    MAKE_V2 = 8,
+
+    #[account(0, writable, signer, name="maker", desc="The user that creates the escrow")]
+    #[account(1, name="mint_a", desc="The mint that the maker gives in exchange")]
+    #[account(2, name="mint_b", desc="The mint that the maker wants to receive")]
+    #[account(3, writable, name="maker_ata", desc="The maker ATA of the `mint_a`")]
+    #[account(4, writable, name="vault", desc="The ATA owned by the escrow program to hold the maker `mint_a` until the exchange completes")]
+    #[account(5, writable, name="escrow", desc="The escrow state account that will be created (PDA derived from seeds and maker pubkey)")]
+    #[account(6, name="system_program", desc="The system program for account creation")]
+    #[account(7, name="token_program", desc="The token program that owns `mint_a` (SPL Token or Token-2022)")]
+    #[account(8, name="associated_token_program", desc="The associated token program for ATA creation")]
+    #[account(9, name="config", desc="The global config holding the protocol fee snapshotted into the escrow")]
+    #[account(10, name="event_authority", desc="The event authority PDA signing the event self-CPI")]
+    #[account(11, name="program", desc="The escrow program, invoked to emit the event")]
+    #[account(12, optional, name="alternative_mint", desc="The mint of a payment alternative, repeated for every alternative in the order of their amounts")]
+    MAKE_WIDE = 9,
+
+    #[account(0, writable, signer, name="maker", desc="The user that creates the basket")]
+    #[account(1, name="mint_b", desc="The mint that the maker wants to receive")]
+    #[account(2, writable, name="basket", desc="The basket state account that will be created (PDA derived from `[\"basket\", maker, seed]`)")]
+    #[account(3, name="system_program", desc="The system program for account creation")]
+    #[account(4, name="associated_token_program", desc="The associated token program for ATA creation")]
+    #[account(5, name="config", desc="The global config holding the protocol fee snapshotted into the basket")]
+    #[account(6, name="event_authority", desc="The event authority PDA signing the event self-CPI")]
+    #[account(7, name="program", desc="The escrow program, invoked to emit the event")]
+    #[account(8, name="mint_a", desc="The mint of a leg; mint_a, maker_ata, vault and token_program are repeated for every leg")]
+    #[account(9, writable, name="maker_ata", desc="The maker ATA of the leg `mint_a`")]
+    #[account(10, writable, name="vault", desc="The ATA owned by the basket to hold the leg `mint_a`")]
+    #[account(11, name="token_program", desc="The token program that owns the leg `mint_a` (SPL Token or Token-2022)")]
+    MAKE_BASKET = 10,
+
+    #[account(0, signer, name="taker", desc="The taker that takes the basket")]
+    #[account(1, writable, name="maker", desc="The maker that created the basket")]
+    #[account(2, name="mint_b", desc="The mint that the taker will give to the maker")]
+    #[account(3, writable, name="taker_ata_b", desc="The taker ATA of the `mint_b`")]
+    #[account(4, writable, name="maker_ata_b", desc="The maker ATA of the `mint_b` to receive from the taker")]
+    #[account(5, writable, name="basket", desc="The basket state account")]
+    #[account(6, name="system_program", desc="The system program")]
+    #[account(7, name="token_program_b", desc="The token program that owns `mint_b` (SPL Token or Token-2022)")]
+    #[account(8, name="config", desc="The global config holding the treasury")]
+    #[account(9, writable, name="treasury_ata_b", desc="The treasury ATA of the `mint_b` to receive the protocol fee, unchecked when no fee is charged")]
+    #[account(10, name="event_authority", desc="The event authority PDA signing the event self-CPI")]
+    #[account(11, name="program", desc="The escrow program, invoked to emit the event")]
+    #[account(12, name="mint_a", desc="The mint of a leg; mint_a, taker_ata_a, vault and token_program are repeated for every leg, in the basket order")]
+    #[account(13, writable, name="taker_ata_a", desc="The taker ATA of the leg `mint_a`")]
+    #[account(14, writable, name="vault", desc="The basket ATA holding the leg `mint_a`")]
+    #[account(15, name="token_program", desc="The token program that owns the leg `mint_a` (SPL Token or Token-2022)")]
+    TAKE_BASKET = 11,
+
+    #[account(0, writable, signer, name="maker", desc="The maker that created the basket, only signing before the expiry")]
+    #[account(1, name="mint_b", desc="The mint that the maker was expecting")]
+    #[account(2, writable, name="basket", desc="The basket state account")]
+    #[account(3, name="system_program", desc="The system program")]
+    #[account(4, name="event_authority", desc="The event authority PDA signing the event self-CPI")]
+    #[account(5, name="program", desc="The escrow program, invoked to emit the event")]
+    #[account(6, name="mint_a", desc="The mint of a leg; mint_a, maker_ata, vault and token_program are repeated for every leg, in the basket order")]
+    #[account(7, writable, name="maker_ata", desc="The maker ATA of the leg `mint_a`")]
+    #[account(8, writable, name="vault", desc="The basket ATA holding the leg `mint_a`")]
+    #[account(9, name="token_program", desc="The token program that owns the leg `mint_a` (SPL Token or Token-2022)")]
+    REFUND_BASKET = 12,
+
+    #[account(0, writable, signer, name="maker", desc="The user that creates the escrow")]
+    #[account(1, name="mint_a", desc="The mint that the maker gives in exchange")]
+    #[account(2, name="mint_b", desc="The mint that the maker wants to receive, priced from the price feed")]
+    #[account(3, writable, name="maker_ata", desc="The maker ATA of the `mint_a`")]
+    #[account(4, writable, name="vault", desc="The ATA owned by the escrow program to hold the maker `mint_a` until the exchange completes")]
+    #[account(5, writable, name="escrow", desc="The escrow state account that will be created (PDA derived from seeds and maker pubkey)")]
+    #[account(6, name="system_program", desc="The system program for account creation")]
+    #[account(7, name="token_program", desc="The token program that owns `mint_a` (SPL Token or Token-2022)")]
+    #[account(8, name="associated_token_program", desc="The associated token program for ATA creation")]
+    #[account(9, name="config", desc="The global config holding the protocol fee snapshotted into the escrow")]
+    #[account(10, name="event_authority", desc="The event authority PDA signing the event self-CPI")]
+    #[account(11, name="program", desc="The escrow program, invoked to emit the event")]
+    #[account(12, name="price_feed", desc="The feed pricing one whole `mint_a` in whole `mint_b`, owned by the price feed program of the config (or the Pyth receiver)")]
+    #[account(13, optional, name="alternative_mint", desc="The mint of a payment alternative, repeated for every alternative in the order of their amounts")]
+    MAKE_ORACLE = 13,
+
+    #[account(0, writable, signer, name="maker", desc="The user that creates the escrow")]
+    #[account(1, name="mint_a", desc="The mint that the maker gives in exchange")]
+    #[account(2, name="mint_b", desc="The mint that the maker wants to receive, sold by Dutch auction")]
+    #[account(3, writable, name="maker_ata", desc="The maker ATA of the `mint_a`")]
+    #[account(4, writable, name="vault", desc="The ATA owned by the escrow program to hold the maker `mint_a` until the exchange completes")]
+    #[account(5, writable, name="escrow", desc="The escrow state account that will be created (PDA derived from seeds and maker pubkey)")]
+    #[account(6, name="system_program", desc="The system program for account creation")]
+    #[account(7, name="token_program", desc="The token program that owns `mint_a` (SPL Token or Token-2022)")]
+    #[account(8, name="associated_token_program", desc="The associated token program for ATA creation")]
+    #[account(9, name="config", desc="The global config holding the protocol fee snapshotted into the escrow")]
+    #[account(10, name="event_authority", desc="The event authority PDA signing the event self-CPI")]
+    #[account(11, name="program", desc="The escrow program, invoked to emit the event")]
+    #[account(12, optional, name="alternative_mint", desc="The mint of a payment alternative, repeated for every alternative in the order of their amounts")]
+    MAKE_DUTCH = 14,
+
+    #[account(0, writable, signer, name="maker", desc="The user that creates the escrow")]
+    #[account(1, name="mint_a", desc="The mint that the maker gives in exchange")]
+    #[account(2, name="mint_b", desc="The mint of the bids, a token")]
+    #[account(3, writable, name="maker_ata", desc="The maker ATA of the `mint_a`")]
+    #[account(4, writable, name="vault", desc="The ATA owned by the escrow program to hold the maker `mint_a` until the exchange completes")]
+    #[account(5, writable, name="escrow", desc="The escrow state account that will be created (PDA derived from seeds and maker pubkey)")]
+    #[account(6, name="system_program", desc="The system program for account creation")]
+    #[account(7, name="token_program", desc="The token program that owns `mint_a` (SPL Token or Token-2022)")]
+    #[account(8, name="associated_token_program", desc="The associated token program for ATA creation")]
+    #[account(9, name="config", desc="The global config holding the protocol fee snapshotted into the escrow")]
+    #[account(10, name="event_authority", desc="The event authority PDA signing the event self-CPI")]
+    #[account(11, name="program", desc="The escrow program, invoked to emit the event")]
+    #[account(12, optional, name="alternative_mint", desc="The mint of a payment alternative, repeated for every alternative in the order of their amounts")]
+    MAKE_AUCTION = 15,
+
+    #[account(0, writable, signer, name="bidder", desc="The user placing the bid, paying the bid vault rent on the first bid")]
+    #[account(1, name="maker", desc="The maker that created the escrow")]
+    #[account(2, name="mint_b", desc="The mint of the bids")]
+    #[account(3, writable, name="bidder_ata_b", desc="The bidder ATA of the `mint_b`")]
+    #[account(4, writable, name="bid_vault", desc="The escrow ATA of the `mint_b` holding the highest bid, created on the first bid (must be the escrow ATA)")]
+    #[account(5, writable, name="escrow", desc="The escrow state account recording the highest bid")]
+    #[account(6, name="system_program", desc="The system program for account creation")]
+    #[account(7, name="token_program_b", desc="The token program that owns `mint_b` (SPL Token or Token-2022)")]
+    #[account(8, name="associated_token_program", desc="The associated token program for ATA creation")]
+    #[account(9, name="event_authority", desc="The event authority PDA signing the event self-CPI")]
+    #[account(10, name="program", desc="The escrow program, invoked to emit the event")]
+    #[account(11, optional, writable, name="previous_bidder_ata_b", desc="The ATA of the `mint_b` of the highest bidder, refunded, only when there is a bid already")]
+    BID = 16,
+
+    #[account(0, writable, name="maker", desc="The maker that created the escrow")]
+    #[account(1, writable, name="winner", desc="The highest bidder, receiving mint_a")]
+    #[account(2, name="mint_a", desc="The mint sold by the auction")]
+    #[account(3, name="mint_b", desc="The mint of the bids")]
+    #[account(4, writable, name="winner_ata_a", desc="The winner ATA of the `mint_a`")]
+    #[account(5, writable, name="vault", desc="The escrow ATA of the `mint_a`")]
+    #[account(6, writable, name="bid_vault", desc="The escrow ATA of the `mint_b` holding the highest bid")]
+    #[account(7, writable, name="maker_ata_b", desc="The maker ATA of the `mint_b` to receive the bid")]
+    #[account(8, writable, name="escrow", desc="The escrow state account")]
+    #[account(9, name="system_program", desc="The system program")]
+    #[account(10, name="token_program_a", desc="The token program that owns `mint_a` (SPL Token or Token-2022)")]
+    #[account(11, name="token_program_b", desc="The token program that owns `mint_b` (SPL Token or Token-2022)")]
+    #[account(12, name="config", desc="The global config holding the treasury")]
+    #[account(13, writable, name="treasury_ata_b", desc="The treasury ATA of the `mint_b` to receive the protocol fee, unchecked when no fee is charged")]
+    #[account(14, name="event_authority", desc="The event authority PDA signing the event self-CPI")]
+    #[account(15, name="program", desc="The escrow program, invoked to emit the event")]
+    #[account(16, writable, name="bid_vault_payer", desc="The first bidder, who paid the bid vault rent (the winner for an auction migrated with bids)")]
+    SETTLE_AUCTION = 17,
+
+    #[account(0, writable, signer, name="maker", desc="The user that creates the escrow")]
+    #[account(1, name="mint_a", desc="The mint that the maker gives in exchange, vesting after the take")]
+    #[account(2, name="mint_b", desc="The mint that the maker wants to receive")]
+    #[account(3, writable, name="maker_ata", desc="The maker ATA of the `mint_a`")]
+    #[account(4, writable, name="vault", desc="The ATA owned by the escrow program to hold the maker `mint_a` until the exchange completes")]
+    #[account(5, writable, name="escrow", desc="The escrow state account that will be created (PDA derived from seeds and maker pubkey)")]
+    #[account(6, name="system_program", desc="The system program for account creation")]
+    #[account(7, name="token_program", desc="The token program that owns `mint_a` (SPL Token or Token-2022)")]
+    #[account(8, name="associated_token_program", desc="The associated token program for ATA creation")]
+    #[account(9, name="config", desc="The global config holding the protocol fee snapshotted into the escrow")]
+    #[account(10, name="event_authority", desc="The event authority PDA signing the event self-CPI")]
+    #[account(11, name="program", desc="The escrow program, invoked to emit the event")]
+    #[account(12, optional, name="alternative_mint", desc="The mint of a payment alternative, repeated for every alternative in the order of their amounts")]
+    MAKE_VESTING = 18,
+
+    #[account(0, writable, signer, name="beneficiary", desc="The taker the vesting account was opened for")]
+    #[account(1, name="mint_a", desc="The vesting mint")]
+    #[account(2, writable, name="beneficiary_ata_a", desc="The beneficiary ATA of the `mint_a`")]
+    #[account(3, writable, name="vesting", desc="The vesting account")]
+    #[account(4, writable, name="vesting_vault", desc="The vesting account ATA of the `mint_a`")]
+    #[account(5, name="token_program_a", desc="The token program that owns `mint_a` (SPL Token or Token-2022)")]
+    #[account(6, name="event_authority", desc="The event authority PDA signing the event self-CPI")]
+    #[account(7, name="program", desc="The escrow program, invoked to emit the event")]
+    CLAIM = 19,
+
+    #[account(0, writable, signer, name="maker", desc="The user that creates the escrow, the buyer")]
+    #[account(1, name="mint_a", desc="The mint that the buyer deposits for the seller")]
+    #[account(2, name="mint_b", desc="The mint of what the buyer pays for, only recorded")]
+    #[account(3, writable, name="maker_ata", desc="The maker ATA of the `mint_a`")]
+    #[account(4, writable, name="vault", desc="The ATA owned by the escrow program to hold the maker `mint_a` until the exchange completes")]
+    #[account(5, writable, name="escrow", desc="The escrow state account that will be created (PDA derived from seeds and maker pubkey)")]
+    #[account(6, name="system_program", desc="The system program for account creation")]
+    #[account(7, name="token_program", desc="The token program that owns `mint_a` (SPL Token or Token-2022)")]
+    #[account(8, name="associated_token_program", desc="The associated token program for ATA creation")]
+    #[account(9, name="config", desc="The global config holding the protocol fee snapshotted into the escrow")]
+    #[account(10, name="event_authority", desc="The event authority PDA signing the event self-CPI")]
+    #[account(11, name="program", desc="The escrow program, invoked to emit the event")]
+    #[account(12, optional, name="alternative_mint", desc="The mint of a payment alternative, repeated for every alternative in the order of their amounts")]
+    MAKE_ARBITER = 20,
+
+    #[account(0, signer, name="authority", desc="The maker releasing to the seller, or the seller releasing back to the maker")]
+    #[account(1, writable, name="maker", desc="The maker that created the escrow, receiving the rent")]
+    #[account(2, writable, name="recipient", desc="The party receiving the `mint_a`: the seller, or the maker")]
+    #[account(3, name="mint_a", desc="The deposited mint")]
+    #[account(4, writable, name="recipient_ata_a", desc="The recipient ATA of the `mint_a`")]
+    #[account(5, writable, name="vault", desc="The ATA owned by the escrow program that is holding the `mint_a`")]
+    #[account(6, writable, name="escrow", desc="The escrow state account")]
+    #[account(7, name="system_program", desc="The system program")]
+    #[account(8, name="token_program", desc="The token program that owns `mint_a` (SPL Token or Token-2022)")]
+    #[account(9, name="event_authority", desc="The event authority PDA signing the event self-CPI")]
+    #[account(10, name="program", desc="The escrow program, invoked to emit the event")]
+    RELEASE = 21,
+
+    #[account(0, signer, name="authority", desc="The arbiter stored in the escrow")]
+    #[account(1, writable, name="maker", desc="The maker that created the escrow, receiving the rent")]
+    #[account(2, writable, name="recipient", desc="The party receiving the `mint_a`: the seller, or the maker")]
+    #[account(3, name="mint_a", desc="The deposited mint")]
+    #[account(4, writable, name="recipient_ata_a", desc="The recipient ATA of the `mint_a`")]
+    #[account(5, writable, name="vault", desc="The ATA owned by the escrow program that is holding the `mint_a`")]
+    #[account(6, writable, name="escrow", desc="The escrow state account")]
+    #[account(7, name="system_program", desc="The system program")]
+    #[account(8, name="token_program", desc="The token program that owns `mint_a` (SPL Token or Token-2022)")]
+    #[account(9, name="event_authority", desc="The event authority PDA signing the event self-CPI")]
+    #[account(10, name="program", desc="The escrow program, invoked to emit the event")]
+    DISPUTE_RESOLVE = 22,
+
+    #[account(0, writable, signer, name="maker", desc="The user that creates the escrow")]
+    #[account(1, name="mint_a", desc="The mint that the maker deposits for the beneficiary")]
+    #[account(2, name="mint_b", desc="The mint of what the beneficiary is paid for, only recorded")]
+    #[account(3, writable, name="maker_ata", desc="The maker ATA of the `mint_a`")]
+    #[account(4, writable, name="vault", desc="The ATA owned by the escrow program to hold the maker `mint_a` until the exchange completes")]
+    #[account(5, writable, name="escrow", desc="The escrow state account that will be created (PDA derived from seeds and maker pubkey)")]
+    #[account(6, name="system_program", desc="The system program for account creation")]
+    #[account(7, name="token_program", desc="The token program that owns `mint_a` (SPL Token or Token-2022)")]
+    #[account(8, name="associated_token_program", desc="The associated token program for ATA creation")]
+    #[account(9, name="config", desc="The global config holding the protocol fee snapshotted into the escrow")]
+    #[account(10, name="event_authority", desc="The event authority PDA signing the event self-CPI")]
+    #[account(11, name="program", desc="The escrow program, invoked to emit the event")]
+    #[account(12, optional, name="alternative_mint", desc="The mint of a payment alternative, repeated for every alternative in the order of their amounts")]
+    MAKE_APPROVAL = 23,
+
+    #[account(0, signer, name="approver", desc="A registered approver")]
+    #[account(1, writable, name="maker", desc="The maker that created the escrow, receiving the rent")]
+    #[account(2, writable, name="beneficiary", desc="The designated taker receiving the `mint_a`")]
+    #[account(3, name="mint_a", desc="The deposited mint")]
+    #[account(4, writable, name="beneficiary_ata_a", desc="The beneficiary ATA of the `mint_a`")]
+    #[account(5, writable, name="vault", desc="The ATA owned by the escrow program that is holding the `mint_a`")]
+    #[account(6, writable, name="escrow", desc="The escrow state account recording the approvals")]
+    #[account(7, name="system_program", desc="The system program")]
+    #[account(8, name="token_program", desc="The token program that owns `mint_a` (SPL Token or Token-2022)")]
+    #[account(9, name="event_authority", desc="The event authority PDA signing the event self-CPI")]
+    #[account(10, name="program", desc="The escrow program, invoked to emit the event")]
+    APPROVE = 24,
+
+    #[account(0, signer, name="approver", desc="A registered approver that has approved")]
+    #[account(1, name="maker", desc="The maker that created the escrow")]
+    #[account(2, writable, name="escrow", desc="The escrow state account recording the approvals")]
+    #[account(3, name="event_authority", desc="The event authority PDA signing the event self-CPI")]
+    #[account(4, name="program", desc="The escrow program, invoked to emit the event")]
+    REVOKE_APPROVAL = 25,
+
+    #[account(0, writable, signer, name="maker", desc="The user that creates the escrow")]
+    #[account(1, name="mint_a", desc="The mint that the maker deposits for the payee")]
+    #[account(2, name="mint_b", desc="The mint of what the payee is paid for, only recorded")]
+    #[account(3, writable, name="maker_ata", desc="The maker ATA of the `mint_a`")]
+    #[account(4, writable, name="vault", desc="The ATA owned by the escrow program to hold the maker `mint_a` until the exchange completes")]
+    #[account(5, writable, name="escrow", desc="The escrow state account that will be created (PDA derived from seeds and maker pubkey)")]
+    #[account(6, name="system_program", desc="The system program for account creation")]
+    #[account(7, name="token_program", desc="The token program that owns `mint_a` (SPL Token or Token-2022)")]
+    #[account(8, name="associated_token_program", desc="The associated token program for ATA creation")]
+    #[account(9, name="config", desc="The global config holding the protocol fee snapshotted into the escrow")]
+    #[account(10, name="event_authority", desc="The event authority PDA signing the event self-CPI")]
+    #[account(11, name="program", desc="The escrow program, invoked to emit the event")]
+    #[account(12, optional, name="alternative_mint", desc="The mint of a payment alternative, repeated for every alternative in the order of their amounts")]
+    MAKE_MILESTONE = 26,
+
+    #[account(0, signer, name="authority", desc="The maker or the arbiter")]
+    #[account(1, writable, name="maker", desc="The maker that created the escrow, receiving the rent")]
+    #[account(2, writable, name="payee", desc="The designated taker receiving the milestone")]
+    #[account(3, name="mint_a", desc="The deposited mint")]
+    #[account(4, writable, name="payee_ata_a", desc="The payee ATA of the `mint_a`")]
+    #[account(5, writable, name="vault", desc="The ATA owned by the escrow program that is holding the `mint_a`")]
+    #[account(6, writable, name="escrow", desc="The escrow state account recording the released milestones")]
+    #[account(7, name="system_program", desc="The system program")]
+    #[account(8, name="token_program", desc="The token program that owns `mint_a` (SPL Token or Token-2022)")]
+    #[account(9, name="event_authority", desc="The event authority PDA signing the event self-CPI")]
+    #[account(10, name="program", desc="The escrow program, invoked to emit the event")]
+    RELEASE_MILESTONE = 27,
+
+    #[account(0, signer, name="authority", desc="The maker, or the arbiter when there is one")]
+    #[account(1, writable, name="maker", desc="The maker that created the escrow, receiving the remaining `mint_a` and the rent")]
+    #[account(2, name="mint_a", desc="The deposited mint")]
+    #[account(3, writable, name="maker_ata", desc="The maker ATA of the `mint_a`")]
+    #[account(4, writable, name="vault", desc="The ATA owned by the escrow program that is holding the `mint_a`")]
+    #[account(5, writable, name="escrow", desc="The escrow state account")]
+    #[account(6, name="system_program", desc="The system program")]
+    #[account(7, name="token_program", desc="The token program that owns `mint_a` (SPL Token or Token-2022)")]
+    #[account(8, name="event_authority", desc="The event authority PDA signing the event self-CPI")]
+    #[account(9, name="program", desc="The escrow program, invoked to emit the event")]
+    #[account(10, optional, signer, name="payee", desc="The payee consenting to the cancel, only when the maker cancels an escrow with an arbiter")]
+    CANCEL = 28,
+
+    #[account(0, writable, signer, name="maker", desc="The user that creates the escrow")]
+    #[account(1, name="mint_a", desc="The mint that the maker gives in exchange")]
+    #[account(2, name="mint_b", desc="The mint that the maker wants to receive")]
+    #[account(3, writable, name="maker_ata", desc="The maker ATA of the `mint_a`")]
+    #[account(4, writable, name="vault", desc="The ATA owned by the escrow program to hold the maker `mint_a` until the exchange completes")]
+    #[account(5, writable, name="escrow", desc="The escrow state account that will be created (PDA derived from seeds and maker pubkey)")]
+    #[account(6, name="system_program", desc="The system program for account creation")]
+    #[account(7, name="token_program", desc="The token program that owns `mint_a` (SPL Token or Token-2022)")]
+    #[account(8, name="associated_token_program", desc="The associated token program for ATA creation")]
+    #[account(9, name="config", desc="The global config holding the protocol fee snapshotted into the escrow")]
+    #[account(10, name="event_authority", desc="The event authority PDA signing the event self-CPI")]
+    #[account(11, name="program", desc="The escrow program, invoked to emit the event")]
+    #[account(12, optional, name="alternative_mint", desc="The mint of a payment alternative, repeated for every alternative in the order of their amounts")]
+    MAKE_HASHLOCK = 29,
+
+    #[account(0, signer, name="event_authority", desc="The event authority PDA (derived from `b\"__event_authority\"`), only signed by the program itself")]
+    EMIT_EVENT = 228,
 }
 
 // Implement the TryFrom trait for the enum
@@ -57,7 +457,35 @@ impl TryFrom<&u8> for EscrowInstructions {
             0 => Ok(EscrowInstructions::MAKE),
             1 => Ok(EscrowInstructions::TAKE),
             2 => Ok(EscrowInstructions::REFUND),
-            _ => Err(ProgramError::InvalidInstructionData)
+            3 => Ok(EscrowInstructions::CLOSE_EXPIRED),
+            4 => Ok(EscrowInstructions::INIT_CONFIG),
+            5 => Ok(EscrowInstructions::UPDATE_CONFIG),
+            6 => Ok(EscrowInstructions::UPDATE),
+            7 => Ok(EscrowInstructions::MIGRATE),
+            8 => Ok(EscrowInstructions::MAKE_V2),
+            9 => Ok(EscrowInstructions::MAKE_WIDE),
+            10 => Ok(EscrowInstructions::MAKE_BASKET),
+            11 => Ok(EscrowInstructions::TAKE_BASKET),
+            12 => Ok(EscrowInstructions::REFUND_BASKET),
+            13 => Ok(EscrowInstructions::MAKE_ORACLE),
+            14 => Ok(EscrowInstructions::MAKE_DUTCH),
+            15 => Ok(EscrowInstructions::MAKE_AUCTION),
+            16 => Ok(EscrowInstructions::BID),
+            17 => Ok(EscrowInstructions::SETTLE_AUCTION),
+            18 => Ok(EscrowInstructions::MAKE_VESTING),
+            19 => Ok(EscrowInstructions::CLAIM),
+            20 => Ok(EscrowInstructions::MAKE_ARBITER),
+            21 => Ok(EscrowInstructions::RELEASE),
+            22 => Ok(EscrowInstructions::DISPUTE_RESOLVE),
+            23 => Ok(EscrowInstructions::MAKE_APPROVAL),
+            24 => Ok(EscrowInstructions::APPROVE),
+            25 => Ok(EscrowInstructions::REVOKE_APPROVAL),
+            26 => Ok(EscrowInstructions::MAKE_MILESTONE),
+            27 => Ok(EscrowInstructions::RELEASE_MILESTONE),
+            28 => Ok(EscrowInstructions::CANCEL),
+            29 => Ok(EscrowInstructions::MAKE_HASHLOCK),
+            228 => Ok(EscrowInstructions::EMIT_EVENT),
+            _ => Err(EscrowError::InvalidInstruction.into())
         }
     }
 }
\ No newline at end of file
@@ -1,11 +1,8 @@
 use pinocchio::{
-    AccountView, ProgramResult, cpi::{Seed, Signer}, error::ProgramError,
+    AccountView, ProgramResult, cpi::{Seed, Signer}, error::ProgramError, sysvars::{Sysvar, clock::Clock},
 
 };
-use pinocchio_token::{instructions::{CloseAccount, TransferChecked}, state::{Mint, TokenAccount}};
-use solana_program_log::log;
-
-use crate::state::Escrow;
+use crate::{errors::EscrowError, events::{EscrowRefunded, emit}, state::Escrow, token_interface::{CloseAccount, Mint, TokenAccount, TransferChecked, check_native_vault, check_token_program, check_token_vault, close_native_vault, is_native_mint, owned_by_token_program}};
 
 /// # Refund Instruction
 /// 
@@ -13,98 +10,183 @@ use crate::state::Escrow;
 /// 
 /// ## Business Logic:
 /// 1. Validate all accounts and verify the escrow PDA from the seeds stored in the escrow account
-/// 2. Verify mint_b matches the one stored in the escrow account
+/// 2. Verify the maker, mint_a and mint_b match the ones stored in the escrow account
 /// 3. Transfer all mint_a from the vault back to the maker (signed by the escrow PDA)
 /// 4. Close the vault ATA and return rent to the maker
+///    (a native SOL vault is closed directly, returning the deposited lamports and its rent to the maker)
 /// 5. Close the escrow account and return rent to the maker
+/// 6. Emit the EscrowRefunded event through the self-CPI event instruction
+///
+/// An English auction can only be refunded before its first bid, it is settled with SETTLE_AUCTION afterwards.
+/// An arbitrated escrow can't be refunded, it is settled with RELEASE or DISPUTE_RESOLVE, nor can a milestone escrow, cancelled with CANCEL.
+/// A hashlocked escrow can only be refunded once its timeout (`expires_at`) has passed.
 /// 
 /// ## Accounts Expected:
 /// 0. [signer] maker - The maker that created the escrow
 /// 1. [] mint_a - The mint that the taker will get from the maker
 /// 2. [] mint_b - The mint that the taker will give to the maker
-/// 3. [writable] maker_ata - The maker ATA of the `mint_a`
-/// 4. [writable] vault - The ATA owned by the escrow program that is holding the `mint_a`
+/// 3. [writable] maker_ata - The maker ATA of the `mint_a` (the maker for native SOL)
+/// 4. [writable] vault - The ATA owned by the escrow program that is holding the `mint_a` (the native vault PDA for native SOL)
 /// 5. [writable] escrow - The escrow state account
 /// 6. [] system_program - The system program for account creation
-/// 7. [] token_program - The token program for token managing
+/// 7. [] token_program - The token program that owns `mint_a` (SPL Token or Token-2022, the system program for native SOL)
+/// 8. [] event_authority - The event authority PDA signing the event self-CPI
+/// 9. [] program - The escrow program, invoked to emit the event
+///
+/// The v1 account list, accounts 0 to 7 only, is still accepted and emits no event.
 /// 
 pub fn refund (accounts: &[AccountView], _instruction_data: &[u8]) -> ProgramResult {
 
     // Unpack accounts - Validate expected accounts
-    let [maker, mint_a, mint_b, maker_ata, vault, escrow, _system_program, _token_program, _remaining @..] = accounts else {
+    let [maker, mint_a, mint_b, maker_ata, vault, escrow, _system_program, token_program, remaining @..] = accounts else {
         return Err(ProgramError::NotEnoughAccountKeys);
     };
 
+    // The v1 account list of REFUND stops at the token program
+    let events = match remaining {
+        [] => None,
+        [event_authority, program, ..] => Some((event_authority, program)),
+        _ => return Err(ProgramError::NotEnoughAccountKeys),
+    };
+
     // Check if maker is signer
     if !maker.is_signer() {
-        return Err(ProgramError::InvalidAccountOwner);
+        return Err(EscrowError::MissingSigner.into());
     }
 
-    // Check if mint accounts are owned by the token program
-    if !mint_a.owned_by(&pinocchio_token::ID) || !mint_b.owned_by(&pinocchio_token::ID) {
-        return Err(ProgramError::InvalidAccountOwner);
-    }
+    // Check if the token program is supported and owns mint_a
+    check_token_program(mint_a, token_program)?;
+    let native_a = is_native_mint(mint_a);
 
-    // Validate the ATAs are owned by the token program
-    if !maker_ata.owned_by(&pinocchio_token::ID) || !vault.owned_by(&pinocchio_token::ID) {
-        return Err(ProgramError::InvalidAccountOwner);
+    // Check if mint_b is native SOL or owned by a supported token program
+    if !is_native_mint(mint_b) && !owned_by_token_program(mint_b) {
+        return Err(EscrowError::InvalidMint.into());
     }
 
-    // Validate the maker ATA mint and authority
-    if TokenAccount::from_account_view(maker_ata)?.owner() != maker.address() {
-        return Err(ProgramError::InvalidAccountData);
-    }
-    if TokenAccount::from_account_view(maker_ata)?.mint() != mint_a.address() {
-        return Err(ProgramError::InvalidAccountData);
-    }
+    // Validate the mint_a accounts: native SOL is returned to the maker directly, otherwise through its ATA
+    if native_a {
+        if maker_ata.address() != maker.address() {
+            return Err(EscrowError::InvalidTokenAccount.into());
+        }
+    } else {
+        // Validate the ATAs are owned by the token program of mint_a
+        if !maker_ata.owned_by(token_program.address()) || !vault.owned_by(token_program.address()) {
+            return Err(EscrowError::InvalidTokenAccount.into());
+        }
 
-    // Validate the vault mint and authority
-    if TokenAccount::from_account_view(vault)?.owner() != escrow.address() {
-        return Err(ProgramError::InvalidAccountData);
-    }
-    if TokenAccount::from_account_view(vault)?.mint() != mint_a.address() {
-        return Err(ProgramError::InvalidAccountData);
+        // Validate the maker ATA mint and authority
+        if TokenAccount::from_account_view(maker_ata)?.owner() != maker.address() {
+            return Err(EscrowError::TokenAccountAuthorityMismatch.into());
+        }
+        if TokenAccount::from_account_view(maker_ata)?.mint() != mint_a.address() {
+            return Err(EscrowError::TokenAccountMintMismatch.into());
+        }
+
+        // Validate the vault is the escrow ATA of mint_a, with its mint and authority
+        check_token_vault(vault, escrow, mint_a, token_program)?;
+        if TokenAccount::from_account_view(vault)?.owner() != escrow.address() {
+            return Err(EscrowError::TokenAccountAuthorityMismatch.into());
+        }
+        if TokenAccount::from_account_view(vault)?.mint() != mint_a.address() {
+            return Err(EscrowError::TokenAccountMintMismatch.into());
+        }
     }
 
     // Validate escrow PDA (derive expected PDA and verify it matches provided address)
     let escrow_account = Escrow::from_account_info_mut(escrow)?;
-    let escrow_seeds = [(b"escrow"), maker.address().as_ref(), escrow_account.seed.as_slice(), escrow_account.bump.as_slice()];
-    let escrow_pda = pinocchio_pubkey::derive_address_const(&escrow_seeds, None, &crate::ID.as_array());
+    let escrow_seeds = [(b"escrow"), maker.address().as_ref(), escrow_account.seed(), escrow_account.bump.as_slice()];
+    let escrow_pda = pinocchio_pubkey::derive_address_const(&escrow_seeds, None, crate::ID.as_array());
     if escrow_pda != escrow.address().to_bytes() {
-        return Err(ProgramError::InvalidAccountOwner);
+        return Err(EscrowError::InvalidEscrowAddress.into());
     }
 
     // Validate the mint_b is the same as the one in the escrow
-    if mint_b.address().to_bytes() != escrow_account.mint_b {
-        return Err(ProgramError::InvalidAccountData);
+    if mint_b.address() != &escrow_account.mint_b() {
+        return Err(EscrowError::MintMismatch.into());
+    }
+
+    // Validate the maker and mint_a are the ones stored in the escrow
+    if maker.address() != &escrow_account.maker() {
+        return Err(EscrowError::MakerMismatch.into());
+    }
+    if mint_a.address() != &escrow_account.mint_a() {
+        return Err(EscrowError::MintMismatch.into());
+    }
+
+    // A standing bid locks the auction until it is settled
+    if escrow_account.highest_bidder().is_some() {
+        return Err(EscrowError::AuctionHasBid.into());
     }
 
-    // Transfer amount_a from vault back to maker
-    let signer_seeds = [Seed::from(b"escrow"), Seed::from(maker.address().as_ref()), Seed::from(escrow_account.seed.as_ref()), Seed::from(escrow_account.bump.as_ref())];
-    let signers = Signer::from(&signer_seeds);
-    let amount_a = TokenAccount::from_account_view(vault)?.amount();
-    TransferChecked {
-        from: vault,
-        mint: mint_a,
-        to: maker_ata,
-        authority: escrow,
-        amount: amount_a,
-        decimals: Mint::from_account_view(mint_a)?.decimals(),
-    }.invoke_signed(&[signers.clone()])?;
-
-    log!("debug");
-
-    // Close Vault Account
-    CloseAccount {
-        account: vault,
-        destination: maker,
-        authority: escrow,
-    }.invoke_signed(&[signers])?;
+    // A hashlocked escrow stays takeable with the preimage until its timeout
+    if escrow_account.is_hashlocked() && !escrow_account.is_expired(Clock::get()?.unix_timestamp) {
+        return Err(EscrowError::HashlockedEscrow.into());
+    }
+
+    // The payer of a milestone escrow gets the milestones left back with CANCEL
+    if escrow_account.is_milestone_escrow() {
+        return Err(EscrowError::MilestoneEscrow.into());
+    }
+
+    // The buyer of an arbitrated escrow only gets it back from the seller or the arbiter
+    if escrow_account.is_arbitrated() {
+        return Err(EscrowError::ArbitratedEscrow.into());
+    }
+
+    // The maker of an approval escrow only gets it back once it expires, with CLOSE_EXPIRED
+    if escrow_account.is_approval_escrow() {
+        return Err(EscrowError::ApprovalEscrow.into());
+    }
+
+    // Return mint_a to the maker: native SOL straight out of the program-owned vault, tokens from the vault ATA
+    let seed = escrow_account.seed_value();
+    let amount_a = if native_a {
+        check_native_vault(vault, escrow, escrow_account.vault_bump)?;
+        let amount_a = escrow_account.remaining_a();
+        close_native_vault(vault, maker);
+        amount_a
+    } else {
+        // Transfer amount_a from vault back to maker
+        let signer_seeds = [Seed::from(b"escrow"), Seed::from(maker.address().as_ref()), Seed::from(escrow_account.seed()), Seed::from(escrow_account.bump.as_ref())];
+        let signers = Signer::from(&signer_seeds);
+        let amount_a = TokenAccount::from_account_view(vault)?.amount();
+        TransferChecked {
+            from: vault,
+            mint: mint_a,
+            to: maker_ata,
+            authority: escrow,
+            amount: amount_a,
+            decimals: Mint::from_account_view(mint_a)?.decimals(),
+            token_program: token_program.address(),
+        }.invoke_signed(core::slice::from_ref(&signers))?;
+
+        // Close Vault Account
+        CloseAccount {
+            account: vault,
+            destination: maker,
+            authority: escrow,
+            token_program: token_program.address(),
+        }.invoke_signed(&[signers])?;
+
+        amount_a
+    };
 
     // Manually close the escrow account and return rent to the maker
     // This completes the trade by cleaning up all accounts
     maker.set_lamports(maker.lamports() + escrow.lamports());
     escrow.set_lamports(0);
+
+    // Emit the EscrowRefunded event, unless the v1 account list left out the event accounts
+    if let Some((event_authority, program)) = events {
+        emit(&EscrowRefunded {
+            escrow: *escrow.address(),
+            maker: *maker.address(),
+            mint_a: *mint_a.address(),
+            mint_b: *mint_b.address(),
+            amount_a,
+            seed,
+        }, event_authority, program)?;
+    }
         
     Ok(())
-} 
\ No newline at end of file
+}
\ No newline at end of file
@@ -0,0 +1,134 @@
+use pinocchio::{
+    AccountView, ProgramResult, cpi::{Seed, Signer}, error::ProgramError, sysvars::{Sysvar, clock::Clock},
+};
+use crate::{errors::EscrowError, events::{BasketRefunded, emit}, state::Basket, token_interface::{CloseAccount, Mint, TokenAccount, TransferChecked, check_token_program, check_token_vault}};
+
+/// # Refund Basket Instruction
+///
+/// This function allows the maker to cancel a basket escrow, getting every leg back. Once the basket has expired,
+/// anyone can sweep it back to the maker, so an expired basket never stays locked
+///
+/// ## Business Logic:
+/// 1. Validate all accounts (the maker signature only before the expiry) and verify the basket PDA from the seed stored in the basket account
+/// 2. Verify the maker, mint_b and every leg mint match the ones stored in the basket, in order
+/// 3. For every leg, transfer the vault balance back to the maker and close the vault, returning rent to the maker (signed by the basket PDA)
+/// 4. Close the basket account, return rent to the maker and emit the BasketRefunded event through the self-CPI event instruction
+///
+/// ## Accounts Expected:
+/// 0. [writable, signer] maker - The maker that created the basket, only signing before the expiry
+/// 1. [] mint_b - The mint that the maker was expecting (the system program for native SOL)
+/// 2. [writable] basket - The basket state account
+/// 3. [] system_program - The system program
+/// 4. [] event_authority - The event authority PDA signing the event self-CPI
+/// 5. [] program - The escrow program, invoked to emit the event
+///
+/// Then, for every leg, in the order of the basket:
+/// 0. [] mint_a - The mint of the leg
+/// 1. [writable] maker_ata - The maker ATA of the `mint_a`
+/// 2. [writable] vault - The basket ATA holding the `mint_a`
+/// 3. [] token_program - The token program that owns `mint_a` (SPL Token or Token-2022)
+pub fn refund_basket(accounts: &[AccountView], _instruction_data: &[u8]) -> ProgramResult {
+
+    // Unpack accounts - Validate expected accounts
+    let [maker, mint_b, basket, _system_program, event_authority, program, leg_accounts @..] = accounts else {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    };
+
+    // Check if maker is signer, unless the basket has expired and anyone can return it to the maker
+    let basket_account = Basket::from_account_info_mut(basket)?;
+    if !maker.is_signer() && !basket_account.is_expired(Clock::get()?.unix_timestamp) {
+        return Err(EscrowError::MissingSigner.into());
+    }
+
+    // Validate basket PDA (derive expected PDA and verify it matches provided address)
+    let basket_seeds = [(b"basket").as_slice(), maker.address().as_ref(), basket_account.seed.as_slice(), basket_account.bump.as_slice()];
+    let basket_pda = pinocchio_pubkey::derive_address_const(&basket_seeds, None, crate::ID.as_array());
+    if basket_pda != basket.address().to_bytes() {
+        return Err(EscrowError::InvalidEscrowAddress.into());
+    }
+
+    // Validate the maker and mint_b are the ones stored in the basket
+    if maker.address() != &basket_account.maker() {
+        return Err(EscrowError::MakerMismatch.into());
+    }
+    if mint_b.address() != &basket_account.mint_b() {
+        return Err(EscrowError::MintMismatch.into());
+    }
+
+    // Validate every stored leg is passed
+    if leg_accounts.len() < basket_account.legs().len() * 4 {
+        return Err(EscrowError::InvalidBasketLegs.into());
+    }
+
+    // Return every leg, signed by the basket PDA
+    let signer_seeds = [Seed::from(b"basket"), Seed::from(maker.address().as_ref()), Seed::from(basket_account.seed.as_ref()), Seed::from(basket_account.bump.as_ref())];
+    let signers = Signer::from(&signer_seeds);
+    for (leg, leg_accounts) in basket_account.legs().iter().zip(leg_accounts.chunks_exact(4)) {
+        let [mint_a, maker_ata, vault, token_program] = leg_accounts else {
+            return Err(ProgramError::NotEnoughAccountKeys);
+        };
+
+        // Validate the leg mint and its token program
+        if mint_a.address() != &leg.mint_a() {
+            return Err(EscrowError::InvalidBasketLegs.into());
+        }
+        check_token_program(mint_a, token_program)?;
+
+        // Validate the ATAs are owned by the token program of mint_a
+        if !maker_ata.owned_by(token_program.address()) || !vault.owned_by(token_program.address()) {
+            return Err(EscrowError::InvalidTokenAccount.into());
+        }
+
+        // Validate the maker ATA mint and authority
+        if TokenAccount::from_account_view(maker_ata)?.owner() != maker.address() {
+            return Err(EscrowError::TokenAccountAuthorityMismatch.into());
+        }
+        if TokenAccount::from_account_view(maker_ata)?.mint() != mint_a.address() {
+            return Err(EscrowError::TokenAccountMintMismatch.into());
+        }
+
+        // Validate the vault is the basket ATA of mint_a, with its mint and authority
+        check_token_vault(vault, basket, mint_a, token_program)?;
+        let vault_account = TokenAccount::from_account_view(vault)?;
+        if vault_account.owner() != basket.address() {
+            return Err(EscrowError::TokenAccountAuthorityMismatch.into());
+        }
+        if vault_account.mint() != mint_a.address() {
+            return Err(EscrowError::TokenAccountMintMismatch.into());
+        }
+
+        // Transfer the whole vault back to the maker
+        TransferChecked {
+            from: vault,
+            mint: mint_a,
+            to: maker_ata,
+            authority: basket,
+            amount: vault_account.amount(),
+            decimals: Mint::from_account_view(mint_a)?.decimals(),
+            token_program: token_program.address(),
+        }.invoke_signed(core::slice::from_ref(&signers))?;
+
+        // Close Vault Account
+        CloseAccount {
+            account: vault,
+            destination: maker,
+            authority: basket,
+            token_program: token_program.address(),
+        }.invoke_signed(core::slice::from_ref(&signers))?;
+    }
+    let seed = basket_account.seed_value();
+
+    // Manually close the basket account and return rent to the maker
+    maker.set_lamports(maker.lamports() + basket.lamports());
+    basket.set_lamports(0);
+
+    // Emit the BasketRefunded event
+    emit(&BasketRefunded {
+        basket: *basket.address(),
+        maker: *maker.address(),
+        mint_b: *mint_b.address(),
+        seed,
+    }, event_authority, program)?;
+
+    Ok(())
+}
@@ -0,0 +1,204 @@
+use pinocchio::{
+    AccountView, ProgramResult, cpi::{Seed, Signer}, error::ProgramError,
+};
+use crate::{errors::EscrowError, events::{EscrowReleased, emit}, state::Escrow, token_interface::{CloseAccount, Mint, TokenAccount, TransferChecked, check_native_vault, check_token_program, check_token_vault, close_native_vault, is_native_mint, withdraw_native}};
+
+/// # Release Instruction
+///
+/// This function allows a party of an arbitrated escrow to give the deposit up to the other one: the maker (the buyer)
+/// releases the vault to the seller once the goods are delivered, or the seller releases it back to the maker
+///
+/// ## Business Logic:
+/// 1. Validate all accounts and verify the escrow PDA from the seeds stored in the escrow account
+/// 2. Verify the maker and mint_a match the ones stored in the escrow account, that it is arbitrated and that the signer is the maker or the seller
+/// 3. Transfer the whole vault of mint_a to the other party (signed by the escrow PDA), its full balance for a token vault
+/// 4. Close the vault and the escrow account, returning rent to the maker
+/// 5. Emit the EscrowReleased event through the self-CPI event instruction
+///
+/// ## Accounts Expected:
+/// 0. [signer] authority - The maker releasing to the seller, or the seller releasing back to the maker
+/// 1. [writable] maker - The maker that created the escrow, receiving the rent
+/// 2. [writable] recipient - The party receiving the mint_a: the seller, or the maker
+/// 3. [] mint_a - The deposited mint (the system program for native SOL)
+/// 4. [writable] recipient_ata_a - The recipient ATA of the `mint_a` (the recipient for native SOL)
+/// 5. [writable] vault - The escrow ATA of the `mint_a` (the native vault PDA for native SOL)
+/// 6. [writable] escrow - The escrow state account
+/// 7. [] system_program - The system program
+/// 8. [] token_program - The token program that owns `mint_a` (SPL Token or Token-2022, the system program for native SOL)
+/// 9. [] event_authority - The event authority PDA signing the event self-CPI
+/// 10. [] program - The escrow program, invoked to emit the event
+pub fn release(accounts: &[AccountView], _instruction_data: &[u8]) -> ProgramResult {
+    process_release(accounts, Settlement::Party)
+}
+
+/// # Dispute Resolve Instruction
+///
+/// This function allows the arbiter of an arbitrated escrow to settle a dispute between the maker and the seller,
+/// releasing the vault to the seller or back to the maker
+///
+/// ## Business Logic:
+/// Same as RELEASE, signed by the arbiter stored in the escrow account, which chooses the recipient
+///
+/// ## Accounts Expected:
+/// Same as RELEASE, the authority being the arbiter
+///
+/// ## Data Parameters:
+/// 0. [u8; 1] to_seller - 1 to release the vault to the seller, 0 to refund it to the maker
+pub fn dispute_resolve(accounts: &[AccountView], instruction_data: &[u8]) -> ProgramResult {
+
+    // Validate data parameters
+    let to_seller = match instruction_data {
+        [0] => false,
+        [1] => true,
+        _ => return Err(EscrowError::InvalidInstructionData.into()),
+    };
+
+    process_release(accounts, Settlement::Arbiter { to_seller })
+}
+
+/// Who settles the escrow, and so who receives the vault
+#[derive(Clone, Copy)]
+enum Settlement {
+    /// One of the parties, giving the vault up to the other one (RELEASE)
+    Party,
+    /// The arbiter, to the seller or back to the maker (DISPUTE_RESOLVE)
+    Arbiter { to_seller: bool },
+}
+
+/// Sends the whole vault of an arbitrated escrow to the recipient the `settlement` designates, then closes the escrow
+fn process_release(accounts: &[AccountView], settlement: Settlement) -> ProgramResult {
+
+    // Unpack accounts - Validate expected accounts
+    let [authority, maker, recipient, mint_a, recipient_ata_a, vault, escrow, _system_program, token_program, event_authority, program, _remaining @..] = accounts else {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    };
+
+    // Check if authority is signer
+    if !authority.is_signer() {
+        return Err(EscrowError::MissingSigner.into());
+    }
+
+    // Check if the token program is supported and owns mint_a
+    check_token_program(mint_a, token_program)?;
+    let native_a = is_native_mint(mint_a);
+
+    // Validate the mint_a accounts: the recipient receives native SOL directly, otherwise through its ATA
+    if native_a {
+        if recipient_ata_a.address() != recipient.address() {
+            return Err(EscrowError::InvalidTokenAccount.into());
+        }
+    } else {
+        // Validate the ATAs are owned by the token program of mint_a
+        if !recipient_ata_a.owned_by(token_program.address()) || !vault.owned_by(token_program.address()) {
+            return Err(EscrowError::InvalidTokenAccount.into());
+        }
+
+        // Validate the recipient ATA mint and authority
+        if TokenAccount::from_account_view(recipient_ata_a)?.owner() != recipient.address() {
+            return Err(EscrowError::TokenAccountAuthorityMismatch.into());
+        }
+        if TokenAccount::from_account_view(recipient_ata_a)?.mint() != mint_a.address() {
+            return Err(EscrowError::TokenAccountMintMismatch.into());
+        }
+
+        // Validate the vault is the escrow ATA of mint_a, with its mint and authority
+        check_token_vault(vault, escrow, mint_a, token_program)?;
+        if TokenAccount::from_account_view(vault)?.owner() != escrow.address() {
+            return Err(EscrowError::TokenAccountAuthorityMismatch.into());
+        }
+        if TokenAccount::from_account_view(vault)?.mint() != mint_a.address() {
+            return Err(EscrowError::TokenAccountMintMismatch.into());
+        }
+    }
+
+    // Validate escrow PDA (derive expected PDA and verify it matches provided address)
+    let escrow_account = Escrow::from_account_info_mut(escrow)?;
+    let escrow_seeds = [(b"escrow"), maker.address().as_ref(), escrow_account.seed(), escrow_account.bump.as_slice()];
+    let escrow_pda = pinocchio_pubkey::derive_address_const(&escrow_seeds, None, crate::ID.as_array());
+    if escrow_pda != escrow.address().to_bytes() {
+        return Err(EscrowError::InvalidEscrowAddress.into());
+    }
+
+    // Validate the maker and mint_a are the ones stored in the escrow
+    if maker.address() != &escrow_account.maker() {
+        return Err(EscrowError::MakerMismatch.into());
+    }
+    if mint_a.address() != &escrow_account.mint_a() {
+        return Err(EscrowError::MintMismatch.into());
+    }
+
+    // Validate the native vault belongs to this escrow
+    if native_a {
+        check_native_vault(vault, escrow, escrow_account.vault_bump)?;
+    }
+
+    // A milestone escrow stores its arbiter too, but is paid out with RELEASE_MILESTONE and CANCEL
+    if escrow_account.is_milestone_escrow() {
+        return Err(EscrowError::MilestoneEscrow.into());
+    }
+
+    // Validate the signer may settle the escrow, which decides where the vault goes
+    let (Some(arbiter), Some(seller)) = (escrow_account.arbiter(), escrow_account.taker()) else {
+        return Err(EscrowError::InvalidArbiterTerms.into());
+    };
+    let to_seller = match settlement {
+        Settlement::Party if authority.address() == maker.address() => true,
+        Settlement::Party if authority.address() == &seller => false,
+        Settlement::Arbiter { to_seller } if authority.address() == &arbiter => to_seller,
+        _ => return Err(EscrowError::UnauthorizedRelease.into()),
+    };
+
+    // Validate the recipient is the designated party
+    if to_seller && recipient.address() != &seller {
+        return Err(EscrowError::TakerNotAllowed.into());
+    }
+    if !to_seller && recipient.address() != maker.address() {
+        return Err(EscrowError::MakerMismatch.into());
+    }
+
+    // Transfer the whole vault to the recipient, tokens sent to it on top of the deposit included so it can be closed
+    let signer_seeds = [Seed::from(b"escrow"), Seed::from(maker.address().as_ref()), Seed::from(escrow_account.seed()), Seed::from(escrow_account.bump.as_ref())];
+    let signers = Signer::from(&signer_seeds);
+    let amount_a = if native_a { escrow_account.remaining_a() } else { TokenAccount::from_account_view(vault)?.amount() };
+    if native_a {
+        withdraw_native(vault, recipient_ata_a, amount_a)?;
+        close_native_vault(vault, maker);
+    } else {
+        TransferChecked {
+            from: vault,
+            mint: mint_a,
+            to: recipient_ata_a,
+            authority: escrow,
+            amount: amount_a,
+            decimals: Mint::from_account_view(mint_a)?.decimals(),
+            token_program: token_program.address(),
+        }.invoke_signed(core::slice::from_ref(&signers))?;
+
+        // Close Vault Account
+        CloseAccount {
+            account: vault,
+            destination: maker,
+            authority: escrow,
+            token_program: token_program.address(),
+        }.invoke_signed(&[signers])?;
+    }
+
+    // Manually close the escrow account and return rent to the maker
+    let seed = escrow_account.seed_value();
+    maker.set_lamports(maker.lamports() + escrow.lamports());
+    escrow.set_lamports(0);
+
+    // Emit the EscrowReleased event
+    emit(&EscrowReleased {
+        escrow: *escrow.address(),
+        maker: *maker.address(),
+        seller,
+        released_by: *authority.address(),
+        mint_a: *mint_a.address(),
+        amount_a,
+        to_seller: to_seller as u8,
+        seed,
+    }, event_authority, program)?;
+
+    Ok(())
+}
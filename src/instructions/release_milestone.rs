@@ -0,0 +1,175 @@
+use pinocchio::{
+    AccountView, ProgramResult, cpi::{Seed, Signer}, error::ProgramError,
+};
+use crate::{errors::EscrowError, events::{MilestoneReleased, emit}, state::Escrow, token_interface::{CloseAccount, Mint, TokenAccount, TransferChecked, check_native_vault, check_token_program, check_token_vault, close_native_vault, is_native_mint, withdraw_native}};
+
+/// # Release Milestone Instruction
+///
+/// This function allows the maker (the payer) or the arbiter of a milestone escrow to pay the next milestone to the payee
+///
+/// ## Business Logic:
+/// 1. Validate all accounts and verify the escrow PDA from the seeds stored in the escrow account
+/// 2. Verify the maker, mint_a and the payee match the ones stored in the escrow account, that the signer is the maker or the arbiter
+///    and that the milestone is the next one to release
+/// 3. Transfer the milestone amount of mint_a from the vault to the payee (signed by the escrow PDA) and record its release,
+///    the last milestone being paid the whole token vault balance so it can be closed
+/// 4. Emit the MilestoneReleased event through the self-CPI event instruction
+/// 5. Once every milestone is released, close the vault and the escrow account, returning rent to the maker
+///
+/// ## Accounts Expected:
+/// 0. [signer] authority - The maker or the arbiter
+/// 1. [writable] maker - The maker that created the escrow, receiving the rent
+/// 2. [writable] payee - The designated taker receiving the milestone
+/// 3. [] mint_a - The deposited mint (the system program for native SOL)
+/// 4. [writable] payee_ata_a - The payee ATA of the `mint_a` (the payee for native SOL)
+/// 5. [writable] vault - The escrow ATA of the `mint_a` (the native vault PDA for native SOL)
+/// 6. [writable] escrow - The escrow state account recording the released milestones
+/// 7. [] system_program - The system program
+/// 8. [] token_program - The token program that owns `mint_a` (SPL Token or Token-2022, the system program for native SOL)
+/// 9. [] event_authority - The event authority PDA signing the event self-CPI
+/// 10. [] program - The escrow program, invoked to emit the event
+///
+/// ## Data Parameters:
+/// 0. [u8; 1] milestone - The index of the milestone to release, which must be the next one, so a retried release can't pay twice (u8)
+pub fn release_milestone(accounts: &[AccountView], instruction_data: &[u8]) -> ProgramResult {
+
+    // Unpack accounts - Validate expected accounts
+    let [authority, maker, payee, mint_a, payee_ata_a, vault, escrow, _system_program, token_program, event_authority, program, _remaining @..] = accounts else {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    };
+
+    // Validate data parameters
+    let [milestone] = *instruction_data else {
+        return Err(EscrowError::InvalidInstructionData.into());
+    };
+
+    // Check if authority is signer
+    if !authority.is_signer() {
+        return Err(EscrowError::MissingSigner.into());
+    }
+
+    // Check if the token program is supported and owns mint_a
+    check_token_program(mint_a, token_program)?;
+    let native_a = is_native_mint(mint_a);
+
+    // Validate the mint_a accounts: the payee receives native SOL directly, otherwise through its ATA
+    if native_a {
+        if payee_ata_a.address() != payee.address() {
+            return Err(EscrowError::InvalidTokenAccount.into());
+        }
+    } else {
+        // Validate the ATAs are owned by the token program of mint_a
+        if !payee_ata_a.owned_by(token_program.address()) || !vault.owned_by(token_program.address()) {
+            return Err(EscrowError::InvalidTokenAccount.into());
+        }
+
+        // Validate the payee ATA mint and authority
+        if TokenAccount::from_account_view(payee_ata_a)?.owner() != payee.address() {
+            return Err(EscrowError::TokenAccountAuthorityMismatch.into());
+        }
+        if TokenAccount::from_account_view(payee_ata_a)?.mint() != mint_a.address() {
+            return Err(EscrowError::TokenAccountMintMismatch.into());
+        }
+
+        // Validate the vault is the escrow ATA of mint_a, with its mint and authority
+        check_token_vault(vault, escrow, mint_a, token_program)?;
+        if TokenAccount::from_account_view(vault)?.owner() != escrow.address() {
+            return Err(EscrowError::TokenAccountAuthorityMismatch.into());
+        }
+        if TokenAccount::from_account_view(vault)?.mint() != mint_a.address() {
+            return Err(EscrowError::TokenAccountMintMismatch.into());
+        }
+    }
+
+    // Validate escrow PDA (derive expected PDA and verify it matches provided address)
+    let escrow_account = Escrow::from_account_info_mut(escrow)?;
+    let escrow_seeds = [(b"escrow"), maker.address().as_ref(), escrow_account.seed(), escrow_account.bump.as_slice()];
+    let escrow_pda = pinocchio_pubkey::derive_address_const(&escrow_seeds, None, crate::ID.as_array());
+    if escrow_pda != escrow.address().to_bytes() {
+        return Err(EscrowError::InvalidEscrowAddress.into());
+    }
+
+    // Validate the maker, mint_a and the payee are the ones stored in the escrow
+    if maker.address() != &escrow_account.maker() {
+        return Err(EscrowError::MakerMismatch.into());
+    }
+    if mint_a.address() != &escrow_account.mint_a() {
+        return Err(EscrowError::MintMismatch.into());
+    }
+    if !escrow_account.is_milestone_escrow() {
+        return Err(EscrowError::InvalidMilestones.into());
+    }
+    if Some(*payee.address()) != escrow_account.taker() {
+        return Err(EscrowError::TakerNotAllowed.into());
+    }
+
+    // Validate the native vault belongs to this escrow
+    if native_a {
+        check_native_vault(vault, escrow, escrow_account.vault_bump)?;
+    }
+
+    // Validate the signer may release milestones
+    if authority.address() != maker.address() && Some(*authority.address()) != escrow_account.arbiter() {
+        return Err(EscrowError::UnauthorizedRelease.into());
+    }
+
+    // Record the release of the next milestone
+    if milestone != escrow_account.released_milestones() {
+        return Err(EscrowError::MilestoneOutOfOrder.into());
+    }
+    let amount_a = escrow_account.release_milestone()?;
+    let remaining_a = escrow_account.remaining_a();
+    let fully_released = escrow_account.is_fully_released();
+
+    // Transfer the milestone to the payee, the last one emptying the vault (tokens sent to it on top of the deposit included) to close it
+    let signer_seeds = [Seed::from(b"escrow"), Seed::from(maker.address().as_ref()), Seed::from(escrow_account.seed()), Seed::from(escrow_account.bump.as_ref())];
+    let signers = Signer::from(&signer_seeds);
+    if native_a {
+        withdraw_native(vault, payee_ata_a, amount_a)?;
+        if fully_released {
+            close_native_vault(vault, maker);
+        }
+    } else {
+        TransferChecked {
+            from: vault,
+            mint: mint_a,
+            to: payee_ata_a,
+            authority: escrow,
+            amount: if fully_released { TokenAccount::from_account_view(vault)?.amount() } else { amount_a },
+            decimals: Mint::from_account_view(mint_a)?.decimals(),
+            token_program: token_program.address(),
+        }.invoke_signed(core::slice::from_ref(&signers))?;
+
+        // Close Vault Account
+        if fully_released {
+            CloseAccount {
+                account: vault,
+                destination: maker,
+                authority: escrow,
+                token_program: token_program.address(),
+            }.invoke_signed(&[signers])?;
+        }
+    }
+
+    // Emit the MilestoneReleased event
+    let seed = escrow_account.seed_value();
+    emit(&MilestoneReleased {
+        escrow: *escrow.address(),
+        maker: *maker.address(),
+        payee: *payee.address(),
+        released_by: *authority.address(),
+        mint_a: *mint_a.address(),
+        milestone,
+        amount_a,
+        remaining_a,
+        seed,
+    }, event_authority, program)?;
+
+    // Manually close the escrow account and return rent to the maker once every milestone is settled
+    if fully_released {
+        maker.set_lamports(maker.lamports() + escrow.lamports());
+        escrow.set_lamports(0);
+    }
+
+    Ok(())
+}
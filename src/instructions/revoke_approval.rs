@@ -0,0 +1,63 @@
+use pinocchio::{
+    AccountView, ProgramResult, error::ProgramError,
+};
+use crate::{errors::EscrowError, events::{ApprovalRecorded, emit}, state::Escrow};
+
+/// # Revoke Approval Instruction
+///
+/// This function allows an approver of an approval escrow to withdraw their approval before the threshold is reached
+/// (reaching it releases the escrow, which closes it)
+///
+/// ## Business Logic:
+/// 1. Verify the escrow PDA from the seeds stored in the escrow account and the maker
+/// 2. Verify the signer is a registered approver that has approved
+/// 3. Withdraw the approval and emit the ApprovalRecorded event through the self-CPI event instruction
+///
+/// ## Accounts Expected:
+/// 0. [signer] approver - A registered approver that has approved
+/// 1. [] maker - The maker that created the escrow
+/// 2. [writable] escrow - The escrow state account recording the approvals
+/// 3. [] event_authority - The event authority PDA signing the event self-CPI
+/// 4. [] program - The escrow program, invoked to emit the event
+pub fn revoke_approval(accounts: &[AccountView], _instruction_data: &[u8]) -> ProgramResult {
+
+    // Unpack accounts - Validate expected accounts
+    let [approver, maker, escrow, event_authority, program, _remaining @..] = accounts else {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    };
+
+    // Check if approver is signer
+    if !approver.is_signer() {
+        return Err(EscrowError::MissingSigner.into());
+    }
+
+    // Validate escrow PDA (derive expected PDA and verify it matches provided address)
+    let escrow_account = Escrow::from_account_info_mut(escrow)?;
+    let escrow_seeds = [(b"escrow"), maker.address().as_ref(), escrow_account.seed(), escrow_account.bump.as_slice()];
+    let escrow_pda = pinocchio_pubkey::derive_address_const(&escrow_seeds, None, crate::ID.as_array());
+    if escrow_pda != escrow.address().to_bytes() {
+        return Err(EscrowError::InvalidEscrowAddress.into());
+    }
+    if maker.address() != &escrow_account.maker() {
+        return Err(EscrowError::MakerMismatch.into());
+    }
+
+    // Withdraw the approval of a registered approver that has approved
+    let index = escrow_account.approver_index(approver.address()).ok_or(EscrowError::NotAnApprover)?;
+    if !escrow_account.has_approved(index) {
+        return Err(EscrowError::NotApproved.into());
+    }
+    escrow_account.set_approved(index, false);
+
+    // Emit the ApprovalRecorded event
+    emit(&ApprovalRecorded {
+        escrow: *escrow.address(),
+        approver: *approver.address(),
+        approved: 0,
+        approvals: escrow_account.approval_count(),
+        threshold: escrow_account.approval_threshold(),
+        seed: escrow_account.seed_value(),
+    }, event_authority, program)?;
+
+    Ok(())
+}
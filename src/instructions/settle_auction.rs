@@ -0,0 +1,222 @@
+use pinocchio::{
+    AccountView, ProgramResult, cpi::{Seed, Signer}, error::ProgramError, sysvars::{Sysvar, clock::Clock},
+};
+use crate::{errors::EscrowError, events::{EscrowTaken, emit}, state::{Config, Escrow}, token_interface::{CloseAccount, Mint, TokenAccount, TransferChecked, check_native_vault, check_token_program, check_token_vault, check_treasury, close_native_vault, is_native_mint, withdraw_native}};
+
+/// # Settle Auction Instruction
+///
+/// This function allows anyone to settle an English auction once its bidding has ended
+///
+/// ## Business Logic:
+/// 1. Validate all accounts and verify the escrow PDA from the seeds stored in the escrow account
+/// 2. Verify the maker, mint_a, mint_b and the winner match the ones stored in the escrow account, and that the bidding has ended
+/// 3. Transfer the protocol fee (at the rate snapshotted in make) on the highest bid from the bid vault to the treasury
+///    and the whole rest of the bid vault to the maker
+/// 4. Transfer the whole vault of mint_a to the winner (signed by the escrow PDA)
+/// 5. Emit the EscrowTaken event through the self-CPI event instruction
+/// 6. Close the bid vault, returning its rent to the first bidder who paid it, then close the vault and the escrow account,
+///    returning rent to the maker
+///
+/// ## Accounts Expected:
+/// 0. [writable] maker - The maker that created the escrow
+/// 1. [writable] winner - The highest bidder, receiving mint_a
+/// 2. [] mint_a - The mint sold by the auction (the system program for native SOL)
+/// 3. [] mint_b - The mint of the bids
+/// 4. [writable] winner_ata_a - The winner ATA of the mint_a (the winner for native SOL)
+/// 5. [writable] vault - The escrow ATA of the `mint_a` (the native vault PDA for native SOL)
+/// 6. [writable] bid_vault - The escrow ATA of the `mint_b` holding the highest bid
+/// 7. [writable] maker_ata_b - The maker ATA of the `mint_b` to receive the bid
+/// 8. [writable] escrow - The escrow state account
+/// 9. [] system_program - The system program
+/// 10. [] token_program_a - The token program that owns `mint_a` (SPL Token or Token-2022, the system program for native SOL)
+/// 11. [] token_program_b - The token program that owns `mint_b` (SPL Token or Token-2022)
+/// 12. [] config - The global config holding the treasury, unchecked when the escrow charges no fee
+/// 13. [writable] treasury_ata_b - The treasury ATA of the `mint_b` to receive the protocol fee, unchecked when the escrow charges no fee
+/// 14. [] event_authority - The event authority PDA signing the event self-CPI
+/// 15. [] program - The escrow program, invoked to emit the event
+/// 16. [writable] bid_vault_payer - The first bidder, who paid the bid vault rent (the winner for an auction migrated with bids)
+pub fn settle_auction(accounts: &[AccountView], _instruction_data: &[u8]) -> ProgramResult {
+
+    // Unpack accounts - Validate expected accounts
+    let [maker, winner, mint_a, mint_b, winner_ata_a, vault, bid_vault, maker_ata_b, escrow, _system_program, token_program_a, token_program_b, config, treasury_ata_b, event_authority, program, bid_vault_payer, _remaining @..] = accounts else {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    };
+
+    // Check if the token programs are supported and own their mints
+    check_token_program(mint_a, token_program_a)?;
+    check_token_program(mint_b, token_program_b)?;
+    let native_a = is_native_mint(mint_a);
+
+    // Validate the mint_a accounts: the winner receives native SOL directly, otherwise through its ATA
+    if native_a {
+        if winner_ata_a.address() != winner.address() {
+            return Err(EscrowError::InvalidTokenAccount.into());
+        }
+    } else {
+        // Validate the ATAs are owned by the token program of mint_a
+        if !winner_ata_a.owned_by(token_program_a.address()) || !vault.owned_by(token_program_a.address()) {
+            return Err(EscrowError::InvalidTokenAccount.into());
+        }
+
+        // Validate the winner ATA mint and authority
+        if TokenAccount::from_account_view(winner_ata_a)?.owner() != winner.address() {
+            return Err(EscrowError::TokenAccountAuthorityMismatch.into());
+        }
+        if TokenAccount::from_account_view(winner_ata_a)?.mint() != mint_a.address() {
+            return Err(EscrowError::TokenAccountMintMismatch.into());
+        }
+
+        // Validate the vault is the escrow ATA of mint_a, with its mint and authority
+        check_token_vault(vault, escrow, mint_a, token_program_a)?;
+        if TokenAccount::from_account_view(vault)?.owner() != escrow.address() {
+            return Err(EscrowError::TokenAccountAuthorityMismatch.into());
+        }
+        if TokenAccount::from_account_view(vault)?.mint() != mint_a.address() {
+            return Err(EscrowError::TokenAccountMintMismatch.into());
+        }
+    }
+
+    // Validate the ATAs are owned by the token program of mint_b
+    if !bid_vault.owned_by(token_program_b.address()) || !maker_ata_b.owned_by(token_program_b.address()) {
+        return Err(EscrowError::InvalidTokenAccount.into());
+    }
+
+    // Validate the bid vault is the escrow ATA of mint_b, with its mint and authority
+    check_token_vault(bid_vault, escrow, mint_b, token_program_b)?;
+    if TokenAccount::from_account_view(bid_vault)?.owner() != escrow.address() {
+        return Err(EscrowError::TokenAccountAuthorityMismatch.into());
+    }
+    if TokenAccount::from_account_view(bid_vault)?.mint() != mint_b.address() {
+        return Err(EscrowError::TokenAccountMintMismatch.into());
+    }
+
+    // Validate the maker ATA mint and authority
+    if TokenAccount::from_account_view(maker_ata_b)?.owner() != maker.address() {
+        return Err(EscrowError::TokenAccountAuthorityMismatch.into());
+    }
+    if TokenAccount::from_account_view(maker_ata_b)?.mint() != mint_b.address() {
+        return Err(EscrowError::TokenAccountMintMismatch.into());
+    }
+
+    // Validate escrow PDA (derive expected PDA and verify it matches provided address)
+    let escrow_account = Escrow::from_account_info_mut(escrow)?;
+    let escrow_seeds = [(b"escrow"), maker.address().as_ref(), escrow_account.seed(), escrow_account.bump.as_slice()];
+    let escrow_pda = pinocchio_pubkey::derive_address_const(&escrow_seeds, None, crate::ID.as_array());
+    if escrow_pda != escrow.address().to_bytes() {
+        return Err(EscrowError::InvalidEscrowAddress.into());
+    }
+
+    // Validate the maker and both mints are the ones stored in the escrow
+    if maker.address() != &escrow_account.maker() {
+        return Err(EscrowError::MakerMismatch.into());
+    }
+    if mint_a.address() != &escrow_account.mint_a() || mint_b.address() != &escrow_account.mint_b() {
+        return Err(EscrowError::MintMismatch.into());
+    }
+
+    // Validate the native vault belongs to this escrow
+    if native_a {
+        check_native_vault(vault, escrow, escrow_account.vault_bump)?;
+    }
+
+    // Validate the bidding has ended with a winner
+    if !escrow_account.is_english_auction() {
+        return Err(EscrowError::InvalidAuctionTerms.into());
+    }
+    if !escrow_account.is_bidding_over(Clock::get()?.unix_timestamp) {
+        return Err(EscrowError::AuctionNotEnded.into());
+    }
+    let highest_bidder = escrow_account.highest_bidder().ok_or(EscrowError::AuctionHasNoBid)?;
+    if winner.address() != &highest_bidder {
+        return Err(EscrowError::TakerNotAllowed.into());
+    }
+
+    // Validate the bid vault rent goes back to the bidder who paid it
+    if bid_vault_payer.address() != &escrow_account.bid_vault_payer().unwrap_or(highest_bidder) {
+        return Err(EscrowError::BidVaultPayerMismatch.into());
+    }
+
+    // Pay the bid out of the bid vault: the protocol fee to the treasury and all the rest to the maker
+    let signer_seeds = [Seed::from(b"escrow"), Seed::from(maker.address().as_ref()), Seed::from(escrow_account.seed()), Seed::from(escrow_account.bump.as_ref())];
+    let signers = Signer::from(&signer_seeds);
+    let amount_b = escrow_account.highest_bid();
+    let fee = escrow_account.fee_for(amount_b);
+    let decimals_b = Mint::from_account_view(mint_b)?.decimals();
+    if fee > 0 {
+        check_treasury(treasury_ata_b, &Config::from_account_info(config)?.treasury, mint_b, token_program_b)?;
+        TransferChecked {
+            from: bid_vault,
+            mint: mint_b,
+            to: treasury_ata_b,
+            authority: escrow,
+            amount: fee,
+            decimals: decimals_b,
+            token_program: token_program_b.address(),
+        }.invoke_signed(core::slice::from_ref(&signers))?;
+    }
+    TransferChecked {
+        from: bid_vault,
+        mint: mint_b,
+        to: maker_ata_b,
+        authority: escrow,
+        amount: TokenAccount::from_account_view(bid_vault)?.amount().checked_sub(fee).ok_or(EscrowError::MathOverflow)?,
+        decimals: decimals_b,
+        token_program: token_program_b.address(),
+    }.invoke_signed(core::slice::from_ref(&signers))?;
+
+    // Transfer the whole of mint_a to the winner, the full vault balance for a token vault
+    let amount_a = escrow_account.remaining_a();
+    if native_a {
+        withdraw_native(vault, winner_ata_a, amount_a)?;
+    } else {
+        TransferChecked {
+            from: vault,
+            mint: mint_a,
+            to: winner_ata_a,
+            authority: escrow,
+            amount: TokenAccount::from_account_view(vault)?.amount(),
+            decimals: Mint::from_account_view(mint_a)?.decimals(),
+            token_program: token_program_a.address(),
+        }.invoke_signed(core::slice::from_ref(&signers))?;
+    }
+
+    // Emit the EscrowTaken event
+    emit(&EscrowTaken {
+        escrow: *escrow.address(),
+        maker: *maker.address(),
+        taker: *winner.address(),
+        mint_a: *mint_a.address(),
+        mint_b: *mint_b.address(),
+        amount_a,
+        amount_b,
+        fee,
+        remaining_a: 0,
+        seed: escrow_account.seed_value(),
+    }, event_authority, program)?;
+
+    // Close the bid vault, returning its rent to the first bidder who paid it
+    CloseAccount {
+        account: bid_vault,
+        destination: bid_vault_payer,
+        authority: escrow,
+        token_program: token_program_b.address(),
+    }.invoke_signed(core::slice::from_ref(&signers))?;
+
+    // Close Vault Account
+    if native_a {
+        close_native_vault(vault, maker);
+    } else {
+        CloseAccount {
+            account: vault,
+            destination: maker,
+            authority: escrow,
+            token_program: token_program_a.address(),
+        }.invoke_signed(&[signers])?;
+    }
+
+    // Manually close the escrow account and return rent to the maker
+    maker.set_lamports(maker.lamports() + escrow.lamports());
+    escrow.set_lamports(0);
+
+    Ok(())
+}
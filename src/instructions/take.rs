@@ -1,138 +1,404 @@
 use pinocchio::{
-    AccountView, ProgramResult, cpi::{Seed, Signer}, error::ProgramError,
+    AccountView, Address, ProgramResult, cpi::{Seed, Signer}, error::ProgramError, sysvars::{Sysvar, clock::Clock, rent::Rent},
 
 };
-use pinocchio_token::{instructions::{CloseAccount, TransferChecked}, state::{Mint, TokenAccount}};
-use solana_program_log::log;
-
-use crate::state::Escrow;
+use pinocchio_associated_token_account::instructions::CreateIdempotent;
+use pinocchio_system::instructions::{Allocate, Assign, CreateAccount};
+use crate::{errors::EscrowError, events::{EscrowTaken, HashlockRevealed, emit}, oracle::Price, state::{Config, Escrow, PREIMAGE_LEN, Vesting}, token_interface::{CloseAccount, Mint, TokenAccount, TransferChecked, check_native_vault, check_token_program, check_token_vault, check_treasury, close_native_vault, is_native_mint, mint_decimals, withdraw_native}};
+use pinocchio_system::instructions::Transfer;
 
 /// # Take Instruction
 /// 
-/// This function allows a user (taker) to accept the escrow deal created by a maker
+/// This function allows a user (taker) to accept the escrow deal created by a maker, either fully or partially
 /// 
 /// ## Business Logic:
 /// 1. Validate all accounts and verify the escrow PDA from the seeds stored in the escrow account
-/// 2. Verify mint_b matches the one stored in the escrow account
-/// 3. Transfer amount_b of mint_b from the taker to the maker
-/// 4. Transfer all mint_a from the vault to the taker (signed by the escrow PDA)
-/// 5. Close the vault ATA and return rent to the maker
-/// 6. Close the escrow account and return rent to the maker
+/// 2. Verify the maker and mint_a match the ones stored in the escrow account and mint_b is the stored one or one of its payment alternatives,
+///    that the escrow has not expired and, for a private escrow, that the taker is the designated one
+/// 3. Compute the mint_b owed for the requested amount_a at the ratio fixed in make for that mint (rounded up), at the current auction price
+///    for a Dutch auction mint_b, or for an oracle-priced mint_b at the feed price with the premium if that is higher, and check it against
+///    the taker's slippage limit
+/// 4. Transfer the protocol fee (at the rate snapshotted in make) from the taker to the treasury and the rest of the owed mint_b to the maker
+/// 5. Decrease the remaining amount_a stored in the escrow account and transfer the requested mint_a from the vault to the taker (signed by the escrow PDA),
+///    or for a vesting escrow, only taken whole, to a new vesting account of the taker, vesting from now on, that the taker CLAIMs it from
+/// 6. Emit the EscrowTaken event through the self-CPI event instruction, and for a hashlocked escrow the HashlockRevealed event carrying the preimage
+/// 7. Once the escrow is fully filled, close the vault ATA and the escrow account and return rent to the maker. The last fill is paid the whole
+///    vault balance, so tokens sent to the vault on top of the deposit can't block its close
+///
+/// A hashlocked escrow is only taken whole, with the preimage of its hashlock.
+///
+/// An English auction escrow can't be taken, it is settled with SETTLE_AUCTION, nor can an arbitrated escrow, settled with RELEASE or DISPUTE_RESOLVE, or a milestone escrow, paid with RELEASE_MILESTONE.
+///
+/// A native SOL leg is paid in lamports: the taker pays `mint_b` with system transfers, and `mint_a` is paid out of the
+/// program-owned vault. The token accounts of a native leg are replaced by the wallets themselves (taker, maker, treasury).
 /// 
 /// ## Accounts Expected:
 /// 0. [signer] taker - The taker that takes the escrow
 /// 1. [] maker - The maker that created the escrow
-/// 2. [] mint_a - The mint that the taker will get from the maker
-/// 3. [] mint_b - The mint that the taker will give to the maker
-/// 4. [writable] taker_ata_a - The taker ATA of the mint_a
-/// 5. [writable] taker_ata_b - The taker ATA of the mint_b
-/// 6. [writable] vault - The ATA owned by the escrow program that is holding the `mint_a`
-/// 7. [writable] maker_ata_b - The maker ATA of the `mint_b` to receive from the taker
+/// 2. [] mint_a - The mint that the taker will get from the maker (the system program for native SOL)
+/// 3. [] mint_b - The mint that the taker will give to the maker, `mint_b` or one of the payment alternatives (the system program for native SOL)
+/// 4. [writable] taker_ata_a - The taker ATA of the mint_a (the taker for native SOL)
+/// 5. [writable] taker_ata_b - The taker ATA of the mint_b (the taker for native SOL)
+/// 6. [writable] vault - The ATA owned by the escrow program that is holding the `mint_a` (the native vault PDA for native SOL)
+/// 7. [writable] maker_ata_b - The maker ATA of the `mint_b` to receive from the taker (the maker for native SOL)
 /// 8. [writable] escrow - The escrow state account
 /// 9. [] system_program - The system program for account creation
-/// 10. [] token_program - The token program for token managing
+/// 10. [] token_program_a - The token program that owns `mint_a` (SPL Token or Token-2022, the system program for native SOL)
+/// 11. [] token_program_b - The token program that owns `mint_b` (SPL Token or Token-2022, the system program for native SOL)
+/// 12. [] config - The global config holding the treasury, unchecked when the escrow charges no fee
+/// 13. [writable] treasury_ata_b - The treasury ATA of the `mint_b` to receive the protocol fee (the treasury for native SOL), unchecked when the escrow charges no fee
+/// 14. [] event_authority - The event authority PDA signing the event self-CPI
+/// 15. [] program - The escrow program, invoked to emit the event
+/// 16. [] price_feed - The price feed stored in the escrow (a price update of its feed id for Pyth), only when paying an oracle-priced mint_b
+///
+/// For a vesting escrow, followed by:
+/// 16. [writable] vesting - The vesting account of the taker to create (PDA derived from `["vesting", escrow, taker]`, topped up if already funded)
+/// 17. [writable] vesting_vault - The ATA owned by the vesting account to hold the `mint_a`, created if it does not exist yet (the vesting account for native SOL)
+/// 18. [] associated_token_program - The associated token program for ATA creation
+///
+/// The v1 account list, accounts 0 to 10 only with `token_program` owning both mints and no instruction data, is still accepted:
+/// it takes the whole remaining_a of an escrow without protocol fee, and emits no event.
 /// 
-pub fn take (accounts: &[AccountView], _instruction_data: &[u8]) -> ProgramResult {
+/// ## Data Parameters:
+/// 0. [u8; 8] amount_a - The amount of mint_a that the taker expects to receive (u64)
+/// 1. [u8; 8] max_amount_b - Slippage guard, the most mint_b the taker agrees to pay for amount_a, fee included (u64), optional unless paying an oracle-priced mint_b
+/// 2. [u8; 32] preimage - Only for a hashlocked escrow, and required: the preimage of its SHA-256 hashlock
+pub fn take (accounts: &[AccountView], instruction_data: &[u8]) -> ProgramResult {
 
     // Unpack accounts - Validate expected accounts
-    let [taker, maker, mint_a, mint_b, taker_ata_a, taker_ata_b, vault, maker_ata_b, escrow, _system_program, _token_program, _remaining @..] = accounts else {
+    let [taker, maker, mint_a, mint_b, taker_ata_a, taker_ata_b, vault, maker_ata_b, escrow, system_program, token_program_a, remaining @..] = accounts else {
         return Err(ProgramError::NotEnoughAccountKeys);
     };
 
+    // The v1 account list of TAKE stops at the token program, shared by both mints, and comes without instruction data
+    let v1 = remaining.is_empty() && instruction_data.is_empty();
+    let (token_program_b, treasury, events, remaining) = match remaining {
+        [] if v1 => (token_program_a, None, None, remaining),
+        [token_program_b, config, treasury_ata_b, event_authority, program, remaining @..] => {
+            (token_program_b, Some((config, treasury_ata_b)), Some((event_authority, program)), remaining)
+        }
+        _ => return Err(ProgramError::NotEnoughAccountKeys),
+    };
+
     // Check if taker is signer
     if !taker.is_signer() {
-        return Err(ProgramError::InvalidAccountOwner);
+        return Err(EscrowError::MissingSigner.into());
     }
 
-    // Check if mint accounts are owned by the token program
-    if !mint_a.owned_by(&pinocchio_token::ID) || !mint_b.owned_by(&pinocchio_token::ID) {
-        return Err(ProgramError::InvalidAccountOwner);
+    // Check if the token programs are supported and own their mints
+    check_token_program(mint_a, token_program_a)?;
+    check_token_program(mint_b, token_program_b)?;
+    let native_a = is_native_mint(mint_a);
+    let native_b = is_native_mint(mint_b);
+
+    // Validate the mint_a accounts: the taker receives native SOL directly, otherwise through its ATA
+    if native_a {
+        if taker_ata_a.address() != taker.address() {
+            return Err(EscrowError::InvalidTokenAccount.into());
+        }
+    } else {
+        // Validate the ATAs are owned by the token program of mint_a
+        if !taker_ata_a.owned_by(token_program_a.address()) || !vault.owned_by(token_program_a.address()) {
+            return Err(EscrowError::InvalidTokenAccount.into());
+        }
+
+        // Validate the taker ATA mint and authority
+        if TokenAccount::from_account_view(taker_ata_a)?.owner() != taker.address() {
+            return Err(EscrowError::TokenAccountAuthorityMismatch.into());
+        }
+        if TokenAccount::from_account_view(taker_ata_a)?.mint() != mint_a.address() {
+            return Err(EscrowError::TokenAccountMintMismatch.into());
+        }
+
+        // Validate the vault is the escrow ATA of mint_a, with its mint and authority
+        check_token_vault(vault, escrow, mint_a, token_program_a)?;
+        if TokenAccount::from_account_view(vault)?.owner() != escrow.address() {
+            return Err(EscrowError::TokenAccountAuthorityMismatch.into());
+        }
+        if TokenAccount::from_account_view(vault)?.mint() != mint_a.address() {
+            return Err(EscrowError::TokenAccountMintMismatch.into());
+        }
     }
 
-    // Validate the ATAs are owned by the token program
-    if !taker_ata_a.owned_by(&pinocchio_token::ID) || 
-        !taker_ata_b.owned_by(&pinocchio_token::ID) ||
-        !vault.owned_by(&pinocchio_token::ID) ||
-        !maker_ata_b.owned_by(&pinocchio_token::ID)
-    {
-        return Err(ProgramError::InvalidAccountOwner);
+    // Validate the mint_b accounts: native SOL moves between the wallets, otherwise between their ATAs
+    if native_b {
+        if taker_ata_b.address() != taker.address() || maker_ata_b.address() != maker.address() {
+            return Err(EscrowError::InvalidTokenAccount.into());
+        }
+    } else {
+        // Validate the ATAs are owned by the token program of mint_b
+        if !taker_ata_b.owned_by(token_program_b.address()) || !maker_ata_b.owned_by(token_program_b.address()) {
+            return Err(EscrowError::InvalidTokenAccount.into());
+        }
+
+        // Validate the taker ATA mint and authority
+        if TokenAccount::from_account_view(taker_ata_b)?.owner() != taker.address() {
+            return Err(EscrowError::TokenAccountAuthorityMismatch.into());
+        }
+        if TokenAccount::from_account_view(taker_ata_b)?.mint() != mint_b.address() {
+            return Err(EscrowError::TokenAccountMintMismatch.into());
+        }
+
+        // Validate the maker ATA mint and authority
+        if TokenAccount::from_account_view(maker_ata_b)?.owner() != maker.address() {
+            return Err(EscrowError::TokenAccountAuthorityMismatch.into());
+        }
+        if TokenAccount::from_account_view(maker_ata_b)?.mint() != mint_b.address() {
+            return Err(EscrowError::TokenAccountMintMismatch.into());
+        }
     }
 
-    // Validate the taker ATA mint and authority
-    if TokenAccount::from_account_view(taker_ata_a)?.owner() != taker.address() {
-        return Err(ProgramError::InvalidAccountData);
+    // Validate escrow PDA (derive expected PDA and verify it matches provided address)
+    let escrow_account = Escrow::from_account_info_mut(escrow)?;
+    let escrow_seeds = [(b"escrow"), maker.address().as_ref(), escrow_account.seed(), escrow_account.bump.as_slice()];
+    let escrow_pda = pinocchio_pubkey::derive_address_const(&escrow_seeds, None, crate::ID.as_array());
+    if escrow_pda != escrow.address().to_bytes() {
+        return Err(EscrowError::InvalidEscrowAddress.into());
     }
-    if TokenAccount::from_account_view(taker_ata_a)?.mint() != mint_a.address() {
-        return Err(ProgramError::InvalidAccountData);
+
+    // Validate the mint_b is the one in the escrow or one of its payment alternatives, and get its price for the whole amount_a
+    let whole_amount_b = escrow_account.amount_b_of(mint_b.address()).ok_or(EscrowError::MintMismatch)?;
+
+    // Validate the maker and mint_a are the ones stored in the escrow
+    if maker.address() != &escrow_account.maker() {
+        return Err(EscrowError::MakerMismatch.into());
     }
-    if TokenAccount::from_account_view(taker_ata_b)?.owner() != taker.address() {
-        return Err(ProgramError::InvalidAccountData);
+    if mint_a.address() != &escrow_account.mint_a() {
+        return Err(EscrowError::MintMismatch.into());
     }
-    if TokenAccount::from_account_view(taker_ata_b)?.mint() != mint_b.address() {
-        return Err(ProgramError::InvalidAccountData);
+
+    // Validate the native vault belongs to this escrow
+    if native_a {
+        check_native_vault(vault, escrow, escrow_account.vault_bump)?;
     }
 
-    // Validate the vault mint and authority
-    if TokenAccount::from_account_view(vault)?.owner() != escrow.address() {
-        return Err(ProgramError::InvalidAccountData);
+    // An English auction goes to the highest bidder through SETTLE_AUCTION
+    if escrow_account.is_english_auction() {
+        return Err(EscrowError::InvalidAuctionTerms.into());
     }
-    if TokenAccount::from_account_view(vault)?.mint() != mint_a.address() {
-        return Err(ProgramError::InvalidAccountData);
+
+    // A milestone escrow goes to the payee through RELEASE_MILESTONE, its arbiter only releasing and cancelling milestones
+    if escrow_account.is_milestone_escrow() {
+        return Err(EscrowError::MilestoneEscrow.into());
     }
 
-    // Validate the maker ATA mint and authority
-    if TokenAccount::from_account_view(maker_ata_b)?.owner() != maker.address() {
-        return Err(ProgramError::InvalidAccountData);
+    // An arbitrated escrow goes to the seller through RELEASE or DISPUTE_RESOLVE
+    if escrow_account.is_arbitrated() {
+        return Err(EscrowError::ArbitratedEscrow.into());
     }
-    if TokenAccount::from_account_view(maker_ata_b)?.mint() != mint_b.address() {
-        return Err(ProgramError::InvalidAccountData);
+
+    // An approval escrow goes to the beneficiary through APPROVE
+    if escrow_account.is_approval_escrow() {
+        return Err(EscrowError::ApprovalEscrow.into());
     }
 
-    // Validate escrow PDA (derive expected PDA and verify it matches provided address)
-    let escrow_account = Escrow::from_account_info_mut(escrow)?;
-    let escrow_seeds = [(b"escrow"), maker.address().as_ref(), escrow_account.seed.as_slice(), escrow_account.bump.as_slice()];
-    let escrow_pda = pinocchio_pubkey::derive_address_const(&escrow_seeds, None, &crate::ID.as_array());
-    if escrow_pda != escrow.address().to_bytes() {
-        return Err(ProgramError::InvalidAccountOwner);
+    // Validate the taker is allowed to take a private escrow
+    if !escrow_account.is_taker_allowed(&taker.address().to_bytes()) {
+        return Err(EscrowError::TakerNotAllowed.into());
+    }
+
+    // Validate the escrow has not expired
+    let now = Clock::get()?.unix_timestamp;
+    if escrow_account.is_expired(now) {
+        return Err(EscrowError::EscrowExpired.into());
+    }
+
+    // Validate data parameters: the preimage of a hashlocked escrow follows the amounts
+    let preimage_len = if escrow_account.is_hashlocked() { PREIMAGE_LEN } else { 0 };
+    if !v1 && instruction_data.len() != 8 + preimage_len && instruction_data.len() != 16 + preimage_len {
+        return Err(EscrowError::InvalidInstructionData.into());
     }
+    let (instruction_data, preimage) = instruction_data.split_at(instruction_data.len().saturating_sub(preimage_len));
 
-    // Validate the mint_b is the same as the one in the escrow
-    if mint_b.address().to_bytes() != escrow_account.mint_b {
-        return Err(ProgramError::InvalidAccountData);
+    // Validate the preimage unlocks a hashlocked escrow
+    if escrow_account.is_hashlocked() && !escrow_account.unlocks(preimage) {
+        return Err(EscrowError::InvalidPreimage.into());
     }
 
-    // Transfer amount_b from taker to maker
-    let amount_b = u64::from_le_bytes(escrow_account.amount_b);
-    TransferChecked {
-        from: taker_ata_b,
-        mint: mint_b,
-        to: maker_ata_b,
-        authority: taker,
-        amount: amount_b,
-        decimals: Mint::from_account_view(mint_b)?.decimals(),
-    }.invoke()?;
+    // Unpack data, the v1 TAKE filling whatever is left in the escrow
+    let remaining_a = escrow_account.remaining_a();
+    let amount_a = instruction_data.get(0..8).map_or(remaining_a, |amount_a| u64::from_le_bytes(amount_a.try_into().unwrap()));
+    let max_amount_b = instruction_data.get(8..16).map_or(u64::MAX, |max_amount_b| u64::from_le_bytes(max_amount_b.try_into().unwrap()));
 
-    // Transfer amount_a from vault to taker
-    let signer_seeds = [Seed::from(b"escrow"), Seed::from(maker.address().as_ref()), Seed::from(escrow_account.seed.as_ref()), Seed::from(escrow_account.bump.as_ref())];
+    // Validate the amount to fill against what is left in the escrow
+    if amount_a == 0 {
+        return Err(EscrowError::ZeroAmount.into());
+    }
+    if amount_a > remaining_a {
+        return Err(EscrowError::FillExceedsRemaining.into());
+    }
+
+    // A vesting escrow is taken whole, as a taker has a single vesting account per escrow
+    if escrow_account.is_vesting() && amount_a != remaining_a {
+        return Err(EscrowError::PartialVestingFill.into());
+    }
+
+    // So is a hashlocked escrow, as its first fill reveals the preimage unlocking the whole counterparty leg
+    if escrow_account.is_hashlocked() && amount_a != remaining_a {
+        return Err(EscrowError::PartialHashlockFill.into());
+    }
+
+    // A Dutch auction asks for mint_b the price decayed to the time of the take
+    let whole_amount_b = if escrow_account.is_dutch_auction() && mint_b.address() == &escrow_account.mint_b() {
+        escrow_account.dutch_amount_b(now)
+    } else {
+        whole_amount_b
+    };
+
+    // Compute the pro rata amount_b
+    let mut amount_b = escrow_account.amount_b_for(amount_a, whole_amount_b).ok_or(EscrowError::MathOverflow)?;
+
+    // An oracle-priced mint_b costs the fresh feed price moved by the premium, never less than the stored amount_b,
+    // so the taker must bound what it pays
+    let price_feed_id = escrow_account.price_feed().filter(|_| mint_b.address() == &escrow_account.mint_b());
+    if let Some(price_feed_id) = price_feed_id {
+        if instruction_data.len() < 16 {
+            return Err(EscrowError::MissingSlippageGuard.into());
+        }
+        let (config, _) = treasury.ok_or(ProgramError::NotEnoughAccountKeys)?;
+        let price_feed_program = Address::new_from_array(Config::from_account_info(config)?.price_feed_program);
+        let price_feed = remaining.first().ok_or(ProgramError::NotEnoughAccountKeys)?;
+        let price = Price::from_account_view(price_feed, &price_feed_program)?;
+        if Price::feed_id(price_feed)? != price_feed_id {
+            return Err(EscrowError::InvalidPriceFeed.into());
+        }
+        price.check(now, escrow_account.max_price_age(), escrow_account.max_confidence_bps())?;
+        let feed_amount_b = price.amount_b_for(amount_a, mint_decimals(mint_a)?, mint_decimals(mint_b)?, escrow_account.premium_bps())
+            .ok_or(EscrowError::MathOverflow)?;
+        amount_b = amount_b.max(feed_amount_b);
+    }
+
+    // Fail if the terms (or the feed price) moved past what the taker signed for
+    if amount_b > max_amount_b {
+        return Err(EscrowError::SlippageExceeded.into());
+    }
+
+    // Transfer the protocol fee from taker to treasury and the rest of the pro rata amount_b from taker to maker
+    // (the treasury is only validated when there is a fee, and the v1 account list has none, so it only takes an escrow charging no fee)
+    let fee = escrow_account.fee_for(amount_b);
+    let fee_treasury = match treasury {
+        _ if fee == 0 => None,
+        Some((config, treasury_ata_b)) => {
+            check_treasury(treasury_ata_b, &Config::from_account_info(config)?.treasury, mint_b, token_program_b)?;
+            Some(treasury_ata_b)
+        }
+        None => return Err(ProgramError::NotEnoughAccountKeys),
+    };
+    if native_b {
+        if let Some(treasury_ata_b) = fee_treasury {
+            Transfer {
+                from: taker,
+                to: treasury_ata_b,
+                lamports: fee,
+            }.invoke()?;
+        }
+        Transfer {
+            from: taker,
+            to: maker_ata_b,
+            lamports: amount_b - fee,
+        }.invoke()?;
+    } else {
+        let decimals_b = Mint::from_account_view(mint_b)?.decimals();
+        if let Some(treasury_ata_b) = fee_treasury {
+            TransferChecked {
+                from: taker_ata_b,
+                mint: mint_b,
+                to: treasury_ata_b,
+                authority: taker,
+                amount: fee,
+                decimals: decimals_b,
+                token_program: token_program_b.address(),
+            }.invoke()?;
+        }
+        TransferChecked {
+            from: taker_ata_b,
+            mint: mint_b,
+            to: maker_ata_b,
+            authority: taker,
+            amount: amount_b - fee,
+            decimals: decimals_b,
+            token_program: token_program_b.address(),
+        }.invoke()?;
+    }
+
+    // Update the remaining amount and keep the escrow open until it is fully filled
+    let remaining_a = remaining_a - amount_a;
+    escrow_account.remaining_a = remaining_a.to_le_bytes();
+
+    // A vesting escrow delivers amount_a to a new vesting account of the taker instead: its ATA, or the account itself for native SOL
+    let recipient = if escrow_account.is_vesting() {
+        let [vesting, vesting_vault, ..] = remaining else {
+            return Err(ProgramError::NotEnoughAccountKeys);
+        };
+        let start = now;
+        let cliff = start.checked_add(escrow_account.vesting_cliff()).ok_or(EscrowError::MathOverflow)?;
+        let end = start.checked_add(escrow_account.vesting_duration()).ok_or(EscrowError::MathOverflow)?;
+        open_vesting(taker, mint_a, escrow, vesting, vesting_vault, system_program, token_program_a, amount_a, start, cliff, end)?;
+        if native_a { vesting } else { vesting_vault }
+    } else {
+        taker_ata_a
+    };
+
+    // Transfer amount_a from vault to taker, the last fill emptying the vault (tokens sent to it on top of the deposit included) so it can be closed
+    let signer_seeds = [Seed::from(b"escrow"), Seed::from(maker.address().as_ref()), Seed::from(escrow_account.seed()), Seed::from(escrow_account.bump.as_ref())];
     let signers = Signer::from(&signer_seeds);
-    let amount_a = TokenAccount::from_account_view(vault)?.amount();
-    TransferChecked {
-        from: vault,
-        mint: mint_a,
-        to: taker_ata_a,
-        authority: escrow,
-        amount: amount_a,
-        decimals: Mint::from_account_view(mint_a)?.decimals(),
-    }.invoke_signed(&[signers.clone()])?;
-
-    log!("debug");
+    if native_a {
+        withdraw_native(vault, recipient, amount_a)?;
+    } else {
+        let vault_amount = if remaining_a == 0 { TokenAccount::from_account_view(vault)?.amount() } else { amount_a };
+        TransferChecked {
+            from: vault,
+            mint: mint_a,
+            to: recipient,
+            authority: escrow,
+            amount: vault_amount,
+            decimals: Mint::from_account_view(mint_a)?.decimals(),
+            token_program: token_program_a.address(),
+        }.invoke_signed(core::slice::from_ref(&signers))?;
+    }
+
+    // Emit the EscrowTaken event, unless the v1 account list left out the event accounts
+    if let Some((event_authority, program)) = events {
+        emit(&EscrowTaken {
+            escrow: *escrow.address(),
+            maker: *maker.address(),
+            taker: *taker.address(),
+            mint_a: *mint_a.address(),
+            mint_b: *mint_b.address(),
+            amount_a,
+            amount_b,
+            fee,
+            remaining_a,
+            seed: escrow_account.seed_value(),
+        }, event_authority, program)?;
+
+        // Reveal the preimage for the counterparty to unlock the other leg of the swap
+        if escrow_account.is_hashlocked() {
+            emit(&HashlockRevealed {
+                escrow: *escrow.address(),
+                taker: *taker.address(),
+                hashlock: escrow_account.hashlock,
+                preimage: preimage.try_into().unwrap(),
+                seed: escrow_account.seed_value(),
+            }, event_authority, program)?;
+        }
+    }
+
+    if remaining_a > 0 {
+        return Ok(());
+    }
 
     // Close Vault Account
-    CloseAccount {
-        account: vault,
-        destination: maker,
-        authority: escrow,
-    }.invoke_signed(&[signers])?;
+    if native_a {
+        close_native_vault(vault, maker);
+    } else {
+        CloseAccount {
+            account: vault,
+            destination: maker,
+            authority: escrow,
+            token_program: token_program_a.address(),
+        }.invoke_signed(&[signers])?;
+    }
 
     // Manually close the escrow account and return rent to the maker
     // This completes the trade by cleaning up all accounts
@@ -140,4 +406,68 @@ pub fn take (accounts: &[AccountView], _instruction_data: &[u8]) -> ProgramResul
     escrow.set_lamports(0);
         
     Ok(())
-} 
\ No newline at end of file
+}
+
+/// Creates the vesting account `["vesting", escrow, taker]` of `amount_a`, paid by the taker, with its ATA of `mint_a` for a token
+#[allow(clippy::too_many_arguments)]
+fn open_vesting(taker: &AccountView, mint_a: &AccountView, escrow: &AccountView, vesting: &AccountView, vesting_vault: &AccountView, system_program: &AccountView, token_program_a: &AccountView, amount_a: u64, start: i64, cliff: i64, end: i64) -> ProgramResult {
+
+    // Validate vesting PDA, deriving its canonical bump
+    let (vesting_pda, vesting_bump) = Address::find_program_address(&[b"vesting", escrow.address().as_ref(), taker.address().as_ref()], &crate::ID);
+    if &vesting_pda != vesting.address() {
+        return Err(EscrowError::InvalidEscrowAddress.into());
+    }
+    if !vesting.owned_by(&pinocchio_system::ID) {
+        return Err(EscrowError::AccountAlreadyInitialized.into());
+    }
+    let vesting_bump = [vesting_bump];
+
+    // Create Vesting account
+    let signer_seeds = [Seed::from(b"vesting"), Seed::from(escrow.address().as_ref()), Seed::from(taker.address().as_ref()), Seed::from(vesting_bump.as_ref())];
+    let signers = Signer::from(&signer_seeds);
+    let rent = Rent::get()?.minimum_balance_unchecked(Vesting::LEN);
+    if vesting.lamports() == 0 {
+        CreateAccount {
+            from: taker,
+            to: vesting,
+            lamports: rent,
+            space: Vesting::LEN as u64,
+            owner: &crate::ID,
+        }.invoke_signed(core::slice::from_ref(&signers))?;
+    } else {
+        // Anyone can send lamports to the vesting address ahead of the take, which would fail CreateAccount:
+        // top it up to the rent exemption, then allocate and assign it instead
+        let shortfall = rent.saturating_sub(vesting.lamports());
+        if shortfall > 0 {
+            Transfer {
+                from: taker,
+                to: vesting,
+                lamports: shortfall,
+            }.invoke()?;
+        }
+        Allocate {
+            account: vesting,
+            space: Vesting::LEN as u64,
+        }.invoke_signed(core::slice::from_ref(&signers))?;
+        Assign {
+            account: vesting,
+            owner: &crate::ID,
+        }.invoke_signed(core::slice::from_ref(&signers))?;
+    }
+    let vesting_account = Vesting::from_account_info_mut_uninitialized(vesting)?;
+    vesting_account.set_inner(taker.address().to_bytes(), escrow.address().to_bytes(), mint_a.address().to_bytes(), amount_a, start, cliff, end, vesting_bump);
+
+    // Create the vesting ATA holding a token mint_a, unless someone already did
+    if !is_native_mint(mint_a) {
+        CreateIdempotent {
+            funding_account: taker,
+            account: vesting_vault,
+            wallet: vesting,
+            mint: mint_a,
+            system_program,
+            token_program: token_program_a,
+        }.invoke()?;
+    }
+
+    Ok(())
+}
@@ -0,0 +1,227 @@
+use pinocchio::{
+    AccountView, ProgramResult, cpi::{Seed, Signer}, error::ProgramError, sysvars::{Sysvar, clock::Clock},
+};
+use pinocchio_system::instructions::Transfer;
+use crate::{errors::EscrowError, events::{BasketTaken, emit}, state::{Basket, Config}, token_interface::{CloseAccount, Mint, TokenAccount, TransferChecked, check_token_program, check_token_vault, check_treasury, is_native_mint}};
+
+/// # Take Basket Instruction
+///
+/// This function allows a taker to take a whole basket escrow, settling every leg atomically: if any transfer fails, the instruction fails as a whole
+///
+/// ## Business Logic:
+/// 1. Validate all accounts and verify the basket PDA from the seed stored in the basket account
+/// 2. Verify the maker, mint_b and every leg mint match the ones stored in the basket, in order, and that the basket has not expired
+/// 3. Transfer the protocol fee (at the rate snapshotted in make_basket) from the taker to the treasury and the rest of amount_b to the maker
+/// 4. For every leg, transfer the vault balance to the taker and close the vault, returning rent to the maker (signed by the basket PDA)
+/// 5. Close the basket account, return rent to the maker and emit the BasketTaken event through the self-CPI event instruction
+///
+/// ## Accounts Expected:
+/// 0. [signer] taker - The taker that takes the basket
+/// 1. [writable] maker - The maker that created the basket
+/// 2. [] mint_b - The mint that the taker will give to the maker (the system program for native SOL)
+/// 3. [writable] taker_ata_b - The taker ATA of the mint_b (the taker for native SOL)
+/// 4. [writable] maker_ata_b - The maker ATA of the `mint_b` to receive from the taker (the maker for native SOL)
+/// 5. [writable] basket - The basket state account
+/// 6. [] system_program - The system program
+/// 7. [] token_program_b - The token program that owns `mint_b` (SPL Token or Token-2022, the system program for native SOL)
+/// 8. [] config - The global config holding the treasury, unchecked when the basket charges no fee
+/// 9. [writable] treasury_ata_b - The treasury ATA of the `mint_b` to receive the protocol fee (the treasury for native SOL), unchecked when the basket charges no fee
+/// 10. [] event_authority - The event authority PDA signing the event self-CPI
+/// 11. [] program - The escrow program, invoked to emit the event
+///
+/// Then, for every leg, in the order of the basket:
+/// 0. [] mint_a - The mint of the leg
+/// 1. [writable] taker_ata_a - The taker ATA of the `mint_a`
+/// 2. [writable] vault - The basket ATA holding the `mint_a`
+/// 3. [] token_program - The token program that owns `mint_a` (SPL Token or Token-2022)
+pub fn take_basket(accounts: &[AccountView], _instruction_data: &[u8]) -> ProgramResult {
+
+    // Unpack accounts - Validate expected accounts
+    let [taker, maker, mint_b, taker_ata_b, maker_ata_b, basket, _system_program, token_program_b, config, treasury_ata_b, event_authority, program, leg_accounts @..] = accounts else {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    };
+
+    // Check if taker is signer
+    if !taker.is_signer() {
+        return Err(EscrowError::MissingSigner.into());
+    }
+
+    // Check if the token program is supported and owns mint_b
+    check_token_program(mint_b, token_program_b)?;
+    let native_b = is_native_mint(mint_b);
+
+    // Validate the mint_b accounts: native SOL moves between the wallets, otherwise between their ATAs
+    if native_b {
+        if taker_ata_b.address() != taker.address() || maker_ata_b.address() != maker.address() {
+            return Err(EscrowError::InvalidTokenAccount.into());
+        }
+    } else {
+        // Validate the ATAs are owned by the token program of mint_b
+        if !taker_ata_b.owned_by(token_program_b.address()) || !maker_ata_b.owned_by(token_program_b.address()) {
+            return Err(EscrowError::InvalidTokenAccount.into());
+        }
+
+        // Validate the taker ATA mint and authority
+        if TokenAccount::from_account_view(taker_ata_b)?.owner() != taker.address() {
+            return Err(EscrowError::TokenAccountAuthorityMismatch.into());
+        }
+        if TokenAccount::from_account_view(taker_ata_b)?.mint() != mint_b.address() {
+            return Err(EscrowError::TokenAccountMintMismatch.into());
+        }
+
+        // Validate the maker ATA mint and authority
+        if TokenAccount::from_account_view(maker_ata_b)?.owner() != maker.address() {
+            return Err(EscrowError::TokenAccountAuthorityMismatch.into());
+        }
+        if TokenAccount::from_account_view(maker_ata_b)?.mint() != mint_b.address() {
+            return Err(EscrowError::TokenAccountMintMismatch.into());
+        }
+    }
+
+    // Validate basket PDA (derive expected PDA and verify it matches provided address)
+    let basket_account = Basket::from_account_info_mut(basket)?;
+    let basket_seeds = [(b"basket").as_slice(), maker.address().as_ref(), basket_account.seed.as_slice(), basket_account.bump.as_slice()];
+    let basket_pda = pinocchio_pubkey::derive_address_const(&basket_seeds, None, crate::ID.as_array());
+    if basket_pda != basket.address().to_bytes() {
+        return Err(EscrowError::InvalidEscrowAddress.into());
+    }
+
+    // Validate the maker and mint_b are the ones stored in the basket
+    if maker.address() != &basket_account.maker() {
+        return Err(EscrowError::MakerMismatch.into());
+    }
+    if mint_b.address() != &basket_account.mint_b() {
+        return Err(EscrowError::MintMismatch.into());
+    }
+
+    // Validate every stored leg is passed
+    if leg_accounts.len() < basket_account.legs().len() * 4 {
+        return Err(EscrowError::InvalidBasketLegs.into());
+    }
+
+    // Validate the basket has not expired
+    if basket_account.is_expired(Clock::get()?.unix_timestamp) {
+        return Err(EscrowError::EscrowExpired.into());
+    }
+
+    // Transfer the protocol fee from taker to treasury and the rest of amount_b from taker to maker
+    let amount_b = basket_account.amount_b();
+    let fee = basket_account.fee_for(amount_b);
+    if fee > 0 {
+        check_treasury(treasury_ata_b, &Config::from_account_info(config)?.treasury, mint_b, token_program_b)?;
+    }
+    if native_b {
+        if fee > 0 {
+            Transfer {
+                from: taker,
+                to: treasury_ata_b,
+                lamports: fee,
+            }.invoke()?;
+        }
+        Transfer {
+            from: taker,
+            to: maker_ata_b,
+            lamports: amount_b - fee,
+        }.invoke()?;
+    } else {
+        let decimals_b = Mint::from_account_view(mint_b)?.decimals();
+        if fee > 0 {
+            TransferChecked {
+                from: taker_ata_b,
+                mint: mint_b,
+                to: treasury_ata_b,
+                authority: taker,
+                amount: fee,
+                decimals: decimals_b,
+                token_program: token_program_b.address(),
+            }.invoke()?;
+        }
+        TransferChecked {
+            from: taker_ata_b,
+            mint: mint_b,
+            to: maker_ata_b,
+            authority: taker,
+            amount: amount_b - fee,
+            decimals: decimals_b,
+            token_program: token_program_b.address(),
+        }.invoke()?;
+    }
+
+    // Settle every leg, signed by the basket PDA
+    let signer_seeds = [Seed::from(b"basket"), Seed::from(maker.address().as_ref()), Seed::from(basket_account.seed.as_ref()), Seed::from(basket_account.bump.as_ref())];
+    let signers = Signer::from(&signer_seeds);
+    for (leg, leg_accounts) in basket_account.legs().iter().zip(leg_accounts.chunks_exact(4)) {
+        let [mint_a, taker_ata_a, vault, token_program] = leg_accounts else {
+            return Err(ProgramError::NotEnoughAccountKeys);
+        };
+
+        // Validate the leg mint and its token program
+        if mint_a.address() != &leg.mint_a() {
+            return Err(EscrowError::InvalidBasketLegs.into());
+        }
+        check_token_program(mint_a, token_program)?;
+
+        // Validate the ATAs are owned by the token program of mint_a
+        if !taker_ata_a.owned_by(token_program.address()) || !vault.owned_by(token_program.address()) {
+            return Err(EscrowError::InvalidTokenAccount.into());
+        }
+
+        // Validate the taker ATA mint and authority
+        if TokenAccount::from_account_view(taker_ata_a)?.owner() != taker.address() {
+            return Err(EscrowError::TokenAccountAuthorityMismatch.into());
+        }
+        if TokenAccount::from_account_view(taker_ata_a)?.mint() != mint_a.address() {
+            return Err(EscrowError::TokenAccountMintMismatch.into());
+        }
+
+        // Validate the vault is the basket ATA of mint_a, with its mint and authority
+        check_token_vault(vault, basket, mint_a, token_program)?;
+        let vault_account = TokenAccount::from_account_view(vault)?;
+        if vault_account.owner() != basket.address() {
+            return Err(EscrowError::TokenAccountAuthorityMismatch.into());
+        }
+        if vault_account.mint() != mint_a.address() {
+            return Err(EscrowError::TokenAccountMintMismatch.into());
+        }
+        if vault_account.amount() < leg.amount_a() {
+            return Err(EscrowError::InvalidBasketLegs.into());
+        }
+
+        // Transfer the whole vault to the taker, so it can be closed
+        TransferChecked {
+            from: vault,
+            mint: mint_a,
+            to: taker_ata_a,
+            authority: basket,
+            amount: vault_account.amount(),
+            decimals: Mint::from_account_view(mint_a)?.decimals(),
+            token_program: token_program.address(),
+        }.invoke_signed(core::slice::from_ref(&signers))?;
+
+        // Close Vault Account
+        CloseAccount {
+            account: vault,
+            destination: maker,
+            authority: basket,
+            token_program: token_program.address(),
+        }.invoke_signed(core::slice::from_ref(&signers))?;
+    }
+    let seed = basket_account.seed_value();
+
+    // Manually close the basket account and return rent to the maker
+    maker.set_lamports(maker.lamports() + basket.lamports());
+    basket.set_lamports(0);
+
+    // Emit the BasketTaken event
+    emit(&BasketTaken {
+        basket: *basket.address(),
+        maker: *maker.address(),
+        taker: *taker.address(),
+        mint_b: *mint_b.address(),
+        amount_b,
+        fee,
+        seed,
+    }, event_authority, program)?;
+
+    Ok(())
+}
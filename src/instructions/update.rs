@@ -0,0 +1,196 @@
+use pinocchio::{
+    AccountView, ProgramResult, cpi::{Seed, Signer}, error::ProgramError,
+
+};
+use pinocchio_system::instructions::Transfer;
+
+use crate::{errors::EscrowError, state::Escrow, token_interface::{Mint, TokenAccount, TransferChecked, check_native_vault, check_token_program, check_token_vault, is_native_mint, withdraw_native}};
+
+/// # Update Instruction
+/// 
+/// This function allows the maker to reprice an open escrow, top it up or withdraw part of it in place,
+/// keeping the same escrow PDA, seed and bump
+/// 
+/// ## Business Logic:
+/// 1. Validate all accounts and verify the escrow PDA from the seeds stored in the escrow account, and the maker and mint_a stored in it
+/// 2. Deposit more mint_a from the maker's ATA into the vault, or withdraw part of the vault back to the maker (signed by the escrow PDA)
+/// 3. Reset the escrow terms to the new remaining amount_a and the new amount_b (and payment alternative amounts) asked for it
+///
+/// An auction, arbitrated, milestone or hashlocked escrow can't be updated, its terms stay the ones it was made with
+/// 
+/// ## Accounts Expected:
+/// 0. [signer] maker - The maker that created the escrow
+/// 1. [] mint_a - The mint that the maker deposited (the system program for native SOL)
+/// 2. [writable] maker_ata - The maker ATA of the `mint_a` (the maker for native SOL)
+/// 3. [writable] vault - The ATA owned by the escrow program that is holding the `mint_a` (the native vault PDA for native SOL)
+/// 4. [writable] escrow - The escrow state account
+/// 5. [] system_program - The system program for native SOL transfers
+/// 6. [] token_program - The token program that owns `mint_a` (SPL Token or Token-2022, the system program for native SOL)
+/// 
+/// ## Data Parameters:
+/// 0. [u8; 8] amount_b - The amount of mint_b asked for the whole remaining amount_a after the update (u64)
+/// 1. [u8; 8] deposit_a - The amount of mint_a to add to the vault (u64)
+/// 2. [u8; 8] withdraw_a - The amount of mint_a to take back from the vault (u64)
+/// 3. [u8; 8] amount_b - For every payment alternative of the escrow, in order, the amount of its mint asked for the whole remaining amount_a (u64)
+pub fn update(accounts: &[AccountView], instruction_data: &[u8]) -> ProgramResult {
+
+    // Unpack accounts - Validate expected accounts
+    let [maker, mint_a, maker_ata, vault, escrow, _system_program, token_program, _remaining @..] = accounts else {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    };
+
+    // Check if maker is signer
+    if !maker.is_signer() {
+        return Err(EscrowError::MissingSigner.into());
+    }
+
+    // Check if the token program is supported and owns mint_a
+    check_token_program(mint_a, token_program)?;
+    let native_a = is_native_mint(mint_a);
+
+    // Validate the mint_a accounts: native SOL moves between the maker and the vault directly, otherwise through its ATA
+    if native_a {
+        if maker_ata.address() != maker.address() {
+            return Err(EscrowError::InvalidTokenAccount.into());
+        }
+    } else {
+        // Validate the ATAs are owned by the token program of mint_a
+        if !maker_ata.owned_by(token_program.address()) || !vault.owned_by(token_program.address()) {
+            return Err(EscrowError::InvalidTokenAccount.into());
+        }
+
+        // Validate the maker ATA mint and authority
+        if TokenAccount::from_account_view(maker_ata)?.owner() != maker.address() {
+            return Err(EscrowError::TokenAccountAuthorityMismatch.into());
+        }
+        if TokenAccount::from_account_view(maker_ata)?.mint() != mint_a.address() {
+            return Err(EscrowError::TokenAccountMintMismatch.into());
+        }
+
+        // Validate the vault is the escrow ATA of mint_a, with its mint and authority
+        check_token_vault(vault, escrow, mint_a, token_program)?;
+        if TokenAccount::from_account_view(vault)?.owner() != escrow.address() {
+            return Err(EscrowError::TokenAccountAuthorityMismatch.into());
+        }
+        if TokenAccount::from_account_view(vault)?.mint() != mint_a.address() {
+            return Err(EscrowError::TokenAccountMintMismatch.into());
+        }
+    }
+
+    // Validate escrow PDA (derive expected PDA and verify it matches provided address)
+    let escrow_account = Escrow::from_account_info_mut(escrow)?;
+    let escrow_seeds = [(b"escrow"), maker.address().as_ref(), escrow_account.seed(), escrow_account.bump.as_slice()];
+    let escrow_pda = pinocchio_pubkey::derive_address_const(&escrow_seeds, None, crate::ID.as_array());
+    if escrow_pda != escrow.address().to_bytes() {
+        return Err(EscrowError::InvalidEscrowAddress.into());
+    }
+
+    // Validate the maker and mint_a are the ones stored in the escrow
+    if maker.address() != &escrow_account.maker() {
+        return Err(EscrowError::MakerMismatch.into());
+    }
+    if mint_a.address() != &escrow_account.mint_a() {
+        return Err(EscrowError::MintMismatch.into());
+    }
+
+    // Validate the native vault belongs to this escrow
+    if native_a {
+        check_native_vault(vault, escrow, escrow_account.vault_bump)?;
+    }
+
+    // An auction keeps the terms it was made with, it can only be refunded (or settled)
+    if escrow_account.is_dutch_auction() || escrow_account.is_english_auction() {
+        return Err(EscrowError::InvalidAuctionTerms.into());
+    }
+
+    // A hashlocked escrow keeps the deal the counterparty locked the other leg of the swap for
+    if escrow_account.is_hashlocked() {
+        return Err(EscrowError::HashlockedEscrow.into());
+    }
+
+    // A milestone escrow keeps the milestones the payee and the arbiter agreed to
+    if escrow_account.is_milestone_escrow() {
+        return Err(EscrowError::MilestoneEscrow.into());
+    }
+
+    // An arbitrated escrow keeps the deal the seller and the arbiter agreed to
+    if escrow_account.is_arbitrated() {
+        return Err(EscrowError::ArbitratedEscrow.into());
+    }
+
+    // An approval escrow keeps the deal its approvers are approving
+    if escrow_account.is_approval_escrow() {
+        return Err(EscrowError::ApprovalEscrow.into());
+    }
+
+    // Validate data parameters: every payment alternative is repriced along with amount_b
+    if instruction_data.len() != 24 + 8 * escrow_account.alternatives().len() {
+        return Err(EscrowError::InvalidInstructionData.into());
+    }
+
+    // Unpack data
+    let amount_b = u64::from_le_bytes(instruction_data[0..8].try_into().unwrap());
+    let deposit_a = u64::from_le_bytes(instruction_data[8..16].try_into().unwrap());
+    let withdraw_a = u64::from_le_bytes(instruction_data[16..24].try_into().unwrap());
+
+    // Only one direction per update
+    if deposit_a != 0 && withdraw_a != 0 {
+        return Err(EscrowError::InvalidInstructionData.into());
+    }
+
+    // Compute the new remaining amount; withdrawing everything is a refund
+    let remaining_a = escrow_account.remaining_a()
+        .checked_add(deposit_a)
+        .ok_or(EscrowError::MathOverflow)?
+        .checked_sub(withdraw_a)
+        .ok_or(EscrowError::FillExceedsRemaining)?;
+    if remaining_a == 0 || amount_b == 0 || instruction_data[24..].chunks_exact(8).any(|amount| amount == [0u8; 8]) {
+        return Err(EscrowError::ZeroAmount.into());
+    }
+
+    // Move mint_a between the maker and the vault
+    if deposit_a > 0 {
+        if native_a {
+            Transfer {
+                from: maker,
+                to: vault,
+                lamports: deposit_a,
+            }.invoke()?;
+        } else {
+            TransferChecked {
+                from: maker_ata,
+                mint: mint_a,
+                to: vault,
+                authority: maker,
+                amount: deposit_a,
+                decimals: Mint::from_account_view(mint_a)?.decimals(),
+                token_program: token_program.address(),
+            }.invoke()?;
+        }
+    }
+    if withdraw_a > 0 {
+        if native_a {
+            withdraw_native(vault, maker_ata, withdraw_a)?;
+        } else {
+            let signer_seeds = [Seed::from(b"escrow"), Seed::from(maker.address().as_ref()), Seed::from(escrow_account.seed()), Seed::from(escrow_account.bump.as_ref())];
+            let signers = Signer::from(&signer_seeds);
+            TransferChecked {
+                from: vault,
+                mint: mint_a,
+                to: maker_ata,
+                authority: escrow,
+                amount: withdraw_a,
+                decimals: Mint::from_account_view(mint_a)?.decimals(),
+                token_program: token_program.address(),
+            }.invoke_signed(&[signers])?;
+        }
+    }
+
+    // Store the new terms, the ratio now applies to what is left in the vault
+    escrow_account.set_terms(remaining_a, amount_b);
+    for (alternative, amount) in escrow_account.alternatives.iter_mut().zip(instruction_data[24..].chunks_exact(8)) {
+        alternative.amount_b = amount.try_into().unwrap();
+    }
+
+    Ok(())
+}
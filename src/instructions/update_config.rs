@@ -0,0 +1,66 @@
+use pinocchio::{
+    AccountView, ProgramResult, error::ProgramError,
+
+};
+
+use crate::{errors::EscrowError, state::Config};
+
+/// # Update Config Instruction
+/// 
+/// This function allows the config admin to change the protocol fee, the treasury, the price feed program and the admin.
+/// Open escrows keep the fee rate snapshotted when they were made.
+/// 
+/// ## Business Logic:
+/// 1. Validate all accounts and instruction data
+/// 2. Verify the signer is the admin stored in the config
+/// 3. Store the new fee, treasury, price feed program and admin
+/// 
+/// ## Accounts Expected:
+/// 0. [signer] admin - The current config admin
+/// 1. [writable] config - The config account
+/// 
+/// ## Data Parameters:
+/// 0. [u8; 2] fee_bps - The new protocol fee, in basis points (u16)
+/// 1. [u8; 32] treasury - The new owner of the token accounts receiving the protocol fee
+/// 2. [u8; 32] new_admin - The new config admin
+/// 3. [u8; 32] price_feed_program - The new program trusted to own the `PriceFeed` accounts of oracle-priced escrows
+pub fn update_config(accounts: &[AccountView], instruction_data: &[u8]) -> ProgramResult {
+
+    // Unpack accounts - Validate expected accounts
+    let [admin, config, _remaining @..] = accounts else {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    };
+
+    // Check if admin is signer
+    if !admin.is_signer() {
+        return Err(EscrowError::MissingSigner.into());
+    }
+
+    // Validate data parameters
+    if instruction_data.len() != 98 {
+        return Err(EscrowError::InvalidInstructionData.into());
+    }
+
+    // Unpack data
+    let fee_bps = u16::from_le_bytes(instruction_data[0..2].try_into().unwrap());
+    let treasury: [u8; 32] = instruction_data[2..34].try_into().unwrap();
+    let new_admin: [u8; 32] = instruction_data[34..66].try_into().unwrap();
+    let price_feed_program: [u8; 32] = instruction_data[66..98].try_into().unwrap();
+
+    // Validate the fee
+    if fee_bps > Config::MAX_FEE_BPS {
+        return Err(EscrowError::InvalidFee.into());
+    }
+
+    // Validate the config and its admin
+    let config_account = Config::from_account_info(config)?;
+    if config_account.admin != admin.address().to_bytes() {
+        return Err(EscrowError::Unauthorized.into());
+    }
+
+    // Store the new settings
+    let config_account = Config::from_account_info_mut(config)?;
+    config_account.set_inner(new_admin, treasury, price_feed_program, fee_bps.to_le_bytes(), config_account.bump);
+
+    Ok(())
+}
\ No newline at end of file
@@ -4,39 +4,69 @@
 // nostd_panic_handler!();
 
 use pinocchio::address::declare_id;
-use pinocchio::error::ProgramError;
 use pinocchio::{
   AccountView,
   Address,
   entrypoint,
   ProgramResult,
 };
-use solana_program_log::log;
 
+pub mod errors;
 mod state;
 mod instructions;
-use instructions::{make, take, refund};
+mod token_interface;
+mod events;
+mod oracle;
+#[cfg(any(test, feature = "client"))]
+pub mod client;
+use instructions::{make, make_v2, make_wide, make_oracle, make_dutch, make_auction, bid, settle_auction, make_vesting, claim, make_arbiter, release, dispute_resolve, make_approval, approve, revoke_approval, make_milestone, release_milestone, cancel, make_hashlock, take, refund, close_expired, init_config, update_config, update, migrate, make_basket, take_basket, refund_basket, emit_event};
 
-use crate::instructions::EscrowInstructions;
+use crate::{errors::EscrowError, instructions::EscrowInstructions};
 
 declare_id!("4ibrEMW5F6hKnkW4jVedswYv6H6VtwPN6ar6dvXDN1nT");
 
 entrypoint!(process_instruction);
 
 pub fn process_instruction(
-  program_id: &Address,
+  _program_id: &Address,
   accounts: &[AccountView],
   instruction_data: &[u8],
 ) -> ProgramResult {
 
-  log!("Hello from my escrow pinocchio program!");
-
-  let (discriminator, data) = instruction_data.split_first().ok_or(ProgramError::InvalidInstructionData)?;
+  let (discriminator, data) = instruction_data.split_first().ok_or(EscrowError::InvalidInstruction)?;
   
   match EscrowInstructions::try_from(discriminator)? {
     EscrowInstructions::MAKE => make(accounts, data)?,
     EscrowInstructions::TAKE => take(accounts, data)?,
     EscrowInstructions::REFUND => refund(accounts, data)?,
+    EscrowInstructions::CLOSE_EXPIRED => close_expired(accounts, data)?,
+    EscrowInstructions::INIT_CONFIG => init_config(accounts, data)?,
+    EscrowInstructions::UPDATE_CONFIG => update_config(accounts, data)?,
+    EscrowInstructions::UPDATE => update(accounts, data)?,
+    EscrowInstructions::MIGRATE => migrate(accounts, data)?,
+    EscrowInstructions::MAKE_V2 => make_v2(accounts, data)?,
+    EscrowInstructions::MAKE_WIDE => make_wide(accounts, data)?,
+    EscrowInstructions::MAKE_BASKET => make_basket(accounts, data)?,
+    EscrowInstructions::TAKE_BASKET => take_basket(accounts, data)?,
+    EscrowInstructions::REFUND_BASKET => refund_basket(accounts, data)?,
+    EscrowInstructions::MAKE_ORACLE => make_oracle(accounts, data)?,
+    EscrowInstructions::MAKE_DUTCH => make_dutch(accounts, data)?,
+    EscrowInstructions::MAKE_AUCTION => make_auction(accounts, data)?,
+    EscrowInstructions::BID => bid(accounts, data)?,
+    EscrowInstructions::SETTLE_AUCTION => settle_auction(accounts, data)?,
+    EscrowInstructions::MAKE_VESTING => make_vesting(accounts, data)?,
+    EscrowInstructions::CLAIM => claim(accounts, data)?,
+    EscrowInstructions::MAKE_ARBITER => make_arbiter(accounts, data)?,
+    EscrowInstructions::RELEASE => release(accounts, data)?,
+    EscrowInstructions::DISPUTE_RESOLVE => dispute_resolve(accounts, data)?,
+    EscrowInstructions::MAKE_APPROVAL => make_approval(accounts, data)?,
+    EscrowInstructions::APPROVE => approve(accounts, data)?,
+    EscrowInstructions::REVOKE_APPROVAL => revoke_approval(accounts, data)?,
+    EscrowInstructions::MAKE_MILESTONE => make_milestone(accounts, data)?,
+    EscrowInstructions::RELEASE_MILESTONE => release_milestone(accounts, data)?,
+    EscrowInstructions::CANCEL => cancel(accounts, data)?,
+    EscrowInstructions::MAKE_HASHLOCK => make_hashlock(accounts, data)?,
+    EscrowInstructions::EMIT_EVENT => emit_event(accounts, data)?,
   }
 
   Ok(())
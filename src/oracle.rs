@@ -0,0 +1,110 @@
+use pinocchio::{AccountView, Address, error::ProgramError};
+
+use crate::errors::EscrowError;
+
+/// Discriminator of a `PriceFeed` account, `sha256("account:PriceFeed")[..8]`
+pub const PRICE_FEED_DISCRIMINATOR: [u8; 8] = [189, 103, 252, 23, 152, 35, 243, 156];
+
+/// Size of a `PriceFeed` account
+pub const PRICE_FEED_LEN: usize = 36;
+
+/// The Pyth Solana receiver program, owning the `PriceUpdateV2` accounts
+#[cfg(feature = "pyth")]
+pub const PYTH_RECEIVER_ID: pinocchio::Address = pinocchio::Address::new_from_array(five8_const::decode_32_const("rec5EKMGg6MxZYaMdyBfgwp4d5rB9T1VQH5pJv5LtFJ"));
+
+/// Discriminator of a Pyth `PriceUpdateV2` account, `sha256("account:PriceUpdateV2")[..8]`
+#[cfg(feature = "pyth")]
+const PRICE_UPDATE_V2_DISCRIMINATOR: [u8; 8] = [34, 241, 35, 99, 157, 126, 244, 205];
+
+/// Offset of the price message in a fully verified `PriceUpdateV2`, after the discriminator, the write authority
+/// and the verification level, followed by the 32-byte feed id
+#[cfg(feature = "pyth")]
+const PRICE_UPDATE_V2_MESSAGE_OFFSET: usize = 41;
+
+/// A price of one whole `mint_a` in whole `mint_b`, `price * 10^expo`, with its confidence interval in the same units
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Price {
+    pub price: i64,
+    pub conf: u64,
+    pub expo: i32,
+    pub publish_time: i64,
+}
+
+impl Price {
+    /// Reads the price from a feed account: a `PriceFeed` account owned by the trusted `price_feed_program`, laid out as
+    /// `discriminator (8) || price (i64) || conf (u64) || expo (i32) || publish_time (i64)` in little-endian,
+    /// or with the `pyth` feature a fully verified Pyth `PriceUpdateV2` account owned by the Pyth receiver
+    pub fn from_account_view(feed: &AccountView, price_feed_program: &Address) -> Result<Self, ProgramError> {
+        let data = feed.try_borrow()?;
+
+        #[cfg(feature = "pyth")]
+        if feed.owned_by(&PYTH_RECEIVER_ID) {
+            // Only a fully verified update (verification level 1) has its price message at a fixed offset
+            if data.len() < PRICE_UPDATE_V2_MESSAGE_OFFSET + 60 || data[..8] != PRICE_UPDATE_V2_DISCRIMINATOR || data[40] != 1 {
+                return Err(EscrowError::InvalidPriceFeed.into());
+            }
+            return Ok(Self::from_fields(&data[PRICE_UPDATE_V2_MESSAGE_OFFSET + 32..]));
+        }
+
+        if !feed.owned_by(price_feed_program) || data.len() < PRICE_FEED_LEN || data[..8] != PRICE_FEED_DISCRIMINATOR {
+            return Err(EscrowError::InvalidPriceFeed.into());
+        }
+        Ok(Self::from_fields(&data[8..]))
+    }
+
+    /// What an escrow records to find its feed again: the feed id of a Pyth `PriceUpdateV2`, which anyone can post
+    /// to a new account, otherwise the address of the `PriceFeed` account. The feed must have been read first
+    pub fn feed_id(feed: &AccountView) -> Result<Address, ProgramError> {
+        #[cfg(feature = "pyth")]
+        if feed.owned_by(&PYTH_RECEIVER_ID) {
+            let data = feed.try_borrow()?;
+            return Ok(Address::new_from_array(data[PRICE_UPDATE_V2_MESSAGE_OFFSET..PRICE_UPDATE_V2_MESSAGE_OFFSET + 32].try_into().unwrap()));
+        }
+
+        Ok(*feed.address())
+    }
+
+    /// Decodes `price || conf || expo || publish_time`, the order shared by both layouts
+    fn from_fields(data: &[u8]) -> Self {
+        Self {
+            price: i64::from_le_bytes(data[0..8].try_into().unwrap()),
+            conf: u64::from_le_bytes(data[8..16].try_into().unwrap()),
+            expo: i32::from_le_bytes(data[16..20].try_into().unwrap()),
+            publish_time: i64::from_le_bytes(data[20..28].try_into().unwrap()),
+        }
+    }
+
+    /// Rejects a non-positive price, one published more than `max_age` seconds before `now`,
+    /// or one whose confidence interval is wider than `max_confidence_bps` of the price
+    pub fn check(&self, now: i64, max_age: u32, max_confidence_bps: u16) -> Result<(), EscrowError> {
+        if self.price <= 0 {
+            return Err(EscrowError::InvalidPriceFeed);
+        }
+        if now.saturating_sub(self.publish_time) > max_age as i64 {
+            return Err(EscrowError::StalePrice);
+        }
+        if self.conf as u128 * 10_000 > self.price as u128 * max_confidence_bps as u128 {
+            return Err(EscrowError::PriceConfidenceTooWide);
+        }
+        Ok(())
+    }
+
+    /// Amount of `mint_b` (in its base units) worth `amount_a` of `mint_a` (in its base units) at this price,
+    /// moved by `premium_bps` (a discount when negative) and rounded up in favour of the maker
+    pub fn amount_b_for(&self, amount_a: u64, decimals_a: u8, decimals_b: u8, premium_bps: i16) -> Option<u64> {
+        let multiplier = u128::try_from(10_000 + premium_bps as i64).ok()?;
+        let value = (amount_a as u128)
+            .checked_mul(u128::try_from(self.price).ok()?)?
+            .checked_mul(multiplier)?;
+
+        // Scale from the price exponent and the mint_a base units to the mint_b base units
+        let exponent = self.expo as i64 + decimals_b as i64 - decimals_a as i64;
+        let scale = 10u128.checked_pow(u32::try_from(exponent.unsigned_abs()).ok()?)?;
+        let amount_b = if exponent >= 0 {
+            value.checked_mul(scale)?.div_ceil(10_000)
+        } else {
+            value.div_ceil(scale.checked_mul(10_000)?)
+        };
+        u64::try_from(amount_b).ok()
+    }
+}
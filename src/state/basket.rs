@@ -0,0 +1,133 @@
+use pinocchio::{AccountView, Address};
+use shank::{ShankAccount, ShankType};
+
+use crate::errors::EscrowError;
+
+/// The most input mints a basket escrow can hold, the length of `Basket::legs`
+pub const MAX_BASKET_LEGS: usize = 4;
+
+/// One input mint of a basket, held in its own vault
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default, PartialEq, ShankType)]
+pub struct BasketLeg {
+    pub mint_a: [u8; 32],
+    pub amount_a: [u8; 8],
+}
+
+impl BasketLeg {
+    pub fn mint_a(&self) -> Address {
+        Address::new_from_array(self.mint_a)
+    }
+
+    pub fn amount_a(&self) -> u64 {
+        u64::from_le_bytes(self.amount_a)
+    }
+}
+
+/// A basket escrow: up to `MAX_BASKET_LEGS` input mints, each with its own amount and vault, taken as a whole for `amount_b` of `mint_b`
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default, PartialEq, ShankAccount)]
+pub struct Basket {
+    pub discriminator: [u8; 1],
+    pub version: [u8; 1],
+    pub maker: [u8; 32],
+    pub mint_b: [u8; 32],
+    pub amount_b: [u8; 8],
+    pub expires_at: [u8; 8],
+    pub fee_bps: [u8; 2],
+    pub seed: [u8; 8],
+    pub bump: [u8; 1],
+    pub leg_count: [u8; 1],
+    pub legs: [BasketLeg; 4],
+}
+impl Basket {
+    pub const LEN: usize = 254;
+
+    /// First byte of every basket account, telling it apart from the escrows
+    pub const DISCRIMINATOR: u8 = 2;
+
+    pub const VERSION: u8 = 1;
+
+    /// Load a basket, validating the discriminator and version header
+    #[allow(clippy::mut_from_ref)]
+    pub fn from_account_info_mut(account_info: &AccountView) -> Result<&mut Self, pinocchio::error::ProgramError> {
+        let mut data = account_info.try_borrow_mut()?;
+
+        if data.len() != Basket::LEN || data[0] != Self::DISCRIMINATOR || data[1] != Self::VERSION {
+            return Err(EscrowError::InvalidEscrowData.into());
+        }
+
+        Ok(unsafe { &mut *(data.as_mut_ptr() as *mut Self)})
+    }
+
+    /// Load a freshly created basket account, before `set_inner` writes its header
+    #[allow(clippy::mut_from_ref)]
+    pub fn from_account_info_mut_uninitialized(account_info: &AccountView) -> Result<&mut Self, pinocchio::error::ProgramError> {
+        let mut data = account_info.try_borrow_mut()?;
+
+        if data.len() != Basket::LEN || data[0] != 0 {
+            return Err(EscrowError::InvalidEscrowData.into());
+        }
+
+        Ok(unsafe { &mut *(data.as_mut_ptr() as *mut Self)})
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    pub fn set_inner(&mut self, maker: [u8; 32], mint_b: [u8; 32], amount_b: [u8; 8], expires_at: [u8; 8], fee_bps: [u8; 2], seed: [u8; 8], bump: [u8; 1]) {
+        self.discriminator = [Self::DISCRIMINATOR];
+        self.version = [Self::VERSION];
+        self.maker = maker;
+        self.mint_b = mint_b;
+        self.amount_b = amount_b;
+        self.expires_at = expires_at;
+        self.fee_bps = fee_bps;
+        self.seed = seed;
+        self.bump = bump;
+        self.leg_count = [0];
+    }
+
+    /// Appends a leg, the legs being taken and refunded in the order they were added
+    pub fn push_leg(&mut self, mint_a: [u8; 32], amount_a: u64) -> Result<(), EscrowError> {
+        let index = self.leg_count[0] as usize;
+        if index == MAX_BASKET_LEGS || self.legs().iter().any(|leg| leg.mint_a == mint_a) {
+            return Err(EscrowError::InvalidBasketLegs);
+        }
+        self.legs[index] = BasketLeg { mint_a, amount_a: amount_a.to_le_bytes() };
+        self.leg_count = [index as u8 + 1];
+        Ok(())
+    }
+
+    /// The legs in use
+    pub fn legs(&self) -> &[BasketLeg] {
+        &self.legs[..self.leg_count[0] as usize]
+    }
+
+    pub fn maker(&self) -> Address {
+        Address::new_from_array(self.maker)
+    }
+
+    pub fn mint_b(&self) -> Address {
+        Address::new_from_array(self.mint_b)
+    }
+
+    pub fn amount_b(&self) -> u64 {
+        u64::from_le_bytes(self.amount_b)
+    }
+
+    pub fn seed_value(&self) -> u64 {
+        u64::from_le_bytes(self.seed)
+    }
+
+    /// Protocol fee skimmed from `amount_b` at the rate snapshotted in `make_basket`. Rounds down in favour of the maker.
+    pub fn fee_for(&self, amount_b: u64) -> u64 {
+        let fee_bps = u16::from_le_bytes(self.fee_bps) as u128;
+        (amount_b as u128 * fee_bps / 10_000) as u64
+    }
+
+    /// Whether the basket has an expiry timestamp (0 means it never expires) that is at or before `now`.
+    pub fn is_expired(&self, now: i64) -> bool {
+        let expires_at = i64::from_le_bytes(self.expires_at);
+        expires_at != 0 && now >= expires_at
+    }
+
+}
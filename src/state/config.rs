@@ -0,0 +1,74 @@
+use pinocchio::{AccountView};
+use shank::ShankAccount;
+
+use crate::errors::EscrowError;
+
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default, PartialEq, ShankAccount)]
+pub struct Config {
+    pub discriminator: [u8; 1],
+    pub admin: [u8; 32],
+    pub treasury: [u8; 32],
+    pub price_feed_program: [u8; 32],
+    pub fee_bps: [u8; 2],
+    pub bump: [u8; 1],
+}
+impl Config {
+    pub const LEN: usize = 100;
+
+    /// First byte of the config account, telling it apart from the escrows, baskets and vesting accounts
+    pub const DISCRIMINATOR: u8 = 4;
+
+    /// The maximum protocol fee, in basis points (10%)
+    pub const MAX_FEE_BPS: u16 = 1_000;
+
+    /// Load the config, validating the discriminator
+    #[allow(clippy::mut_from_ref)]
+    pub fn from_account_info_mut(account_info: &AccountView) -> Result<&mut Self, pinocchio::error::ProgramError> {
+        let mut data = account_info.try_borrow_mut()?;
+
+        if data.len() != Config::LEN || data[0] != Self::DISCRIMINATOR {
+            return Err(EscrowError::InvalidConfig.into());
+        }
+
+        Ok(unsafe { &mut *(data.as_mut_ptr() as *mut Self)})
+    }
+
+    /// Load the freshly created config, before `set_inner` writes its discriminator
+    #[allow(clippy::mut_from_ref)]
+    pub fn from_account_info_mut_uninitialized(account_info: &AccountView) -> Result<&mut Self, pinocchio::error::ProgramError> {
+        let mut data = account_info.try_borrow_mut()?;
+
+        if data.len() != Config::LEN || data[0] != 0 {
+            return Err(EscrowError::InvalidConfig.into());
+        }
+
+        Ok(unsafe { &mut *(data.as_mut_ptr() as *mut Self)})
+    }
+
+    /// Loads the global config, checking it is owned by this program and lives at the config PDA
+    pub fn from_account_info(account_info: &AccountView) -> Result<&Self, pinocchio::error::ProgramError> {
+        if !account_info.owned_by(&crate::ID) {
+            return Err(EscrowError::InvalidConfig.into());
+        }
+
+        let config = Self::from_account_info_mut(account_info)?;
+        let config_seeds = [(b"config").as_slice(), config.bump.as_slice()];
+        let config_pda = pinocchio_pubkey::derive_address_const(&config_seeds, None, crate::ID.as_array());
+        if config_pda != account_info.address().to_bytes() {
+            return Err(EscrowError::InvalidConfig.into());
+        }
+
+        Ok(config)
+    }
+
+    pub fn set_inner(&mut self, admin: [u8; 32], treasury: [u8; 32], price_feed_program: [u8; 32], fee_bps: [u8; 2], bump: [u8; 1]) {
+        self.discriminator = [Self::DISCRIMINATOR];
+        self.admin = admin;
+        self.treasury = treasury;
+        self.price_feed_program = price_feed_program;
+        self.fee_bps = fee_bps;
+        self.bump = bump;
+    }
+
+}
\ No newline at end of file
@@ -1,32 +1,616 @@
-use pinocchio::{AccountView};
-use shank::ShankAccount;
+use pinocchio::{AccountView, Address};
+use shank::{ShankAccount, ShankType};
+
+use crate::errors::EscrowError;
+
+/// Escrow account layouts, from the unversioned ones (told apart by their size) to the versioned ones (told apart by their header)
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum EscrowLayout {
+    /// mint_b, amount_b, seed, bump
+    Unversioned42,
+    /// mint_b, amount_b, amount_a, remaining_a, expires_at, fee_bps, taker, seed, bump, vault_bump
+    Unversioned101,
+    /// discriminator and version header, then the fields of `Unversioned101`
+    V1,
+    /// discriminator and version header, maker, mint_a, then the fields of `Unversioned101`
+    V2,
+    /// same as `V2` with the seed widened to 8 bytes, followed by its length (1 or 8)
+    V3,
+    /// `V3` followed by the payment alternatives
+    V4,
+    /// `V4` followed by the oracle pricing terms
+    V5,
+    /// `V5` followed by the Dutch auction terms
+    V6,
+    /// `V6` followed by the English auction terms and its highest bid
+    V7,
+    /// `V7` followed by the vesting schedule
+    V8,
+    /// `V8` followed by the arbiter
+    V9,
+    /// `V9` followed by the approvers, the approval threshold and the approvals
+    V10,
+    /// `V10` followed by the milestones
+    V11,
+    /// `V11` followed by the hashlock
+    V12,
+    /// `V12` followed by the payer of the bid vault rent
+    V13,
+}
+
+impl EscrowLayout {
+    /// Layout of the escrow account data, if it is one
+    pub fn of(data: &[u8]) -> Option<Self> {
+        if data.len() >= 2 && data[0] == Escrow::DISCRIMINATOR {
+            match (data[1], data.len()) {
+                (1, 103) => return Some(Self::V1),
+                (2, 167) => return Some(Self::V2),
+                (3, 175) if matches!(data[172], 1 | 8) => return Some(Self::V3),
+                (4, 296) if matches!(data[172], 1 | 8) => return Some(Self::V4),
+                (5, 336) if matches!(data[172], 1 | 8) => return Some(Self::V5),
+                (6, 360) if matches!(data[172], 1 | 8) => return Some(Self::V6),
+                (7, 416) if matches!(data[172], 1 | 8) => return Some(Self::V7),
+                (8, 432) if matches!(data[172], 1 | 8) => return Some(Self::V8),
+                (9, 464) if matches!(data[172], 1 | 8) => return Some(Self::V9),
+                (10, 627) if matches!(data[172], 1 | 8) => return Some(Self::V10),
+                (11, 693) if matches!(data[172], 1 | 8) => return Some(Self::V11),
+                (12, 725) if matches!(data[172], 1 | 8) => return Some(Self::V12),
+                (13, 757) if matches!(data[172], 1 | 8) => return Some(Self::V13),
+                _ => {}
+            }
+        }
+        match data.len() {
+            42 => Some(Self::Unversioned42),
+            101 => Some(Self::Unversioned101),
+            _ => None,
+        }
+    }
+}
+
+/// The most payment alternatives an escrow can accept on top of `mint_b`, the length of `Escrow::alternatives`
+pub const MAX_PAYMENT_ALTERNATIVES: usize = 3;
+
+/// The most approvers an escrow released by approvals can register, the length of `Escrow::approvers`
+pub const MAX_APPROVERS: usize = 5;
+
+/// The most milestones a milestone escrow can be split into, the length of `Escrow::milestones`
+pub const MAX_MILESTONES: usize = 8;
+
+/// Length of the preimage the taker of a hashlocked escrow reveals, hashed with SHA-256 into `Escrow::hashlock`
+pub const PREIMAGE_LEN: usize = 32;
+
+/// A payment of a milestone escrow, released to the payee once the work it pays for is done
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default, PartialEq, ShankType)]
+pub struct Milestone {
+    pub amount_a: [u8; 8],
+}
+
+impl Milestone {
+    pub fn amount_a(&self) -> u64 {
+        u64::from_le_bytes(self.amount_a)
+    }
+}
+
+/// A wallet whose approval counts towards releasing an escrow released by approvals
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default, PartialEq, ShankType)]
+pub struct Approver {
+    pub address: [u8; 32],
+}
+
+/// Another mint the maker accepts instead of `mint_b`, with the amount asked for the whole of `amount_a`
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default, PartialEq, ShankType)]
+pub struct PaymentAlternative {
+    pub mint_b: [u8; 32],
+    pub amount_b: [u8; 8],
+}
+
+impl PaymentAlternative {
+    pub fn mint_b(&self) -> Address {
+        Address::new_from_array(self.mint_b)
+    }
+
+    pub fn amount_b(&self) -> u64 {
+        u64::from_le_bytes(self.amount_b)
+    }
+}
 
 #[repr(C)]
 #[derive(Clone, Copy, Debug, Default, PartialEq, ShankAccount)]
 pub struct Escrow {
+    pub discriminator: [u8; 1],
+    pub version: [u8; 1],
+    pub maker: [u8; 32],
+    pub mint_a: [u8; 32],
     pub mint_b: [u8; 32],
     pub amount_b: [u8; 8],
-    pub seed: [u8; 1],
+    pub amount_a: [u8; 8],
+    pub remaining_a: [u8; 8],
+    pub expires_at: [u8; 8],
+    pub fee_bps: [u8; 2],
+    pub taker: [u8; 32],
+    pub seed: [u8; 8],
+    pub seed_len: [u8; 1],
     pub bump: [u8; 1],
+    pub vault_bump: [u8; 1],
+    pub alternative_count: [u8; 1],
+    pub alternatives: [PaymentAlternative; 3],
+    pub price_feed: [u8; 32],
+    pub premium_bps: [u8; 2],
+    pub max_price_age: [u8; 4],
+    pub max_confidence_bps: [u8; 2],
+    pub end_amount_b: [u8; 8],
+    pub auction_start: [u8; 8],
+    pub auction_end: [u8; 8],
+    pub min_increment: [u8; 8],
+    pub bidding_end: [u8; 8],
+    pub highest_bidder: [u8; 32],
+    pub highest_bid: [u8; 8],
+    pub vesting_cliff: [u8; 8],
+    pub vesting_duration: [u8; 8],
+    pub arbiter: [u8; 32],
+    pub approvers: [Approver; 5],
+    pub approver_count: [u8; 1],
+    pub approval_threshold: [u8; 1],
+    pub approvals: [u8; 1],
+    pub milestones: [Milestone; 8],
+    pub milestone_count: [u8; 1],
+    pub released_milestones: [u8; 1],
+    pub hashlock: [u8; 32],
+    pub bid_vault_payer: [u8; 32],
 }
 impl Escrow {
-    pub const LEN: usize = 42;
+    pub const LEN: usize = 757;
+
+    /// First byte of every escrow account, telling it apart from the other accounts of the program
+    pub const DISCRIMINATOR: u8 = 1;
+
+    /// Current layout version, older versions must go through MIGRATE first
+    pub const VERSION: u8 = 13;
 
+    /// Load an escrow with the current layout, validating the discriminator and version header
+    #[allow(clippy::mut_from_ref)]
     pub fn from_account_info_mut(account_info: &AccountView) -> Result<&mut Self, pinocchio::error::ProgramError> {
         let mut data = account_info.try_borrow_mut()?;
 
-        if data.len() != Escrow::LEN {
-            return Err(pinocchio::error::ProgramError::InvalidAccountData);
+        match EscrowLayout::of(&data) {
+            Some(EscrowLayout::V13) => Ok(unsafe { &mut *(data.as_mut_ptr() as *mut Self)}),
+            Some(_) => Err(EscrowError::OutdatedEscrowVersion.into()),
+            None => Err(EscrowError::InvalidEscrowData.into()),
+        }
+    }
+
+    /// Load a freshly created escrow account, before `set_inner` writes its header
+    #[allow(clippy::mut_from_ref)]
+    pub fn from_account_info_mut_uninitialized(account_info: &AccountView) -> Result<&mut Self, pinocchio::error::ProgramError> {
+        let mut data = account_info.try_borrow_mut()?;
+
+        if data.len() != Escrow::LEN || data[0] != 0 {
+            return Err(EscrowError::InvalidEscrowData.into());
         }
 
         Ok(unsafe { &mut *(data.as_mut_ptr() as *mut Self)})
     }
 
-    pub fn set_inner(&mut self, mint_b: [u8; 32], amount_b: [u8; 8], seed: [u8; 1], bump: [u8;1]) {
+    #[allow(clippy::too_many_arguments)]
+    pub fn set_inner(&mut self, maker: [u8; 32], mint_a: [u8; 32], mint_b: [u8; 32], amount_b: [u8; 8], amount_a: [u8; 8], expires_at: [u8; 8], fee_bps: [u8; 2], taker: [u8; 32], seed: &[u8], bump: [u8;1], vault_bump: [u8; 1]) {
+        self.discriminator = [Self::DISCRIMINATOR];
+        self.version = [Self::VERSION];
+        self.maker = maker;
+        self.mint_a = mint_a;
         self.mint_b = mint_b;
         self.amount_b = amount_b;
-        self.seed = seed;
+        self.amount_a = amount_a;
+        self.remaining_a = amount_a;
+        self.expires_at = expires_at;
+        self.fee_bps = fee_bps;
+        self.taker = taker;
+        self.seed = [0; 8];
+        self.seed[..seed.len()].copy_from_slice(seed);
+        self.seed_len = [seed.len() as u8];
         self.bump = bump;
+        self.vault_bump = vault_bump;
+        self.alternative_count = [0];
+        self.alternatives = Default::default();
+        self.price_feed = [0; 32];
+        self.premium_bps = [0; 2];
+        self.max_price_age = [0; 4];
+        self.max_confidence_bps = [0; 2];
+        self.end_amount_b = [0; 8];
+        self.auction_start = [0; 8];
+        self.auction_end = [0; 8];
+        self.min_increment = [0; 8];
+        self.bidding_end = [0; 8];
+        self.highest_bidder = [0; 32];
+        self.highest_bid = [0; 8];
+        self.vesting_cliff = [0; 8];
+        self.vesting_duration = [0; 8];
+        self.arbiter = [0; 32];
+        self.approvers = Default::default();
+        self.approver_count = [0];
+        self.approval_threshold = [0];
+        self.approvals = [0];
+        self.milestones = Default::default();
+        self.milestone_count = [0];
+        self.released_milestones = [0];
+        self.hashlock = [0; 32];
+        self.bid_vault_payer = [0; 32];
+    }
+
+    /// Prices `mint_b` from a price feed (see `Price::feed_id`), `amount_b` becoming the least asked for the whole of `amount_a`
+    pub fn set_oracle(&mut self, price_feed: [u8; 32], premium_bps: i16, max_price_age: u32, max_confidence_bps: u16) {
+        self.price_feed = price_feed;
+        self.premium_bps = premium_bps.to_le_bytes();
+        self.max_price_age = max_price_age.to_le_bytes();
+        self.max_confidence_bps = max_confidence_bps.to_le_bytes();
+    }
+
+    /// Sells by Dutch auction: the `amount_b` asked decays linearly to `end_amount_b` between `auction_start` and `auction_end`
+    pub fn set_dutch_auction(&mut self, end_amount_b: u64, auction_start: i64, auction_end: i64) {
+        self.end_amount_b = end_amount_b.to_le_bytes();
+        self.auction_start = auction_start.to_le_bytes();
+        self.auction_end = auction_end.to_le_bytes();
+    }
+
+    /// Whether the escrow is sold by Dutch auction
+    pub fn is_dutch_auction(&self) -> bool {
+        self.auction_end != [0u8; 8]
+    }
+
+    /// Amount of `mint_b` asked for the whole of `amount_a` at `now` in a Dutch auction: `amount_b` until the auction starts,
+    /// then linearly down to `end_amount_b` at its end. Rounds up in favour of the maker.
+    pub fn dutch_amount_b(&self, now: i64) -> u64 {
+        let start_amount_b = self.amount_b();
+        let end_amount_b = u64::from_le_bytes(self.end_amount_b);
+        let auction_start = i64::from_le_bytes(self.auction_start);
+        let auction_end = i64::from_le_bytes(self.auction_end);
+        if now <= auction_start {
+            return start_amount_b;
+        }
+        if now >= auction_end {
+            return end_amount_b;
+        }
+        let left = (auction_end - now) as u128;
+        let duration = (auction_end - auction_start) as u128;
+        let decay = (start_amount_b.saturating_sub(end_amount_b) as u128 * left).div_ceil(duration);
+        end_amount_b + decay as u64
+    }
+
+    /// Sells by English auction: bids of at least `amount_b` (the reserve price), each raising the highest bid by at least
+    /// `min_increment`, are accepted until `bidding_end`
+    pub fn set_english_auction(&mut self, min_increment: u64, bidding_end: i64) {
+        self.min_increment = min_increment.to_le_bytes();
+        self.bidding_end = bidding_end.to_le_bytes();
+    }
+
+    /// Whether the escrow is sold by English auction
+    pub fn is_english_auction(&self) -> bool {
+        self.bidding_end != [0u8; 8]
+    }
+
+    /// Unix timestamp after which the English auction takes no more bids and can be settled
+    pub fn bidding_end(&self) -> i64 {
+        i64::from_le_bytes(self.bidding_end)
+    }
+
+    /// Whether the English auction takes no more bids at `now`
+    pub fn is_bidding_over(&self, now: i64) -> bool {
+        now >= self.bidding_end()
+    }
+
+    /// The bidder holding the highest bid, `None` before the first bid
+    pub fn highest_bidder(&self) -> Option<Address> {
+        (self.highest_bidder != [0u8; 32]).then(|| Address::new_from_array(self.highest_bidder))
+    }
+
+    /// The highest bid locked in the bid vault, 0 before the first bid
+    pub fn highest_bid(&self) -> u64 {
+        u64::from_le_bytes(self.highest_bid)
+    }
+
+    /// The lowest bid accepted: the reserve price for the first bid, then the highest bid raised by `min_increment`
+    pub fn min_bid(&self) -> Option<u64> {
+        match self.highest_bidder() {
+            Some(_) => self.highest_bid().checked_add(u64::from_le_bytes(self.min_increment)),
+            None => Some(self.amount_b()),
+        }
+    }
+
+    pub fn set_highest_bid(&mut self, bidder: [u8; 32], bid: u64) {
+        self.highest_bidder = bidder;
+        self.highest_bid = bid.to_le_bytes();
+    }
+
+    /// Records who paid the rent of the bid vault, the first bidder, to return it when the auction settles
+    pub fn set_bid_vault_payer(&mut self, payer: [u8; 32]) {
+        self.bid_vault_payer = payer;
+    }
+
+    /// Who paid the rent of the bid vault, `None` before the first bid or for an auction migrated from an older layout with bids
+    pub fn bid_vault_payer(&self) -> Option<Address> {
+        (self.bid_vault_payer != [0u8; 32]).then(|| Address::new_from_array(self.bid_vault_payer))
+    }
+
+    /// Delivers `mint_a` through a vesting account: nothing can be claimed for `vesting_cliff` seconds after the take,
+    /// then `mint_a` vests linearly from the take until `vesting_duration` seconds after it
+    pub fn set_vesting(&mut self, vesting_cliff: i64, vesting_duration: i64) {
+        self.vesting_cliff = vesting_cliff.to_le_bytes();
+        self.vesting_duration = vesting_duration.to_le_bytes();
+    }
+
+    /// Whether the taken `mint_a` vests instead of going straight to the taker
+    pub fn is_vesting(&self) -> bool {
+        self.vesting_duration != [0u8; 8]
+    }
+
+    pub fn vesting_cliff(&self) -> i64 {
+        i64::from_le_bytes(self.vesting_cliff)
+    }
+
+    pub fn vesting_duration(&self) -> i64 {
+        i64::from_le_bytes(self.vesting_duration)
+    }
+
+    /// Hands the settlement to the designated taker, the seller, and `arbiter`: the vault is released to the seller
+    /// or back to the maker, the buyer, instead of being taken
+    pub fn set_arbiter(&mut self, arbiter: [u8; 32]) {
+        self.arbiter = arbiter;
+    }
+
+    /// The arbiter resolving disputes between the maker and the seller, or releasing and cancelling milestones, `None` for a swap
+    pub fn arbiter(&self) -> Option<Address> {
+        (self.arbiter != [0u8; 32]).then(|| Address::new_from_array(self.arbiter))
+    }
+
+    /// Whether the escrow is settled by RELEASE or DISPUTE_RESOLVE instead of being taken
+    pub fn is_arbitrated(&self) -> bool {
+        self.arbiter != [0u8; 32]
+    }
+
+    /// Registers an approver of an escrow released by approvals, failing past `MAX_APPROVERS` or for a duplicate
+    pub fn push_approver(&mut self, approver: [u8; 32]) -> Result<(), EscrowError> {
+        let index = self.approver_count[0] as usize;
+        if index == MAX_APPROVERS || approver == [0u8; 32] || self.approvers().iter().any(|registered| registered.address == approver) {
+            return Err(EscrowError::InvalidApprovers);
+        }
+        self.approvers[index] = Approver { address: approver };
+        self.approver_count = [index as u8 + 1];
+        Ok(())
     }
 
-}
\ No newline at end of file
+    /// Releases the vault to the designated taker, the beneficiary, once `threshold` of the approvers have approved
+    pub fn set_approval_threshold(&mut self, threshold: u8) {
+        self.approval_threshold = [threshold];
+    }
+
+    /// The registered approvers
+    pub fn approvers(&self) -> &[Approver] {
+        &self.approvers[..self.approver_count[0] as usize]
+    }
+
+    /// Index of `approver` among the registered approvers, `None` if it is not one
+    pub fn approver_index(&self, approver: &Address) -> Option<usize> {
+        self.approvers().iter().position(|registered| &registered.address == approver.as_array())
+    }
+
+    /// Whether the escrow is released by its approvers instead of being taken
+    pub fn is_approval_escrow(&self) -> bool {
+        self.approval_threshold[0] != 0
+    }
+
+    pub fn approval_threshold(&self) -> u8 {
+        self.approval_threshold[0]
+    }
+
+    /// Whether the approver at `index` has approved
+    pub fn has_approved(&self, index: usize) -> bool {
+        self.approvals[0] & (1 << index) != 0
+    }
+
+    /// Records or withdraws the approval of the approver at `index`
+    pub fn set_approved(&mut self, index: usize, approved: bool) {
+        if approved {
+            self.approvals[0] |= 1 << index;
+        } else {
+            self.approvals[0] &= !(1 << index);
+        }
+    }
+
+    /// Number of approvers that have approved
+    pub fn approval_count(&self) -> u8 {
+        self.approvals[0].count_ones() as u8
+    }
+
+    /// Appends a milestone paying `amount_a` to the designated taker, the payee, failing past `MAX_MILESTONES` or for a zero amount
+    pub fn push_milestone(&mut self, amount_a: u64) -> Result<(), EscrowError> {
+        let index = self.milestone_count[0] as usize;
+        if index == MAX_MILESTONES || amount_a == 0 {
+            return Err(EscrowError::InvalidMilestones);
+        }
+        self.milestones[index] = Milestone { amount_a: amount_a.to_le_bytes() };
+        self.milestone_count = [index as u8 + 1];
+        Ok(())
+    }
+
+    /// The milestones, in the order they are released
+    pub fn milestones(&self) -> &[Milestone] {
+        &self.milestones[..self.milestone_count[0] as usize]
+    }
+
+    /// Whether the vault is paid out milestone by milestone with RELEASE_MILESTONE instead of being taken
+    pub fn is_milestone_escrow(&self) -> bool {
+        self.milestone_count[0] != 0
+    }
+
+    /// Number of milestones released so far, which is also the index of the next one
+    pub fn released_milestones(&self) -> u8 {
+        self.released_milestones[0]
+    }
+
+    /// Records the release of the next milestone, returning its amount, `MilestoneOutOfOrder` once they are all released
+    pub fn release_milestone(&mut self) -> Result<u64, pinocchio::error::ProgramError> {
+        let amount_a = self.milestones().get(self.released_milestones() as usize).ok_or(EscrowError::MilestoneOutOfOrder)?.amount_a();
+        let remaining_a = self.remaining_a().checked_sub(amount_a).ok_or(EscrowError::MathOverflow)?;
+        self.released_milestones = [self.released_milestones() + 1];
+        self.remaining_a = remaining_a.to_le_bytes();
+        Ok(amount_a)
+    }
+
+    /// Whether every milestone has been released
+    pub fn is_fully_released(&self) -> bool {
+        self.released_milestones[0] == self.milestone_count[0]
+    }
+
+    /// Locks the take behind the SHA-256 `hashlock`: the taker must reveal its preimage, `expires_at` being the timeout
+    pub fn set_hashlock(&mut self, hashlock: [u8; 32]) {
+        self.hashlock = hashlock;
+    }
+
+    /// Whether taking the escrow requires the preimage of its hashlock
+    pub fn is_hashlocked(&self) -> bool {
+        self.hashlock != [0u8; 32]
+    }
+
+    /// Whether `preimage` hashes to the hashlock with SHA-256
+    pub fn unlocks(&self, preimage: &[u8]) -> bool {
+        solana_sha256_hasher::hash(preimage).to_bytes() == self.hashlock
+    }
+
+    /// The price feed pricing `mint_b`, its feed id for Pyth, `None` for a fixed price
+    pub fn price_feed(&self) -> Option<Address> {
+        (self.price_feed != [0u8; 32]).then(|| Address::new_from_array(self.price_feed))
+    }
+
+    /// Premium over the feed price in basis points, a discount when negative
+    pub fn premium_bps(&self) -> i16 {
+        i16::from_le_bytes(self.premium_bps)
+    }
+
+    /// The oldest a feed price may be, in seconds
+    pub fn max_price_age(&self) -> u32 {
+        u32::from_le_bytes(self.max_price_age)
+    }
+
+    /// The widest the feed confidence interval may be, in basis points of the price
+    pub fn max_confidence_bps(&self) -> u16 {
+        u16::from_le_bytes(self.max_confidence_bps)
+    }
+
+    /// Accepts `amount_b` of another `mint_b` for the whole of `amount_a`
+    pub fn push_alternative(&mut self, mint_b: [u8; 32], amount_b: u64) -> Result<(), EscrowError> {
+        let index = self.alternative_count[0] as usize;
+        if index == MAX_PAYMENT_ALTERNATIVES || mint_b == self.mint_b || self.alternatives().iter().any(|alternative| alternative.mint_b == mint_b) {
+            return Err(EscrowError::InvalidPaymentAlternatives);
+        }
+        self.alternatives[index] = PaymentAlternative { mint_b, amount_b: amount_b.to_le_bytes() };
+        self.alternative_count = [index as u8 + 1];
+        Ok(())
+    }
+
+    /// The payment alternatives in use
+    pub fn alternatives(&self) -> &[PaymentAlternative] {
+        &self.alternatives[..self.alternative_count[0] as usize]
+    }
+
+    /// The maker that created the escrow and receives `mint_b`
+    pub fn maker(&self) -> Address {
+        Address::new_from_array(self.maker)
+    }
+
+    /// The mint deposited in the vault, `NATIVE_MINT` for native SOL
+    pub fn mint_a(&self) -> Address {
+        Address::new_from_array(self.mint_a)
+    }
+
+    /// The mint the maker asks for, `NATIVE_MINT` for native SOL
+    pub fn mint_b(&self) -> Address {
+        Address::new_from_array(self.mint_b)
+    }
+
+    /// The seed of the escrow PDA as it is passed to the derivation: 1 byte for MAKE and MAKE_V2, 8 bytes for MAKE_WIDE
+    pub fn seed(&self) -> &[u8] {
+        &self.seed[..self.seed_len[0] as usize]
+    }
+
+    /// The seed as a number, whichever its width
+    pub fn seed_value(&self) -> u64 {
+        u64::from_le_bytes(self.seed)
+    }
+
+    /// Amount of `mint_a` the current terms are priced on
+    pub fn amount_a(&self) -> u64 {
+        u64::from_le_bytes(self.amount_a)
+    }
+
+    /// Amount of `mint_b` asked for the whole of `amount_a`, the least asked when `mint_b` is priced from a feed
+    pub fn amount_b(&self) -> u64 {
+        u64::from_le_bytes(self.amount_b)
+    }
+
+    /// Amount of `mint` asked for the whole of `amount_a`, if it is `mint_b` or one of the payment alternatives
+    pub fn amount_b_of(&self, mint: &Address) -> Option<u64> {
+        if mint == &self.mint_b() {
+            return Some(self.amount_b());
+        }
+        self.alternatives().iter().find(|alternative| &alternative.mint_b() == mint).map(PaymentAlternative::amount_b)
+    }
+
+    /// Amount of `mint_a` still left in the vault for takers
+    pub fn remaining_a(&self) -> u64 {
+        u64::from_le_bytes(self.remaining_a)
+    }
+
+    /// Unix timestamp after which the escrow can no longer be taken, 0 for no expiry
+    pub fn expires_at(&self) -> i64 {
+        i64::from_le_bytes(self.expires_at)
+    }
+
+    /// Protocol fee in basis points, snapshotted from the config in `make`
+    pub fn fee_bps(&self) -> u16 {
+        u16::from_le_bytes(self.fee_bps)
+    }
+
+    /// The designated taker, `None` for a public offer
+    pub fn taker(&self) -> Option<Address> {
+        (self.taker != [0u8; 32]).then(|| Address::new_from_array(self.taker))
+    }
+
+    /// Resets the terms of an open escrow: `amount_b` is asked for the whole of `amount_a`, which becomes the remaining amount.
+    pub fn set_terms(&mut self, amount_a: u64, amount_b: u64) {
+        self.amount_a = amount_a.to_le_bytes();
+        self.remaining_a = amount_a.to_le_bytes();
+        self.amount_b = amount_b.to_le_bytes();
+    }
+
+    /// Amount owed for `fill_a` of `mint_a` when `amount_b` (see `amount_b_of`) is asked for the whole of `amount_a`.
+    /// Rounds up so partial fills never favour the taker.
+    pub fn amount_b_for(&self, fill_a: u64, amount_b: u64) -> Option<u64> {
+        let amount_a = self.amount_a() as u128;
+        let amount_b = amount_b as u128;
+        if amount_a == 0 {
+            return None;
+        }
+        let due = (fill_a as u128 * amount_b).div_ceil(amount_a);
+        u64::try_from(due).ok()
+    }
+
+    /// Protocol fee skimmed from `amount_b` at the rate snapshotted in `make`. Rounds down in favour of the maker.
+    pub fn fee_for(&self, amount_b: u64) -> u64 {
+        let fee_bps = self.fee_bps() as u128;
+        (amount_b as u128 * fee_bps / 10_000) as u64
+    }
+
+    /// Whether the escrow has an expiry timestamp (0 means it never expires) that is at or before `now`.
+    pub fn is_expired(&self, now: i64) -> bool {
+        let expires_at = self.expires_at();
+        expires_at != 0 && now >= expires_at
+    }
+
+    /// Whether `taker` may take the escrow: anyone for a public offer (all-zero taker), only the designated taker otherwise.
+    pub fn is_taker_allowed(&self, taker: &[u8; 32]) -> bool {
+        self.taker().is_none_or(|allowed| &allowed.to_bytes() == taker)
+    }
+
+}
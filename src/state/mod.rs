@@ -1,2 +1,8 @@
 pub mod escrow;
-pub use escrow::*;
\ No newline at end of file
+pub mod config;
+pub mod basket;
+pub mod vesting;
+pub use escrow::*;
+pub use config::*;
+pub use basket::*;
+pub use vesting::*;
\ No newline at end of file
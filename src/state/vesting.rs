@@ -0,0 +1,116 @@
+use pinocchio::{AccountView, Address};
+use shank::ShankAccount;
+
+use crate::errors::EscrowError;
+
+/// The `mint_a` a taker bought from a vesting escrow, held until it vests: nothing before `cliff`, then linearly from `start` to `end`.
+/// Token `mint_a` is held by the vesting ATA, native SOL by the vesting account itself on top of its rent.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default, PartialEq, ShankAccount)]
+pub struct Vesting {
+    pub discriminator: [u8; 1],
+    pub version: [u8; 1],
+    pub beneficiary: [u8; 32],
+    pub escrow: [u8; 32],
+    pub mint_a: [u8; 32],
+    pub amount_a: [u8; 8],
+    pub claimed_a: [u8; 8],
+    pub start: [u8; 8],
+    pub cliff: [u8; 8],
+    pub end: [u8; 8],
+    pub bump: [u8; 1],
+}
+impl Vesting {
+    pub const LEN: usize = 139;
+
+    /// First byte of every vesting account, telling it apart from the escrows
+    pub const DISCRIMINATOR: u8 = 3;
+
+    pub const VERSION: u8 = 1;
+
+    /// Load a vesting account, validating the discriminator and version header
+    #[allow(clippy::mut_from_ref)]
+    pub fn from_account_info_mut(account_info: &AccountView) -> Result<&mut Self, pinocchio::error::ProgramError> {
+        let mut data = account_info.try_borrow_mut()?;
+
+        if data.len() != Vesting::LEN || data[0] != Self::DISCRIMINATOR || data[1] != Self::VERSION {
+            return Err(EscrowError::InvalidEscrowData.into());
+        }
+
+        Ok(unsafe { &mut *(data.as_mut_ptr() as *mut Self)})
+    }
+
+    /// Load a freshly created vesting account, before `set_inner` writes its header
+    #[allow(clippy::mut_from_ref)]
+    pub fn from_account_info_mut_uninitialized(account_info: &AccountView) -> Result<&mut Self, pinocchio::error::ProgramError> {
+        let mut data = account_info.try_borrow_mut()?;
+
+        if data.len() != Vesting::LEN || data[0] != 0 {
+            return Err(EscrowError::InvalidEscrowData.into());
+        }
+
+        Ok(unsafe { &mut *(data.as_mut_ptr() as *mut Self)})
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    pub fn set_inner(&mut self, beneficiary: [u8; 32], escrow: [u8; 32], mint_a: [u8; 32], amount_a: u64, start: i64, cliff: i64, end: i64, bump: [u8; 1]) {
+        self.discriminator = [Self::DISCRIMINATOR];
+        self.version = [Self::VERSION];
+        self.beneficiary = beneficiary;
+        self.escrow = escrow;
+        self.mint_a = mint_a;
+        self.amount_a = amount_a.to_le_bytes();
+        self.claimed_a = [0; 8];
+        self.start = start.to_le_bytes();
+        self.cliff = cliff.to_le_bytes();
+        self.end = end.to_le_bytes();
+        self.bump = bump;
+    }
+
+    pub fn beneficiary(&self) -> Address {
+        Address::new_from_array(self.beneficiary)
+    }
+
+    pub fn escrow(&self) -> Address {
+        Address::new_from_array(self.escrow)
+    }
+
+    pub fn mint_a(&self) -> Address {
+        Address::new_from_array(self.mint_a)
+    }
+
+    pub fn amount_a(&self) -> u64 {
+        u64::from_le_bytes(self.amount_a)
+    }
+
+    pub fn claimed_a(&self) -> u64 {
+        u64::from_le_bytes(self.claimed_a)
+    }
+
+    /// Amount of `mint_a` vested at `now`: none before the cliff, all of it from the end, linearly in between. Rounds down.
+    pub fn vested_a(&self, now: i64) -> u64 {
+        let start = i64::from_le_bytes(self.start);
+        let end = i64::from_le_bytes(self.end);
+        if now < i64::from_le_bytes(self.cliff) {
+            return 0;
+        }
+        if now >= end {
+            return self.amount_a();
+        }
+        (self.amount_a() as u128 * (now - start) as u128 / (end - start) as u128) as u64
+    }
+
+    /// Records the claim of everything vested at `now`, returning the amount claimed
+    pub fn claim(&mut self, now: i64) -> u64 {
+        let vested_a = self.vested_a(now);
+        let claimable_a = vested_a - self.claimed_a();
+        self.claimed_a = vested_a.to_le_bytes();
+        claimable_a
+    }
+
+    /// Whether everything has been claimed
+    pub fn is_fully_claimed(&self) -> bool {
+        self.claimed_a == self.amount_a
+    }
+
+}
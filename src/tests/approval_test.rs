@@ -0,0 +1,143 @@
+use litesvm_token::MintTo;
+use solana_keypair::Keypair;
+use solana_message::Message;
+use solana_native_token::LAMPORTS_PER_SOL;
+use solana_signer::Signer;
+use solana_transaction::Transaction;
+use solana_program::{clock::Clock, msg};
+use spl_associated_token_account_interface::address::get_associated_token_address;
+
+use crate::client::{ApprovalTerms, ApproveParams, Escrow, EscrowSeed, MakeParams, RefundParams, TOKEN_PROGRAM_ID, approve_instruction, find_escrow_address, make_approval_instruction, refund_instruction, revoke_approval_instruction};
+use crate::errors::EscrowError;
+use crate::tests::test_helpers::{EscrowTestSetup, assert_escrow_error, get_token_balance, setup_escrow_test};
+
+/// Builds an approval of the escrow by `approver` on a fresh blockhash, so retrying the same approval is a new transaction
+fn approve_transaction(escrow_setup: &mut EscrowTestSetup, approver: &Keypair) -> Transaction {
+    let approve_params = ApproveParams {
+        approver: approver.pubkey(),
+        maker: escrow_setup.maker.pubkey(),
+        beneficiary: escrow_setup.taker.pubkey(),
+        mint_a: escrow_setup.mint_a,
+        token_program_a: TOKEN_PROGRAM_ID,
+        seed: EscrowSeed::Wide(1),
+    };
+    let message = Message::new(&[approve_instruction(&approve_params)], Some(&approver.pubkey()));
+    escrow_setup.litesvm.expire_blockhash();
+    let recent_blockhash = escrow_setup.litesvm.latest_blockhash();
+    Transaction::new(&[approver], message, recent_blockhash)
+}
+
+/// Builds the withdrawal of the approval of `approver` on a fresh blockhash
+fn revoke_transaction(escrow_setup: &mut EscrowTestSetup, approver: &Keypair) -> Transaction {
+    let instruction = revoke_approval_instruction(&approver.pubkey(), &escrow_setup.maker.pubkey(), EscrowSeed::Wide(1));
+    let message = Message::new(&[instruction], Some(&approver.pubkey()));
+    escrow_setup.litesvm.expire_blockhash();
+    let recent_blockhash = escrow_setup.litesvm.latest_blockhash();
+    Transaction::new(&[approver], message, recent_blockhash)
+}
+
+/// The approvers that have approved the escrow so far
+fn approved_by(escrow_setup: &EscrowTestSetup) -> Vec<solana_pubkey::Pubkey> {
+    let (escrow_pda, _) = find_escrow_address(&escrow_setup.maker.pubkey(), EscrowSeed::Wide(1));
+    Escrow::from_bytes(&escrow_setup.litesvm.get_account(&escrow_pda).unwrap().data).unwrap().approved_by
+}
+
+#[test]
+fn test_approval_release() {
+    let mut escrow_setup = setup_escrow_test();
+
+    let maker_pubkey = escrow_setup.maker.pubkey();
+    let approvers = [Keypair::new(), Keypair::new(), Keypair::new()];
+    for approver in &approvers {
+        escrow_setup.litesvm.airdrop(&approver.pubkey(), LAMPORTS_PER_SOL).unwrap();
+    }
+
+    // Deposit 30 mint_a released to the taker by 2 of the 3 approvers, or back to the maker after a day
+    let clock: Clock = escrow_setup.litesvm.get_sysvar();
+    let make_params = MakeParams {
+        maker: maker_pubkey,
+        mint_a: escrow_setup.mint_a,
+        mint_b: escrow_setup.mint_b,
+        token_program_a: TOKEN_PROGRAM_ID,
+        amount_a: 30_000_000,
+        amount_b: 1,
+        seed: EscrowSeed::Wide(1),
+        expires_at: clock.unix_timestamp + 86_400,
+        taker: Some(escrow_setup.taker.pubkey()),
+        alternatives: vec![],
+    };
+    let approval = ApprovalTerms {
+        approvers: approvers.iter().map(|approver| approver.pubkey()).collect(),
+        threshold: 2,
+    };
+
+    // The expiry is required, as it is the only way back to the maker
+    let no_expiry = MakeParams { expires_at: 0, ..make_params.clone() };
+    let message = Message::new(&[make_approval_instruction(&no_expiry, &approval)], Some(&maker_pubkey));
+    let recent_blockhash = escrow_setup.litesvm.latest_blockhash();
+    let transaction = Transaction::new(&[&escrow_setup.maker], message, recent_blockhash);
+    assert_escrow_error(escrow_setup.litesvm.send_transaction(transaction), EscrowError::InvalidApprovers);
+
+    // The threshold can't exceed the number of approvers
+    let unreachable = ApprovalTerms { threshold: 4, ..approval.clone() };
+    let message = Message::new(&[make_approval_instruction(&make_params, &unreachable)], Some(&maker_pubkey));
+    let recent_blockhash = escrow_setup.litesvm.latest_blockhash();
+    let transaction = Transaction::new(&[&escrow_setup.maker], message, recent_blockhash);
+    assert_escrow_error(escrow_setup.litesvm.send_transaction(transaction), EscrowError::InvalidApprovers);
+
+    let message = Message::new(&[make_approval_instruction(&make_params, &approval)], Some(&maker_pubkey));
+    let recent_blockhash = escrow_setup.litesvm.latest_blockhash();
+    let transaction = Transaction::new(&[&escrow_setup.maker], message, recent_blockhash);
+    escrow_setup.litesvm.send_transaction(transaction).unwrap();
+
+    let (escrow_pda, _) = find_escrow_address(&maker_pubkey, EscrowSeed::Wide(1));
+    let escrow_account = escrow_setup.litesvm.get_account(&escrow_pda).unwrap();
+    assert_eq!(Escrow::from_bytes(&escrow_account.data).unwrap().approval, Some(approval));
+
+    // The maker can't take the deposit back
+    let refund_params = RefundParams {
+        maker: maker_pubkey,
+        mint_a: escrow_setup.mint_a,
+        mint_b: escrow_setup.mint_b,
+        token_program_a: TOKEN_PROGRAM_ID,
+        seed: EscrowSeed::Wide(1),
+    };
+    let message = Message::new(&[refund_instruction(&refund_params)], Some(&maker_pubkey));
+    let recent_blockhash = escrow_setup.litesvm.latest_blockhash();
+    let transaction = Transaction::new(&[&escrow_setup.maker], message, recent_blockhash);
+    assert_escrow_error(escrow_setup.litesvm.send_transaction(transaction), EscrowError::ApprovalEscrow);
+
+    // Only registered approvers approve, once each
+    let stranger = Keypair::new();
+    escrow_setup.litesvm.airdrop(&stranger.pubkey(), LAMPORTS_PER_SOL).unwrap();
+    let transaction = approve_transaction(&mut escrow_setup, &stranger);
+    assert_escrow_error(escrow_setup.litesvm.send_transaction(transaction), EscrowError::NotAnApprover);
+    let transaction = approve_transaction(&mut escrow_setup, &approvers[0]);
+    let tx = escrow_setup.litesvm.send_transaction(transaction).unwrap();
+    assert_eq!(approved_by(&escrow_setup), vec![approvers[0].pubkey()]);
+    let transaction = approve_transaction(&mut escrow_setup, &approvers[0]);
+    assert_escrow_error(escrow_setup.litesvm.send_transaction(transaction), EscrowError::AlreadyApproved);
+
+    msg!("\n\nApprove transaction sucessfull");
+    msg!("CUs Consumed: {}", tx.compute_units_consumed);
+
+    // An approver can withdraw their approval before the threshold is reached
+    let transaction = revoke_transaction(&mut escrow_setup, &approvers[0]);
+    escrow_setup.litesvm.send_transaction(transaction).unwrap();
+    assert!(approved_by(&escrow_setup).is_empty());
+    let transaction = revoke_transaction(&mut escrow_setup, &approvers[0]);
+    assert_escrow_error(escrow_setup.litesvm.send_transaction(transaction), EscrowError::NotApproved);
+
+    // The second approval releases the whole vault to the beneficiary, tokens sent to it on top of the deposit included
+    let transaction = approve_transaction(&mut escrow_setup, &approvers[1]);
+    escrow_setup.litesvm.send_transaction(transaction).unwrap();
+    assert_eq!(get_token_balance(&escrow_setup.litesvm, &escrow_setup.taker_ata_a), 0);
+    let vault = get_associated_token_address(&escrow_pda, &escrow_setup.mint_a);
+    MintTo::new(&mut escrow_setup.litesvm, &escrow_setup._mint_authority, &escrow_setup.mint_a, &vault, 1_000_000)
+        .send()
+        .unwrap();
+    let transaction = approve_transaction(&mut escrow_setup, &approvers[2]);
+    escrow_setup.litesvm.send_transaction(transaction).unwrap();
+    assert_eq!(get_token_balance(&escrow_setup.litesvm, &escrow_setup.taker_ata_a), 31_000_000);
+    assert!(escrow_setup.litesvm.get_account(&escrow_pda).is_none_or(|account| account.lamports == 0));
+}
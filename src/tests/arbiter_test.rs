@@ -0,0 +1,152 @@
+use litesvm_token::MintTo;
+use solana_keypair::Keypair;
+use solana_message::Message;
+use solana_native_token::LAMPORTS_PER_SOL;
+use solana_pubkey::Pubkey;
+use solana_signer::Signer;
+use solana_transaction::Transaction;
+use solana_program::msg;
+use spl_associated_token_account_interface::address::get_associated_token_address;
+
+use crate::client::{Escrow, EscrowSeed, MakeParams, ReleaseParams, TakeParams, TOKEN_PROGRAM_ID, dispute_resolve_instruction, find_escrow_address, make_arbiter_instruction, release_instruction, take_instruction};
+use crate::errors::EscrowError;
+use crate::tests::test_helpers::{EscrowTestSetup, assert_escrow_error, get_token_balance, setup_escrow_test};
+
+/// Makes an arbitrated escrow of 30 mint_a with the taker as seller
+fn make_arbitrated(escrow_setup: &mut EscrowTestSetup, seed: u64, arbiter: &Pubkey) {
+    let make_params = MakeParams {
+        maker: escrow_setup.maker.pubkey(),
+        mint_a: escrow_setup.mint_a,
+        mint_b: escrow_setup.mint_b,
+        token_program_a: TOKEN_PROGRAM_ID,
+        amount_a: 30_000_000,
+        amount_b: 1,
+        seed: EscrowSeed::Wide(seed),
+        expires_at: 0,
+        taker: Some(escrow_setup.taker.pubkey()),
+        alternatives: vec![],
+    };
+    let message = Message::new(&[make_arbiter_instruction(&make_params, arbiter)], Some(&escrow_setup.maker.pubkey()));
+    let recent_blockhash = escrow_setup.litesvm.latest_blockhash();
+    let transaction = Transaction::new(&[&escrow_setup.maker], message, recent_blockhash);
+    escrow_setup.litesvm.send_transaction(transaction).unwrap();
+}
+
+/// Builds a release (or a dispute resolution when `to_seller` is given) signed by `authority` on a fresh blockhash
+fn release_transaction(escrow_setup: &mut EscrowTestSetup, seed: u64, authority: &Keypair, recipient: &Pubkey, to_seller: Option<bool>) -> Transaction {
+    let release_params = ReleaseParams {
+        authority: authority.pubkey(),
+        maker: escrow_setup.maker.pubkey(),
+        recipient: *recipient,
+        mint_a: escrow_setup.mint_a,
+        token_program_a: TOKEN_PROGRAM_ID,
+        seed: EscrowSeed::Wide(seed),
+    };
+    let instruction = match to_seller {
+        Some(to_seller) => dispute_resolve_instruction(&release_params, to_seller),
+        None => release_instruction(&release_params),
+    };
+    let message = Message::new(&[instruction], Some(&authority.pubkey()));
+    escrow_setup.litesvm.expire_blockhash();
+    let recent_blockhash = escrow_setup.litesvm.latest_blockhash();
+    Transaction::new(&[authority], message, recent_blockhash)
+}
+
+#[test]
+fn test_arbiter_release() {
+    let mut escrow_setup = setup_escrow_test();
+
+    let maker_pubkey = escrow_setup.maker.pubkey();
+    let seller_pubkey = escrow_setup.taker.pubkey();
+    let arbiter = Keypair::new();
+    escrow_setup.litesvm.airdrop(&arbiter.pubkey(), LAMPORTS_PER_SOL).unwrap();
+
+    // The arbiter can't be the seller
+    let make_params = MakeParams {
+        maker: maker_pubkey,
+        mint_a: escrow_setup.mint_a,
+        mint_b: escrow_setup.mint_b,
+        token_program_a: TOKEN_PROGRAM_ID,
+        amount_a: 30_000_000,
+        amount_b: 1,
+        seed: EscrowSeed::Wide(1),
+        expires_at: 0,
+        taker: Some(seller_pubkey),
+        alternatives: vec![],
+    };
+    let message = Message::new(&[make_arbiter_instruction(&make_params, &seller_pubkey)], Some(&maker_pubkey));
+    let recent_blockhash = escrow_setup.litesvm.latest_blockhash();
+    let transaction = Transaction::new(&[&escrow_setup.maker], message, recent_blockhash);
+    assert_escrow_error(escrow_setup.litesvm.send_transaction(transaction), EscrowError::InvalidArbiterTerms);
+
+    make_arbitrated(&mut escrow_setup, 1, &arbiter.pubkey());
+    let (escrow_pda, _) = find_escrow_address(&maker_pubkey, EscrowSeed::Wide(1));
+    let escrow_account = escrow_setup.litesvm.get_account(&escrow_pda).unwrap();
+    assert_eq!(Escrow::from_bytes(&escrow_account.data).unwrap().arbiter, Some(arbiter.pubkey()));
+
+    // The seller can't take it
+    let take_params = TakeParams {
+        taker: seller_pubkey,
+        maker: maker_pubkey,
+        mint_a: escrow_setup.mint_a,
+        mint_b: escrow_setup.mint_b,
+        token_program_a: TOKEN_PROGRAM_ID,
+        token_program_b: TOKEN_PROGRAM_ID,
+        treasury: escrow_setup.treasury,
+        seed: EscrowSeed::Wide(1),
+        amount_a: 30_000_000,
+        max_amount_b: None,
+    };
+    let message = Message::new(&[take_instruction(&take_params)], Some(&seller_pubkey));
+    let recent_blockhash = escrow_setup.litesvm.latest_blockhash();
+    let transaction = Transaction::new(&[&escrow_setup.taker], message, recent_blockhash);
+    assert_escrow_error(escrow_setup.litesvm.send_transaction(transaction), EscrowError::ArbitratedEscrow);
+
+    // Only the parties release it, each to the other one
+    let transaction = release_transaction(&mut escrow_setup, 1, &arbiter, &seller_pubkey, None);
+    assert_escrow_error(escrow_setup.litesvm.send_transaction(transaction), EscrowError::UnauthorizedRelease);
+    let seller = escrow_setup.taker.insecure_clone();
+    let transaction = release_transaction(&mut escrow_setup, 1, &seller, &seller_pubkey, None);
+    assert_escrow_error(escrow_setup.litesvm.send_transaction(transaction), EscrowError::MakerMismatch);
+
+    // The buyer releases the whole vault to the seller, tokens sent to it on top of the deposit included
+    let vault = get_associated_token_address(&escrow_pda, &escrow_setup.mint_a);
+    MintTo::new(&mut escrow_setup.litesvm, &escrow_setup._mint_authority, &escrow_setup.mint_a, &vault, 1_000_000)
+        .send()
+        .unwrap();
+    let seller_a_before = get_token_balance(&escrow_setup.litesvm, &escrow_setup.taker_ata_a);
+    let maker = escrow_setup.maker.insecure_clone();
+    let transaction = release_transaction(&mut escrow_setup, 1, &maker, &seller_pubkey, None);
+    let tx = escrow_setup.litesvm.send_transaction(transaction).unwrap();
+    assert_eq!(get_token_balance(&escrow_setup.litesvm, &escrow_setup.taker_ata_a), seller_a_before + 31_000_000);
+    assert!(escrow_setup.litesvm.get_account(&escrow_pda).is_none_or(|account| account.lamports == 0));
+
+    msg!("\n\nRelease transaction sucessfull");
+    msg!("CUs Consumed: {}", tx.compute_units_consumed);
+}
+
+#[test]
+fn test_arbiter_dispute_resolve() {
+    let mut escrow_setup = setup_escrow_test();
+
+    let maker_pubkey = escrow_setup.maker.pubkey();
+    let arbiter = Keypair::new();
+    escrow_setup.litesvm.airdrop(&arbiter.pubkey(), LAMPORTS_PER_SOL).unwrap();
+    make_arbitrated(&mut escrow_setup, 1, &arbiter.pubkey());
+    let maker_a_before = get_token_balance(&escrow_setup.litesvm, &escrow_setup.maker_ata_a);
+
+    // The buyer can't get the deposit back on their own
+    let maker = escrow_setup.maker.insecure_clone();
+    let transaction = release_transaction(&mut escrow_setup, 1, &maker, &maker_pubkey, Some(false));
+    assert_escrow_error(escrow_setup.litesvm.send_transaction(transaction), EscrowError::UnauthorizedRelease);
+
+    // The arbiter settles the dispute in favour of the buyer
+    let transaction = release_transaction(&mut escrow_setup, 1, &arbiter, &maker_pubkey, Some(false));
+    let tx = escrow_setup.litesvm.send_transaction(transaction).unwrap();
+    assert_eq!(get_token_balance(&escrow_setup.litesvm, &escrow_setup.maker_ata_a), maker_a_before + 30_000_000);
+    let (escrow_pda, _) = find_escrow_address(&maker_pubkey, EscrowSeed::Wide(1));
+    assert!(escrow_setup.litesvm.get_account(&escrow_pda).is_none_or(|account| account.lamports == 0));
+
+    msg!("\n\nDispute resolve transaction sucessfull");
+    msg!("CUs Consumed: {}", tx.compute_units_consumed);
+}
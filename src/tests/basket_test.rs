@@ -0,0 +1,260 @@
+use litesvm_token::{CreateAccount, CreateAssociatedTokenAccount, CreateMint, MintTo};
+use solana_message::Message;
+use solana_pubkey::Pubkey;
+use solana_signer::Signer;
+use solana_transaction::Transaction;
+use solana_instruction::AccountMeta;
+use solana_program::{clock::Clock, msg};
+use spl_associated_token_account_interface::address::get_associated_token_address_with_program_id;
+
+use crate::client::{Basket, BasketLegParams, MakeBasketParams, RefundBasketParams, TakeBasketParams, TOKEN_PROGRAM_ID, find_basket_address, make_basket_instruction, refund_basket_instruction, take_basket_instruction};
+use crate::errors::EscrowError;
+use crate::tests::test_helpers::{EscrowTestSetup, assert_escrow_error, get_token_balance, setup_escrow_test};
+
+/// Creates two more mints next to `mint_a`, funding the maker and creating the taker ATAs, and returns the three leg mints
+fn setup_basket_legs(escrow_setup: &mut EscrowTestSetup) -> Vec<Pubkey> {
+    let mut mints = vec![escrow_setup.mint_a];
+    for _ in 0..2 {
+        let mint = CreateMint::new(&mut escrow_setup.litesvm, &escrow_setup._mint_authority)
+            .authority(&escrow_setup._mint_authority.pubkey())
+            .decimals(6)
+            .send()
+            .unwrap();
+        let maker_ata = CreateAssociatedTokenAccount::new(&mut escrow_setup.litesvm, &escrow_setup.maker, &mint)
+            .owner(&escrow_setup.maker.pubkey())
+            .send()
+            .unwrap();
+        CreateAssociatedTokenAccount::new(&mut escrow_setup.litesvm, &escrow_setup.taker, &mint)
+            .owner(&escrow_setup.taker.pubkey())
+            .send()
+            .unwrap();
+        MintTo::new(&mut escrow_setup.litesvm, &escrow_setup._mint_authority, &mint, &maker_ata, 100_000_000)
+            .send()
+            .unwrap();
+        mints.push(mint);
+    }
+    mints
+}
+
+/// Makes a basket offering `amounts[i]` of `mints[i]` for `amount_b` of `mint_b`
+fn make_basket(escrow_setup: &mut EscrowTestSetup, mints: &[Pubkey], amounts: &[u64], amount_b: u64, seed: u64, expires_at: i64) {
+    let maker_pubkey = escrow_setup.maker.pubkey();
+    let make_basket_instruction = make_basket_instruction(&MakeBasketParams {
+        maker: maker_pubkey,
+        mint_b: escrow_setup.mint_b,
+        amount_b,
+        seed,
+        expires_at,
+        legs: mints.iter().zip(amounts).map(|(mint_a, amount_a)| BasketLegParams {
+            mint_a: *mint_a,
+            token_program_a: TOKEN_PROGRAM_ID,
+            amount_a: *amount_a,
+        }).collect(),
+    });
+    let message = Message::new(&[make_basket_instruction], Some(&maker_pubkey));
+    let recent_blockhash = escrow_setup.litesvm.latest_blockhash();
+    let transaction = Transaction::new(&[&escrow_setup.maker], message, recent_blockhash);
+    let tx = escrow_setup.litesvm.send_transaction(transaction).unwrap();
+
+    msg!("\n\nMake basket transaction sucessfull");
+    msg!("CUs Consumed: {}", tx.compute_units_consumed);
+}
+
+fn take_basket_params(escrow_setup: &EscrowTestSetup, mints: &[Pubkey], seed: u64) -> TakeBasketParams {
+    TakeBasketParams {
+        taker: escrow_setup.taker.pubkey(),
+        maker: escrow_setup.maker.pubkey(),
+        mint_b: escrow_setup.mint_b,
+        token_program_b: TOKEN_PROGRAM_ID,
+        treasury: escrow_setup.treasury,
+        seed,
+        legs: mints.iter().map(|mint_a| (*mint_a, TOKEN_PROGRAM_ID)).collect(),
+    }
+}
+
+#[test]
+fn test_take_basket() {
+    let mut escrow_setup = setup_escrow_test();
+    let mints = setup_basket_legs(&mut escrow_setup);
+
+    let seed: u64 = 7;
+    let amounts = [10_000_000u64, 20_000_000, 30_000_000];
+    let amount_b: u64 = 50_000_000;
+    let maker_pubkey = escrow_setup.maker.pubkey();
+    let taker_pubkey = escrow_setup.taker.pubkey();
+    make_basket(&mut escrow_setup, &mints, &amounts, amount_b, seed, 0);
+
+    // Every leg is recorded in the basket and held in its own vault
+    let (basket_pda, basket_bump) = find_basket_address(&maker_pubkey, seed);
+    let basket_account = escrow_setup.litesvm.get_account(&basket_pda).unwrap();
+    let basket = Basket::from_bytes(&basket_account.data).unwrap();
+    assert_eq!(basket.maker, maker_pubkey);
+    assert_eq!(basket.amount_b, amount_b);
+    assert_eq!(basket.bump, basket_bump);
+    assert_eq!(basket.legs, mints.iter().copied().zip(amounts).collect::<Vec<_>>());
+    for (mint_a, amount_a) in mints.iter().zip(amounts) {
+        let vault = get_associated_token_address_with_program_id(&basket_pda, mint_a, &TOKEN_PROGRAM_ID);
+        assert_eq!(get_token_balance(&escrow_setup.litesvm, &vault), amount_a);
+    }
+
+    // Take the whole basket at once
+    let take_basket_instruction = take_basket_instruction(&take_basket_params(&escrow_setup, &mints, seed));
+    let message = Message::new(&[take_basket_instruction], Some(&taker_pubkey));
+    let recent_blockhash = escrow_setup.litesvm.latest_blockhash();
+    let transaction = Transaction::new(&[&escrow_setup.taker], message, recent_blockhash);
+    let tx = escrow_setup.litesvm.send_transaction(transaction).unwrap();
+
+    msg!("\n\nTake basket transaction sucessfull");
+    msg!("CUs Consumed: {}", tx.compute_units_consumed);
+
+    // The taker received every leg, the maker amount_b, and the basket and vaults are closed
+    for (mint_a, amount_a) in mints.iter().zip(amounts) {
+        let taker_ata = get_associated_token_address_with_program_id(&taker_pubkey, mint_a, &TOKEN_PROGRAM_ID);
+        assert_eq!(get_token_balance(&escrow_setup.litesvm, &taker_ata), amount_a);
+        let vault = get_associated_token_address_with_program_id(&basket_pda, mint_a, &TOKEN_PROGRAM_ID);
+        assert!(escrow_setup.litesvm.get_account(&vault).is_none_or(|account| account.lamports == 0));
+    }
+    assert_eq!(get_token_balance(&escrow_setup.litesvm, &escrow_setup.maker_ata_b), amount_b);
+    assert!(escrow_setup.litesvm.get_account(&basket_pda).is_none_or(|account| account.lamports == 0));
+}
+
+#[test]
+fn test_take_basket_wrong_legs() {
+    let mut escrow_setup = setup_escrow_test();
+    let mints = setup_basket_legs(&mut escrow_setup);
+
+    let seed: u64 = 8;
+    let amounts = [10_000_000u64, 20_000_000, 30_000_000];
+    let taker_pubkey = escrow_setup.taker.pubkey();
+    make_basket(&mut escrow_setup, &mints, &amounts, 50_000_000, seed, 0);
+
+    // A missing leg fails the whole take
+    let missing_leg_instruction = take_basket_instruction(&take_basket_params(&escrow_setup, &mints[..2], seed));
+    let message = Message::new(&[missing_leg_instruction], Some(&taker_pubkey));
+    let recent_blockhash = escrow_setup.litesvm.latest_blockhash();
+    let transaction = Transaction::new(&[&escrow_setup.taker], message, recent_blockhash);
+    assert_escrow_error(escrow_setup.litesvm.send_transaction(transaction), EscrowError::InvalidBasketLegs);
+
+    // So do the legs passed out of order
+    let swapped = [mints[1], mints[0], mints[2]];
+    let swapped_legs_instruction = take_basket_instruction(&take_basket_params(&escrow_setup, &swapped, seed));
+    let message = Message::new(&[swapped_legs_instruction], Some(&taker_pubkey));
+    let recent_blockhash = escrow_setup.litesvm.latest_blockhash();
+    let transaction = Transaction::new(&[&escrow_setup.taker], message, recent_blockhash);
+    assert_escrow_error(escrow_setup.litesvm.send_transaction(transaction), EscrowError::InvalidBasketLegs);
+
+    // So does a leg vault that is not the basket ATA of its mint, even one owned by the basket
+    let (basket_pda, _) = find_basket_address(&escrow_setup.maker.pubkey(), seed);
+    let fake_vault = CreateAccount::new(&mut escrow_setup.litesvm, &escrow_setup.taker, &mints[0])
+        .owner(&basket_pda)
+        .send()
+        .unwrap();
+    let mut fake_vault_instruction = take_basket_instruction(&take_basket_params(&escrow_setup, &mints, seed));
+    fake_vault_instruction.accounts[14] = AccountMeta::new(fake_vault, false);
+    let message = Message::new(&[fake_vault_instruction], Some(&taker_pubkey));
+    let recent_blockhash = escrow_setup.litesvm.latest_blockhash();
+    let transaction = Transaction::new(&[&escrow_setup.taker], message, recent_blockhash);
+    assert_escrow_error(escrow_setup.litesvm.send_transaction(transaction), EscrowError::InvalidVaultAddress);
+
+    // Nothing moved
+    assert_eq!(get_token_balance(&escrow_setup.litesvm, &escrow_setup.taker_ata_b), 100_000_000);
+    assert_eq!(get_token_balance(&escrow_setup.litesvm, &escrow_setup.taker_ata_a), 0);
+}
+
+#[test]
+fn test_make_basket_same_mint() {
+    let mut escrow_setup = setup_escrow_test();
+    let mut mints = setup_basket_legs(&mut escrow_setup);
+    let maker_pubkey = escrow_setup.maker.pubkey();
+
+    // A leg offering mint_b itself is rejected
+    mints[1] = escrow_setup.mint_b;
+    let make_basket_instruction = make_basket_instruction(&MakeBasketParams {
+        maker: maker_pubkey,
+        mint_b: escrow_setup.mint_b,
+        amount_b: 50_000_000,
+        seed: 11,
+        expires_at: 0,
+        legs: mints.iter().map(|mint_a| BasketLegParams {
+            mint_a: *mint_a,
+            token_program_a: TOKEN_PROGRAM_ID,
+            amount_a: 10_000_000,
+        }).collect(),
+    });
+    let message = Message::new(&[make_basket_instruction], Some(&maker_pubkey));
+    let recent_blockhash = escrow_setup.litesvm.latest_blockhash();
+    let transaction = Transaction::new(&[&escrow_setup.maker], message, recent_blockhash);
+    assert_escrow_error(escrow_setup.litesvm.send_transaction(transaction), EscrowError::SameMint);
+}
+
+#[test]
+fn test_refund_basket() {
+    let mut escrow_setup = setup_escrow_test();
+    let mints = setup_basket_legs(&mut escrow_setup);
+
+    let seed: u64 = 9;
+    let amounts = [10_000_000u64, 20_000_000, 30_000_000];
+    let maker_pubkey = escrow_setup.maker.pubkey();
+    make_basket(&mut escrow_setup, &mints, &amounts, 50_000_000, seed, 0);
+
+    // Refund every leg to the maker
+    let refund_basket_instruction = refund_basket_instruction(&RefundBasketParams {
+        maker: maker_pubkey,
+        mint_b: escrow_setup.mint_b,
+        seed,
+        legs: mints.iter().map(|mint_a| (*mint_a, TOKEN_PROGRAM_ID)).collect(),
+    });
+    let message = Message::new(&[refund_basket_instruction], Some(&maker_pubkey));
+    let recent_blockhash = escrow_setup.litesvm.latest_blockhash();
+    let transaction = Transaction::new(&[&escrow_setup.maker], message, recent_blockhash);
+    escrow_setup.litesvm.send_transaction(transaction).unwrap();
+
+    for mint_a in &mints {
+        let maker_ata = get_associated_token_address_with_program_id(&maker_pubkey, mint_a, &TOKEN_PROGRAM_ID);
+        assert_eq!(get_token_balance(&escrow_setup.litesvm, &maker_ata), 100_000_000);
+    }
+    let (basket_pda, _) = find_basket_address(&maker_pubkey, seed);
+    assert!(escrow_setup.litesvm.get_account(&basket_pda).is_none_or(|account| account.lamports == 0));
+}
+
+#[test]
+fn test_refund_expired_basket() {
+    let mut escrow_setup = setup_escrow_test();
+    let mints = setup_basket_legs(&mut escrow_setup);
+
+    let seed: u64 = 10;
+    let amounts = [10_000_000u64, 20_000_000, 30_000_000];
+    let maker_pubkey = escrow_setup.maker.pubkey();
+    let taker_pubkey = escrow_setup.taker.pubkey();
+    let mut clock: Clock = escrow_setup.litesvm.get_sysvar();
+    let expires_at = clock.unix_timestamp + 60;
+    make_basket(&mut escrow_setup, &mints, &amounts, 50_000_000, seed, expires_at);
+
+    // Anyone can sweep the basket back to the maker, but only once it has expired
+    let mut sweep_instruction = refund_basket_instruction(&RefundBasketParams {
+        maker: maker_pubkey,
+        mint_b: escrow_setup.mint_b,
+        seed,
+        legs: mints.iter().map(|mint_a| (*mint_a, TOKEN_PROGRAM_ID)).collect(),
+    });
+    sweep_instruction.accounts[0] = AccountMeta::new(maker_pubkey, false);
+    let message = Message::new(std::slice::from_ref(&sweep_instruction), Some(&taker_pubkey));
+    let recent_blockhash = escrow_setup.litesvm.latest_blockhash();
+    let transaction = Transaction::new(&[&escrow_setup.taker], message, recent_blockhash);
+    assert_escrow_error(escrow_setup.litesvm.send_transaction(transaction), EscrowError::MissingSigner);
+
+    clock.unix_timestamp = expires_at;
+    escrow_setup.litesvm.set_sysvar(&clock);
+    escrow_setup.litesvm.expire_blockhash();
+    let message = Message::new(&[sweep_instruction], Some(&taker_pubkey));
+    let recent_blockhash = escrow_setup.litesvm.latest_blockhash();
+    let transaction = Transaction::new(&[&escrow_setup.taker], message, recent_blockhash);
+    escrow_setup.litesvm.send_transaction(transaction).unwrap();
+
+    for mint_a in &mints {
+        let maker_ata = get_associated_token_address_with_program_id(&maker_pubkey, mint_a, &TOKEN_PROGRAM_ID);
+        assert_eq!(get_token_balance(&escrow_setup.litesvm, &maker_ata), 100_000_000);
+    }
+    let (basket_pda, _) = find_basket_address(&maker_pubkey, seed);
+    assert!(escrow_setup.litesvm.get_account(&basket_pda).is_none_or(|account| account.lamports == 0));
+}
@@ -0,0 +1,89 @@
+use solana_instruction::{AccountMeta, Instruction};
+use solana_message::Message;
+use solana_pubkey::Pubkey;
+use solana_signer::Signer;
+use solana_transaction::Transaction;
+
+use crate::client::{Escrow, MakeParams, TOKEN_PROGRAM_ID, find_escrow_address, make_instruction};
+use crate::errors::EscrowError;
+use crate::tests::test_helpers::{EscrowTestSetup, assert_escrow_error, setup_escrow_test};
+
+/// The legacy MAKE, carrying the escrow bump in its data, built from the client MAKE_V2 instruction
+fn legacy_make_instruction(params: &MakeParams, bump: u8) -> Instruction {
+    let mut instruction = make_instruction(params);
+    instruction.data[0] = 0;
+    instruction.data.insert(18, bump);
+    instruction
+}
+
+/// Signs the instruction with the maker on a fresh blockhash, so identical attempts are separate transactions
+fn make_transaction(escrow_setup: &mut EscrowTestSetup, instruction: Instruction) -> Transaction {
+    escrow_setup.litesvm.expire_blockhash();
+    let maker_pubkey = escrow_setup.maker.pubkey();
+    let message = Message::new(&[instruction], Some(&maker_pubkey));
+    let recent_blockhash = escrow_setup.litesvm.latest_blockhash();
+    Transaction::new(&[&escrow_setup.maker], message, recent_blockhash)
+}
+
+#[test]
+fn test_make_rejects_non_canonical_bumps() {
+    let mut escrow_setup = setup_escrow_test();
+    let maker_pubkey = escrow_setup.maker.pubkey();
+
+    for seed in 0u8..8 {
+        let params = MakeParams {
+            maker: maker_pubkey,
+            mint_a: escrow_setup.mint_a,
+            mint_b: escrow_setup.mint_b,
+            token_program_a: TOKEN_PROGRAM_ID,
+            amount_a: 1_000_000,
+            amount_b: 2_000_000,
+            seed: seed.into(),
+            expires_at: 0,
+            taker: None,
+            alternatives: vec![],
+        };
+        let (escrow_pda, canonical_bump) = find_escrow_address(&maker_pubkey, seed);
+
+        // Every other bump deriving a valid PDA for the same maker and seed is refused,
+        // whether the escrow account is the non-canonical PDA or the canonical one
+        let non_canonical_bumps: Vec<(u8, Pubkey)> = (0..canonical_bump)
+            .rev()
+            .filter_map(|bump| {
+                Pubkey::create_program_address(&[b"escrow", maker_pubkey.as_ref(), &[seed], &[bump]], &escrow_setup.program_id)
+                    .ok()
+                    .map(|address| (bump, address))
+            })
+            .take(3)
+            .collect();
+        assert!(!non_canonical_bumps.is_empty());
+        for (bump, address) in non_canonical_bumps {
+            let mut instruction = legacy_make_instruction(&params, bump);
+            instruction.accounts[5] = AccountMeta::new(address, false);
+            let transaction = make_transaction(&mut escrow_setup, instruction);
+            assert_escrow_error(escrow_setup.litesvm.send_transaction(transaction), EscrowError::NonCanonicalBump);
+
+            let instruction = legacy_make_instruction(&params, bump);
+            let transaction = make_transaction(&mut escrow_setup, instruction);
+            assert_escrow_error(escrow_setup.litesvm.send_transaction(transaction), EscrowError::NonCanonicalBump);
+        }
+
+        // MAKE_V2 doesn't take a bump
+        let mut instruction = make_instruction(&params);
+        instruction.data.insert(18, canonical_bump);
+        let transaction = make_transaction(&mut escrow_setup, instruction);
+        assert_escrow_error(escrow_setup.litesvm.send_transaction(transaction), EscrowError::InvalidInstructionData);
+
+        // The canonical bump goes through the legacy MAKE, and MAKE_V2 derives it
+        let instruction = if seed % 2 == 0 {
+            legacy_make_instruction(&params, canonical_bump)
+        } else {
+            make_instruction(&params)
+        };
+        let transaction = make_transaction(&mut escrow_setup, instruction);
+        escrow_setup.litesvm.send_transaction(transaction).unwrap();
+
+        let escrow_account = escrow_setup.litesvm.get_account(&escrow_pda).unwrap();
+        assert_eq!(Escrow::from_bytes(&escrow_account.data).unwrap().bump, canonical_bump);
+    }
+}
@@ -0,0 +1,104 @@
+use solana_message::Message;
+use solana_signer::Signer;
+use solana_transaction::Transaction;
+use solana_program::msg;
+
+use crate::client::{Escrow, EscrowSeed, MakeParams, RefundParams, TakeParams, TOKEN_PROGRAM_ID, find_escrow_address, find_vault_address, make_instruction, refund_instruction, take_instruction};
+use crate::tests::test_helpers::{get_token_balance, setup_escrow_test};
+
+#[test]
+fn test_client_escrow_len() {
+    assert_eq!(Escrow::LEN, crate::state::Escrow::LEN);
+}
+
+#[test]
+fn test_client_make_take_refund() {
+    let mut escrow_setup = setup_escrow_test();
+
+    let amount_a: u64 = 30_000_000;
+    let amount_b: u64 = 70_000_000;
+    let maker_pubkey = escrow_setup.maker.pubkey();
+    let taker_pubkey = escrow_setup.taker.pubkey();
+
+    for seed in [1u8, 2u8] {
+        // Make through the client builder
+        let make_instruction = make_instruction(&MakeParams {
+            maker: maker_pubkey,
+            mint_a: escrow_setup.mint_a,
+            mint_b: escrow_setup.mint_b,
+            token_program_a: TOKEN_PROGRAM_ID,
+            amount_a,
+            amount_b,
+            seed: seed.into(),
+            expires_at: 0,
+            taker: Some(taker_pubkey),
+            alternatives: vec![],
+        });
+        let message = Message::new(&[make_instruction], Some(&maker_pubkey));
+        let recent_blockhash = escrow_setup.litesvm.latest_blockhash();
+        let transaction = Transaction::new(&[&escrow_setup.maker], message, recent_blockhash);
+        escrow_setup.litesvm.send_transaction(transaction).unwrap();
+
+        // Decode the escrow state
+        let (escrow_pda, escrow_bump) = find_escrow_address(&maker_pubkey, seed);
+        let escrow_account = escrow_setup.litesvm.get_account(&escrow_pda).unwrap();
+        let escrow = Escrow::from_bytes(&escrow_account.data).unwrap();
+        assert_eq!(escrow.maker, maker_pubkey);
+        assert_eq!(escrow.mint_a, escrow_setup.mint_a);
+        assert_eq!(escrow.mint_b, escrow_setup.mint_b);
+        assert_eq!(escrow.amount_a, amount_a);
+        assert_eq!(escrow.amount_b, amount_b);
+        assert_eq!(escrow.remaining_a, amount_a);
+        assert_eq!(escrow.taker, Some(taker_pubkey));
+        assert_eq!(escrow.seed, EscrowSeed::Byte(seed));
+        assert_eq!(escrow.bump, escrow_bump);
+
+        let vault = find_vault_address(&escrow_pda, &escrow_setup.mint_a, &TOKEN_PROGRAM_ID);
+        assert_eq!(get_token_balance(&escrow_setup.litesvm, &vault), amount_a);
+
+        if seed == 1 {
+            // Take through the client builder
+            let take_instruction = take_instruction(&TakeParams {
+                taker: taker_pubkey,
+                maker: maker_pubkey,
+                mint_a: escrow_setup.mint_a,
+                mint_b: escrow_setup.mint_b,
+                token_program_a: TOKEN_PROGRAM_ID,
+                token_program_b: TOKEN_PROGRAM_ID,
+                treasury: escrow_setup.treasury,
+                seed: seed.into(),
+                amount_a,
+                max_amount_b: Some(amount_b),
+            });
+            let message = Message::new(&[take_instruction], Some(&taker_pubkey));
+            let recent_blockhash = escrow_setup.litesvm.latest_blockhash();
+            let transaction = Transaction::new(&[&escrow_setup.taker], message, recent_blockhash);
+            let tx = escrow_setup.litesvm.send_transaction(transaction).unwrap();
+            assert_eq!(get_token_balance(&escrow_setup.litesvm, &escrow_setup.taker_ata_a), amount_a);
+            assert_eq!(get_token_balance(&escrow_setup.litesvm, &escrow_setup.maker_ata_b), amount_b);
+
+            msg!("\n\nTake escrow with the client builders transaction sucessfull");
+            msg!("CUs Consumed: {}", tx.compute_units_consumed);
+        } else {
+            // Refund through the client builder
+            let refund_instruction = refund_instruction(&RefundParams {
+                maker: maker_pubkey,
+                mint_a: escrow_setup.mint_a,
+                mint_b: escrow_setup.mint_b,
+                token_program_a: TOKEN_PROGRAM_ID,
+                seed: seed.into(),
+            });
+            let message = Message::new(&[refund_instruction], Some(&maker_pubkey));
+            let recent_blockhash = escrow_setup.litesvm.latest_blockhash();
+            let transaction = Transaction::new(&[&escrow_setup.maker], message, recent_blockhash);
+            let tx = escrow_setup.litesvm.send_transaction(transaction).unwrap();
+            assert_eq!(get_token_balance(&escrow_setup.litesvm, &escrow_setup.maker_ata_a), 100_000_000 - amount_a);
+
+            msg!("\n\nRefund escrow with the client builders transaction sucessfull");
+            msg!("CUs Consumed: {}", tx.compute_units_consumed);
+        }
+
+        // Both paths close the escrow
+        assert!(escrow_setup.litesvm.get_account(&escrow_pda).is_none_or(|account| account.lamports == 0));
+    }
+}
@@ -0,0 +1,92 @@
+use solana_message::Message;
+use solana_signer::Signer;
+use solana_transaction::Transaction;
+use solana_program::{clock::Clock, msg};
+
+use crate::client::{DutchAuction, Escrow, EscrowSeed, MakeParams, TakeParams, TOKEN_PROGRAM_ID, find_escrow_address, make_dutch_instruction, take_instruction};
+use crate::errors::EscrowError;
+use crate::tests::test_helpers::{EscrowTestSetup, assert_escrow_error, get_token_balance, setup_escrow_test};
+
+/// Builds a take of the auctioned escrow on a fresh blockhash, so retrying the same take is a new transaction
+fn take_transaction(escrow_setup: &mut EscrowTestSetup, amount_a: u64, max_amount_b: Option<u64>) -> Transaction {
+    let take_params = TakeParams {
+        taker: escrow_setup.taker.pubkey(),
+        maker: escrow_setup.maker.pubkey(),
+        mint_a: escrow_setup.mint_a,
+        mint_b: escrow_setup.mint_b,
+        token_program_a: TOKEN_PROGRAM_ID,
+        token_program_b: TOKEN_PROGRAM_ID,
+        treasury: escrow_setup.treasury,
+        seed: EscrowSeed::Wide(1),
+        amount_a,
+        max_amount_b,
+    };
+    let message = Message::new(&[take_instruction(&take_params)], Some(&escrow_setup.taker.pubkey()));
+    escrow_setup.litesvm.expire_blockhash();
+    let recent_blockhash = escrow_setup.litesvm.latest_blockhash();
+    Transaction::new(&[&escrow_setup.taker], message, recent_blockhash)
+}
+
+#[test]
+fn test_dutch_auction_take() {
+    let mut escrow_setup = setup_escrow_test();
+
+    let maker_pubkey = escrow_setup.maker.pubkey();
+    let mut clock: Clock = escrow_setup.litesvm.get_sysvar();
+    let now = clock.unix_timestamp;
+
+    // Sell 30 mint_a starting at 90 mint_b, down to 30 mint_b over 100 seconds
+    let make_params = MakeParams {
+        maker: maker_pubkey,
+        mint_a: escrow_setup.mint_a,
+        mint_b: escrow_setup.mint_b,
+        token_program_a: TOKEN_PROGRAM_ID,
+        amount_a: 30_000_000,
+        amount_b: 90_000_000,
+        seed: EscrowSeed::Wide(1),
+        expires_at: 0,
+        taker: None,
+        alternatives: vec![],
+    };
+    let auction = DutchAuction {
+        end_amount_b: 30_000_000,
+        auction_start: now,
+        auction_end: now + 100,
+    };
+
+    // The price can't rise over the auction
+    let rising = DutchAuction { end_amount_b: 100_000_000, ..auction };
+    let message = Message::new(&[make_dutch_instruction(&make_params, &rising)], Some(&maker_pubkey));
+    let recent_blockhash = escrow_setup.litesvm.latest_blockhash();
+    let transaction = Transaction::new(&[&escrow_setup.maker], message, recent_blockhash);
+    assert_escrow_error(escrow_setup.litesvm.send_transaction(transaction), EscrowError::InvalidAuctionTerms);
+
+    let message = Message::new(&[make_dutch_instruction(&make_params, &auction)], Some(&maker_pubkey));
+    let recent_blockhash = escrow_setup.litesvm.latest_blockhash();
+    let transaction = Transaction::new(&[&escrow_setup.maker], message, recent_blockhash);
+    escrow_setup.litesvm.send_transaction(transaction).unwrap();
+
+    let (escrow_pda, _) = find_escrow_address(&maker_pubkey, EscrowSeed::Wide(1));
+    let escrow_account = escrow_setup.litesvm.get_account(&escrow_pda).unwrap();
+    assert_eq!(Escrow::from_bytes(&escrow_account.data).unwrap().dutch_auction, Some(auction));
+
+    // Halfway, the whole amount_a costs 60 mint_b: 10 mint_a cost 20 mint_b
+    clock.unix_timestamp = now + 50;
+    escrow_setup.litesvm.set_sysvar(&clock);
+    let transaction = take_transaction(&mut escrow_setup, 10_000_000, Some(19_999_999));
+    assert_escrow_error(escrow_setup.litesvm.send_transaction(transaction), EscrowError::SlippageExceeded);
+    let transaction = take_transaction(&mut escrow_setup, 10_000_000, Some(20_000_000));
+    let tx = escrow_setup.litesvm.send_transaction(transaction).unwrap();
+    assert_eq!(get_token_balance(&escrow_setup.litesvm, &escrow_setup.maker_ata_b), 20_000_000);
+
+    msg!("\n\nTake Dutch auction escrow transaction sucessfull");
+    msg!("CUs Consumed: {}", tx.compute_units_consumed);
+
+    // Once the auction ends, the price stays at end_amount_b
+    clock.unix_timestamp = now + 200;
+    escrow_setup.litesvm.set_sysvar(&clock);
+    let transaction = take_transaction(&mut escrow_setup, 20_000_000, Some(20_000_000));
+    escrow_setup.litesvm.send_transaction(transaction).unwrap();
+    assert_eq!(get_token_balance(&escrow_setup.litesvm, &escrow_setup.maker_ata_b), 40_000_000);
+    assert!(escrow_setup.litesvm.get_account(&escrow_pda).is_none_or(|account| account.lamports == 0));
+}
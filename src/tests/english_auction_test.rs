@@ -0,0 +1,178 @@
+use litesvm_token::{CreateAssociatedTokenAccount, MintTo};
+use solana_keypair::Keypair;
+use solana_message::Message;
+use solana_native_token::LAMPORTS_PER_SOL;
+use solana_pubkey::Pubkey;
+use solana_signer::Signer;
+use solana_transaction::Transaction;
+use solana_program::{clock::Clock, msg};
+use spl_associated_token_account_interface::address::get_associated_token_address_with_program_id;
+
+use crate::client::{BidParams, EnglishAuction, Escrow, EscrowSeed, MakeParams, RefundParams, SettleAuctionParams, TOKEN_PROGRAM_ID, bid_instruction, find_escrow_address, make_auction_instruction, refund_instruction, settle_auction_instruction};
+use crate::errors::EscrowError;
+use crate::tests::test_helpers::{EscrowTestSetup, assert_escrow_error, get_token_balance, setup_escrow_test};
+
+/// Creates a bidder holding 100 mint_b and an empty mint_a ATA
+fn create_bidder(escrow_setup: &mut EscrowTestSetup) -> (Keypair, Pubkey, Pubkey) {
+    let bidder = Keypair::new();
+    escrow_setup.litesvm.airdrop(&bidder.pubkey(), 10 * LAMPORTS_PER_SOL).unwrap();
+    let bidder_ata_a = CreateAssociatedTokenAccount::new(&mut escrow_setup.litesvm, &bidder, &escrow_setup.mint_a)
+        .owner(&bidder.pubkey())
+        .send()
+        .unwrap();
+    let bidder_ata_b = CreateAssociatedTokenAccount::new(&mut escrow_setup.litesvm, &bidder, &escrow_setup.mint_b)
+        .owner(&bidder.pubkey())
+        .send()
+        .unwrap();
+    MintTo::new(&mut escrow_setup.litesvm, &escrow_setup._mint_authority, &escrow_setup.mint_b, &bidder_ata_b, 100_000_000)
+        .send()
+        .unwrap();
+    (bidder, bidder_ata_a, bidder_ata_b)
+}
+
+/// Builds a bid on the auction on a fresh blockhash, so retrying the same bid is a new transaction
+fn bid_transaction(escrow_setup: &mut EscrowTestSetup, bidder: &Keypair, amount_b: u64, previous_bidder: Option<Pubkey>) -> Transaction {
+    let bid_params = BidParams {
+        bidder: bidder.pubkey(),
+        maker: escrow_setup.maker.pubkey(),
+        mint_b: escrow_setup.mint_b,
+        token_program_b: TOKEN_PROGRAM_ID,
+        seed: EscrowSeed::Wide(1),
+        amount_b,
+        previous_bidder,
+    };
+    let message = Message::new(&[bid_instruction(&bid_params)], Some(&bidder.pubkey()));
+    escrow_setup.litesvm.expire_blockhash();
+    let recent_blockhash = escrow_setup.litesvm.latest_blockhash();
+    Transaction::new(&[bidder], message, recent_blockhash)
+}
+
+/// Builds a settlement of the auction on a fresh blockhash, paid by the maker
+fn settle_transaction(escrow_setup: &mut EscrowTestSetup, winner: &Pubkey, bid_vault_payer: &Pubkey) -> Transaction {
+    let settle_params = SettleAuctionParams {
+        maker: escrow_setup.maker.pubkey(),
+        winner: *winner,
+        mint_a: escrow_setup.mint_a,
+        mint_b: escrow_setup.mint_b,
+        token_program_a: TOKEN_PROGRAM_ID,
+        token_program_b: TOKEN_PROGRAM_ID,
+        treasury: escrow_setup.treasury,
+        bid_vault_payer: *bid_vault_payer,
+        seed: EscrowSeed::Wide(1),
+    };
+    let message = Message::new(&[settle_auction_instruction(&settle_params)], Some(&escrow_setup.maker.pubkey()));
+    escrow_setup.litesvm.expire_blockhash();
+    let recent_blockhash = escrow_setup.litesvm.latest_blockhash();
+    Transaction::new(&[&escrow_setup.maker], message, recent_blockhash)
+}
+
+#[test]
+fn test_english_auction() {
+    let mut escrow_setup = setup_escrow_test();
+
+    let maker_pubkey = escrow_setup.maker.pubkey();
+    let mut clock: Clock = escrow_setup.litesvm.get_sysvar();
+    let now = clock.unix_timestamp;
+    let (alice, alice_ata_a, alice_ata_b) = create_bidder(&mut escrow_setup);
+    let (bob, bob_ata_a, bob_ata_b) = create_bidder(&mut escrow_setup);
+
+    // Auction 30 mint_a with a reserve price of 50 mint_b, raised 5 mint_b at a time for 100 seconds
+    let make_params = MakeParams {
+        maker: maker_pubkey,
+        mint_a: escrow_setup.mint_a,
+        mint_b: escrow_setup.mint_b,
+        token_program_a: TOKEN_PROGRAM_ID,
+        amount_a: 30_000_000,
+        amount_b: 50_000_000,
+        seed: EscrowSeed::Wide(1),
+        expires_at: 0,
+        taker: None,
+        alternatives: vec![],
+    };
+    let auction = EnglishAuction {
+        min_increment: 5_000_000,
+        bidding_end: now + 100,
+    };
+    let message = Message::new(&[make_auction_instruction(&make_params, &auction)], Some(&maker_pubkey));
+    let recent_blockhash = escrow_setup.litesvm.latest_blockhash();
+    let transaction = Transaction::new(&[&escrow_setup.maker], message, recent_blockhash);
+    escrow_setup.litesvm.send_transaction(transaction).unwrap();
+
+    let (escrow_pda, _) = find_escrow_address(&maker_pubkey, EscrowSeed::Wide(1));
+    let escrow_account = escrow_setup.litesvm.get_account(&escrow_pda).unwrap();
+    assert_eq!(Escrow::from_bytes(&escrow_account.data).unwrap().english_auction, Some(auction));
+
+    // Creating the bid vault ahead of the first bid doesn't block the auction
+    CreateAssociatedTokenAccount::new(&mut escrow_setup.litesvm, &escrow_setup.taker, &escrow_setup.mint_b)
+        .owner(&escrow_pda)
+        .send()
+        .unwrap();
+
+    // The first bid must reach the reserve price
+    let transaction = bid_transaction(&mut escrow_setup, &alice, 40_000_000, None);
+    assert_escrow_error(escrow_setup.litesvm.send_transaction(transaction), EscrowError::BidTooLow);
+    let transaction = bid_transaction(&mut escrow_setup, &alice, 50_000_000, None);
+    let tx = escrow_setup.litesvm.send_transaction(transaction).unwrap();
+    assert_eq!(get_token_balance(&escrow_setup.litesvm, &alice_ata_b), 50_000_000);
+    let escrow_account = escrow_setup.litesvm.get_account(&escrow_pda).unwrap();
+    assert_eq!(Escrow::from_bytes(&escrow_account.data).unwrap().bid_vault_payer, Some(alice.pubkey()));
+
+    msg!("\n\nBid transaction sucessfull");
+    msg!("CUs Consumed: {}", tx.compute_units_consumed);
+
+    // The maker can no longer cancel the auction
+    let refund_params = RefundParams {
+        maker: maker_pubkey,
+        mint_a: escrow_setup.mint_a,
+        mint_b: escrow_setup.mint_b,
+        token_program_a: TOKEN_PROGRAM_ID,
+        seed: EscrowSeed::Wide(1),
+    };
+    let message = Message::new(&[refund_instruction(&refund_params)], Some(&maker_pubkey));
+    let recent_blockhash = escrow_setup.litesvm.latest_blockhash();
+    let transaction = Transaction::new(&[&escrow_setup.maker], message, recent_blockhash);
+    assert_escrow_error(escrow_setup.litesvm.send_transaction(transaction), EscrowError::AuctionHasBid);
+
+    // Bids are only locked in the escrow ATA of mint_b
+    let mut transaction = bid_transaction(&mut escrow_setup, &bob, 55_000_000, Some(alice.pubkey()));
+    let bid_vault_index = transaction.message.instructions[0].accounts[4] as usize;
+    transaction.message.account_keys[bid_vault_index] = Pubkey::new_unique();
+    let transaction = Transaction::new(&[&bob], transaction.message, escrow_setup.litesvm.latest_blockhash());
+    assert_escrow_error(escrow_setup.litesvm.send_transaction(transaction), EscrowError::InvalidVaultAddress);
+
+    // A higher bid must raise by the minimum increment, and refunds the previous bidder
+    let transaction = bid_transaction(&mut escrow_setup, &bob, 54_000_000, Some(alice.pubkey()));
+    assert_escrow_error(escrow_setup.litesvm.send_transaction(transaction), EscrowError::BidTooLow);
+    let transaction = bid_transaction(&mut escrow_setup, &bob, 55_000_000, Some(alice.pubkey()));
+    escrow_setup.litesvm.send_transaction(transaction).unwrap();
+    assert_eq!(get_token_balance(&escrow_setup.litesvm, &alice_ata_b), 100_000_000);
+    assert_eq!(get_token_balance(&escrow_setup.litesvm, &bob_ata_b), 45_000_000);
+    let escrow_account = escrow_setup.litesvm.get_account(&escrow_pda).unwrap();
+    assert_eq!(Escrow::from_bytes(&escrow_account.data).unwrap().highest_bid, Some((bob.pubkey(), 55_000_000)));
+
+    // The auction can only be settled once the bidding has ended, and then takes no more bids
+    let transaction = settle_transaction(&mut escrow_setup, &bob.pubkey(), &alice.pubkey());
+    assert_escrow_error(escrow_setup.litesvm.send_transaction(transaction), EscrowError::AuctionNotEnded);
+    clock.unix_timestamp = now + 100;
+    escrow_setup.litesvm.set_sysvar(&clock);
+    let transaction = bid_transaction(&mut escrow_setup, &alice, 60_000_000, Some(bob.pubkey()));
+    assert_escrow_error(escrow_setup.litesvm.send_transaction(transaction), EscrowError::AuctionEnded);
+
+    // Only to the highest bidder
+    let transaction = settle_transaction(&mut escrow_setup, &alice.pubkey(), &alice.pubkey());
+    assert_escrow_error(escrow_setup.litesvm.send_transaction(transaction), EscrowError::TakerNotAllowed);
+
+    // Returning the bid vault rent to the first bidder, who paid it
+    let transaction = settle_transaction(&mut escrow_setup, &bob.pubkey(), &bob.pubkey());
+    assert_escrow_error(escrow_setup.litesvm.send_transaction(transaction), EscrowError::BidVaultPayerMismatch);
+    let bid_vault = get_associated_token_address_with_program_id(&escrow_pda, &escrow_setup.mint_b, &TOKEN_PROGRAM_ID);
+    let bid_vault_rent = escrow_setup.litesvm.get_account(&bid_vault).unwrap().lamports;
+    let alice_lamports = escrow_setup.litesvm.get_account(&alice.pubkey()).unwrap().lamports;
+    let transaction = settle_transaction(&mut escrow_setup, &bob.pubkey(), &alice.pubkey());
+    escrow_setup.litesvm.send_transaction(transaction).unwrap();
+    assert_eq!(escrow_setup.litesvm.get_account(&alice.pubkey()).unwrap().lamports, alice_lamports + bid_vault_rent);
+    assert_eq!(get_token_balance(&escrow_setup.litesvm, &bob_ata_a), 30_000_000);
+    assert_eq!(get_token_balance(&escrow_setup.litesvm, &alice_ata_a), 0);
+    assert_eq!(get_token_balance(&escrow_setup.litesvm, &escrow_setup.maker_ata_b), 55_000_000);
+    assert!(escrow_setup.litesvm.get_account(&escrow_pda).is_none_or(|account| account.lamports == 0));
+}
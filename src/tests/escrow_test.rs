@@ -1,14 +1,17 @@
-use solana_instruction::{AccountMeta, Instruction};
+use solana_instruction::{AccountMeta, Instruction, error::InstructionError};
 use solana_message::Message;
 use solana_signer::Signer;
 use solana_transaction::Transaction;
+use solana_transaction_error::TransactionError;
 use spl_associated_token_account_interface::address::get_associated_token_address;
 use solana_pubkey::{Pubkey, pubkey};
-use solana_program::msg;
+use solana_program::{clock::Clock, msg};
+use litesvm_token::CreateAccount;
 use litesvm_token::spl_token::ID as TOKEN_PROGRAM_ID;
 use spl_associated_token_account_interface::program::ID as ASSOCIATED_TOKEN_PROGRAM_ID;
 
-use crate::tests::test_helpers::setup_escrow_test;
+use crate::errors::EscrowError;
+use crate::tests::test_helpers::{assert_escrow_error, get_token_balance, init_config_transaction, send_tokens, set_protocol_fee, setup_escrow_test};
 
 const SYSTEM_PROGRAM_ID: Pubkey = pubkey!("11111111111111111111111111111111");
 
@@ -40,6 +43,7 @@ fn test_make() {
         amount_b.to_le_bytes().to_vec(),
         vec![seed],
         vec![escrow_bump],
+        0i64.to_le_bytes().to_vec(),  // expires_at (no expiry)
     ].concat();
     let make_accounts = vec![
         AccountMeta::new(escrow_setup.maker.pubkey(), true),
@@ -51,6 +55,9 @@ fn test_make() {
         AccountMeta::new(SYSTEM_PROGRAM_ID, false),
         AccountMeta::new(TOKEN_PROGRAM_ID, false),
         AccountMeta::new(ASSOCIATED_TOKEN_PROGRAM_ID, false),
+        AccountMeta::new(escrow_setup.config, false),
+        AccountMeta::new_readonly(escrow_setup.event_authority, false),
+        AccountMeta::new_readonly(escrow_setup.program_id, false),
     ];
     let make_instruction = Instruction {
         program_id: escrow_setup.program_id,
@@ -102,6 +109,7 @@ fn test_take() {
         amount_b.to_le_bytes().to_vec(),
         vec![seed],
         vec![escrow_bump],
+        0i64.to_le_bytes().to_vec(),  // expires_at (no expiry)
     ].concat();
     let make_accounts = vec![
         AccountMeta::new(escrow_setup.maker.pubkey(), true),
@@ -113,6 +121,9 @@ fn test_take() {
         AccountMeta::new(SYSTEM_PROGRAM_ID, false),
         AccountMeta::new(TOKEN_PROGRAM_ID, false),
         AccountMeta::new(ASSOCIATED_TOKEN_PROGRAM_ID, false),
+        AccountMeta::new(escrow_setup.config, false),
+        AccountMeta::new_readonly(escrow_setup.event_authority, false),
+        AccountMeta::new_readonly(escrow_setup.program_id, false),
     ];
     let make_instruction = Instruction {
         program_id: escrow_setup.program_id,
@@ -133,6 +144,7 @@ fn test_take() {
     // Create the take instruction
     let take_data = [
         vec![1u8],  // discriminator
+        amount_a.to_le_bytes().to_vec(),
     ].concat();
     let take_accounts = vec![
         AccountMeta::new(escrow_setup.taker.pubkey(), true),
@@ -146,6 +158,11 @@ fn test_take() {
         AccountMeta::new(escrow_pda, false),
         AccountMeta::new(SYSTEM_PROGRAM_ID, false),
         AccountMeta::new(TOKEN_PROGRAM_ID, false),
+        AccountMeta::new(TOKEN_PROGRAM_ID, false),
+        AccountMeta::new(escrow_setup.config, false),
+        AccountMeta::new_readonly(escrow_setup.program_id, false),  // no fee, so no treasury account
+        AccountMeta::new_readonly(escrow_setup.event_authority, false),
+        AccountMeta::new_readonly(escrow_setup.program_id, false),
     ];
     let take_instruction = Instruction {
         program_id: escrow_setup.program_id,
@@ -153,6 +170,18 @@ fn test_take() {
         data: take_data,
     };
 
+    // A token account of the escrow other than its ATA can't stand in for the vault
+    let fake_vault = CreateAccount::new(&mut escrow_setup.litesvm, &escrow_setup.taker, &escrow_setup.mint_a)
+        .owner(&escrow_pda)
+        .send()
+        .unwrap();
+    let mut fake_take_instruction = take_instruction.clone();
+    fake_take_instruction.accounts[6] = AccountMeta::new(fake_vault, false);
+    let message = Message::new(&[fake_take_instruction], Some(&escrow_setup.taker.pubkey()));
+    let recent_blockhash = escrow_setup.litesvm.latest_blockhash();
+    let transaction = Transaction::new(&[&escrow_setup.taker], message, recent_blockhash);
+    assert_escrow_error(escrow_setup.litesvm.send_transaction(transaction), EscrowError::InvalidVaultAddress);
+
     // Create and send the transaction
     let message = Message::new(&[take_instruction], Some(&escrow_setup.taker.pubkey()));
     let recent_blockhash = escrow_setup.litesvm.latest_blockhash();
@@ -169,6 +198,128 @@ fn test_take() {
 
 }
 
+#[test]
+fn test_take_partial() {
+    let mut escrow_setup = setup_escrow_test();
+
+    let seed: u8 = 7;
+    let amount_a: u64 = 30_000_000;
+    let amount_b: u64 = 70_000_000;
+
+    // Derive the escrow PDA
+    let maker_pubkey = escrow_setup.maker.pubkey();
+    let escrow_seeds: &[&[u8]] = &[b"escrow", maker_pubkey.as_ref(), &[seed]];
+    let (escrow_pda, escrow_bump) = Pubkey::find_program_address(escrow_seeds, &escrow_setup.program_id);
+
+    // Derive the vault PDA
+    let vault = get_associated_token_address(
+        &escrow_pda,
+        &escrow_setup.mint_a,
+    );
+
+    // Create the make instruction
+    let make_data = [
+        vec![0u8],  // discriminator
+        amount_a.to_le_bytes().to_vec(),
+        amount_b.to_le_bytes().to_vec(),
+        vec![seed],
+        vec![escrow_bump],
+        0i64.to_le_bytes().to_vec(),  // expires_at (no expiry)
+    ].concat();
+    let make_accounts = vec![
+        AccountMeta::new(escrow_setup.maker.pubkey(), true),
+        AccountMeta::new(escrow_setup.mint_a, false),
+        AccountMeta::new(escrow_setup.mint_b, false),
+        AccountMeta::new(escrow_setup.maker_ata_a, false),
+        AccountMeta::new(vault, false),
+        AccountMeta::new(escrow_pda, false),
+        AccountMeta::new(SYSTEM_PROGRAM_ID, false),
+        AccountMeta::new(TOKEN_PROGRAM_ID, false),
+        AccountMeta::new(ASSOCIATED_TOKEN_PROGRAM_ID, false),
+        AccountMeta::new(escrow_setup.config, false),
+        AccountMeta::new_readonly(escrow_setup.event_authority, false),
+        AccountMeta::new_readonly(escrow_setup.program_id, false),
+    ];
+    let make_instruction = Instruction {
+        program_id: escrow_setup.program_id,
+        accounts: make_accounts,
+        data: make_data,
+    };
+    let message = Message::new(&[make_instruction], Some(&escrow_setup.maker.pubkey()));
+    let recent_blockhash = escrow_setup.litesvm.latest_blockhash();
+    let transaction = Transaction::new(&[&escrow_setup.maker], message, recent_blockhash);
+    escrow_setup.litesvm.send_transaction(transaction).unwrap();
+
+    let take_accounts = vec![
+        AccountMeta::new(escrow_setup.taker.pubkey(), true),
+        AccountMeta::new(escrow_setup.maker.pubkey(), false),
+        AccountMeta::new(escrow_setup.mint_a, false),
+        AccountMeta::new(escrow_setup.mint_b, false),
+        AccountMeta::new(escrow_setup.taker_ata_a, false),
+        AccountMeta::new(escrow_setup.taker_ata_b, false),
+        AccountMeta::new(vault, false),
+        AccountMeta::new(escrow_setup.maker_ata_b, false),
+        AccountMeta::new(escrow_pda, false),
+        AccountMeta::new(SYSTEM_PROGRAM_ID, false),
+        AccountMeta::new(TOKEN_PROGRAM_ID, false),
+        AccountMeta::new(TOKEN_PROGRAM_ID, false),
+        AccountMeta::new(escrow_setup.config, false),
+        AccountMeta::new(escrow_setup.treasury_ata_b, false),
+        AccountMeta::new_readonly(escrow_setup.event_authority, false),
+        AccountMeta::new_readonly(escrow_setup.program_id, false),
+    ];
+
+    // Fill a third of the escrow: 10_000_000 * 70 / 30 = 23_333_333.33, rounded up against the taker
+    let take_instruction = Instruction {
+        program_id: escrow_setup.program_id,
+        accounts: take_accounts.clone(),
+        data: [vec![1u8], 10_000_000u64.to_le_bytes().to_vec()].concat(),
+    };
+    let message = Message::new(&[take_instruction], Some(&escrow_setup.taker.pubkey()));
+    let recent_blockhash = escrow_setup.litesvm.latest_blockhash();
+    let transaction = Transaction::new(&[&escrow_setup.taker], message, recent_blockhash);
+    escrow_setup.litesvm.send_transaction(transaction).unwrap();
+
+    assert_eq!(get_token_balance(&escrow_setup.litesvm, &escrow_setup.taker_ata_a), 10_000_000);
+    assert_eq!(get_token_balance(&escrow_setup.litesvm, &escrow_setup.maker_ata_b), 23_333_334);
+    assert_eq!(get_token_balance(&escrow_setup.litesvm, &vault), 20_000_000);
+
+    // Taking more than what is left must fail
+    let take_instruction = Instruction {
+        program_id: escrow_setup.program_id,
+        accounts: take_accounts.clone(),
+        data: [vec![1u8], 20_000_001u64.to_le_bytes().to_vec()].concat(),
+    };
+    let message = Message::new(&[take_instruction], Some(&escrow_setup.taker.pubkey()));
+    let recent_blockhash = escrow_setup.litesvm.latest_blockhash();
+    let transaction = Transaction::new(&[&escrow_setup.taker], message, recent_blockhash);
+    assert_escrow_error(escrow_setup.litesvm.send_transaction(transaction), EscrowError::FillExceedsRemaining);
+
+    // Tokens sent to the vault on top of the deposit can't block the last fill
+    send_tokens(&mut escrow_setup.litesvm, &escrow_setup.maker, &escrow_setup.mint_a, &vault, 1);
+
+    // Fill the rest, which pays out the whole vault and closes it and the escrow
+    let take_instruction = Instruction {
+        program_id: escrow_setup.program_id,
+        accounts: take_accounts,
+        data: [vec![1u8], 20_000_000u64.to_le_bytes().to_vec()].concat(),
+    };
+    let message = Message::new(&[take_instruction], Some(&escrow_setup.taker.pubkey()));
+    let recent_blockhash = escrow_setup.litesvm.latest_blockhash();
+    let transaction = Transaction::new(&[&escrow_setup.taker], message, recent_blockhash);
+    let tx = escrow_setup.litesvm.send_transaction(transaction).unwrap();
+
+    assert_eq!(get_token_balance(&escrow_setup.litesvm, &escrow_setup.taker_ata_a), amount_a + 1);
+    assert_eq!(get_token_balance(&escrow_setup.litesvm, &escrow_setup.maker_ata_b), 23_333_334 + 46_666_667);
+    assert!(escrow_setup.litesvm.get_account(&vault).is_none_or(|account| account.lamports == 0));
+    assert!(escrow_setup.litesvm.get_account(&escrow_pda).is_none_or(|account| account.lamports == 0));
+
+    // Log transaction details
+    msg!("\n\nPartial take escrow transactions sucessfull");
+    msg!("CUs Consumed: {}", tx.compute_units_consumed);
+
+}
+
 #[test]
 fn test_refund() {
     let mut escrow_setup = setup_escrow_test();
@@ -197,6 +348,7 @@ fn test_refund() {
         amount_b.to_le_bytes().to_vec(),
         vec![seed],
         vec![escrow_bump],
+        0i64.to_le_bytes().to_vec(),  // expires_at (no expiry)
     ].concat();
     let make_accounts = vec![
         AccountMeta::new(escrow_setup.maker.pubkey(), true),
@@ -208,6 +360,9 @@ fn test_refund() {
         AccountMeta::new(SYSTEM_PROGRAM_ID, false),
         AccountMeta::new(TOKEN_PROGRAM_ID, false),
         AccountMeta::new(ASSOCIATED_TOKEN_PROGRAM_ID, false),
+        AccountMeta::new(escrow_setup.config, false),
+        AccountMeta::new_readonly(escrow_setup.event_authority, false),
+        AccountMeta::new_readonly(escrow_setup.program_id, false),
     ];
     let make_instruction = Instruction {
         program_id: escrow_setup.program_id,
@@ -238,6 +393,8 @@ fn test_refund() {
         AccountMeta::new(escrow_pda, false),
         AccountMeta::new(SYSTEM_PROGRAM_ID, false),
         AccountMeta::new(TOKEN_PROGRAM_ID, false),
+        AccountMeta::new_readonly(escrow_setup.event_authority, false),
+        AccountMeta::new_readonly(escrow_setup.program_id, false),
     ];
     let refund_instruction = Instruction {
         program_id: escrow_setup.program_id,
@@ -259,4 +416,868 @@ fn test_refund() {
     msg!("\n\nRefund escrow transaction sucessfull");
     msg!("CUs Consumed: {}", tx.compute_units_consumed);
 
-}
\ No newline at end of file
+}
+
+#[test]
+fn test_close_expired() {
+    let mut escrow_setup = setup_escrow_test();
+
+    let seed: u8 = 42;
+    let amount_a: u64 = 70_000_000;
+    let amount_b: u64 = 30_000_000;
+    let mut clock: Clock = escrow_setup.litesvm.get_sysvar();
+    let expires_at = clock.unix_timestamp + 3_600;
+
+    // Derive the escrow PDA
+    let maker_pubkey = escrow_setup.maker.pubkey();
+    let escrow_seeds: &[&[u8]] = &[b"escrow", maker_pubkey.as_ref(), &[seed]];
+    let (escrow_pda, escrow_bump) = Pubkey::find_program_address(escrow_seeds, &escrow_setup.program_id);
+
+    // Derive the vault PDA
+    let vault = get_associated_token_address(
+        &escrow_pda,
+        &escrow_setup.mint_a,
+    );
+
+    // Create the make instruction with an expiry
+    let make_data = [
+        vec![0u8],  // discriminator
+        amount_a.to_le_bytes().to_vec(),
+        amount_b.to_le_bytes().to_vec(),
+        vec![seed],
+        vec![escrow_bump],
+        expires_at.to_le_bytes().to_vec(),
+    ].concat();
+    let make_accounts = vec![
+        AccountMeta::new(escrow_setup.maker.pubkey(), true),
+        AccountMeta::new(escrow_setup.mint_a, false),
+        AccountMeta::new(escrow_setup.mint_b, false),
+        AccountMeta::new(escrow_setup.maker_ata_a, false),
+        AccountMeta::new(vault, false),
+        AccountMeta::new(escrow_pda, false),
+        AccountMeta::new(SYSTEM_PROGRAM_ID, false),
+        AccountMeta::new(TOKEN_PROGRAM_ID, false),
+        AccountMeta::new(ASSOCIATED_TOKEN_PROGRAM_ID, false),
+        AccountMeta::new(escrow_setup.config, false),
+        AccountMeta::new_readonly(escrow_setup.event_authority, false),
+        AccountMeta::new_readonly(escrow_setup.program_id, false),
+    ];
+    let make_instruction = Instruction {
+        program_id: escrow_setup.program_id,
+        accounts: make_accounts,
+        data: make_data,
+    };
+    let message = Message::new(&[make_instruction], Some(&escrow_setup.maker.pubkey()));
+    let recent_blockhash = escrow_setup.litesvm.latest_blockhash();
+    let transaction = Transaction::new(&[&escrow_setup.maker], message, recent_blockhash);
+    escrow_setup.litesvm.send_transaction(transaction).unwrap();
+
+    // Anyone can send the close expired instruction, here the taker pays for the transaction
+    let close_expired_instruction = Instruction {
+        program_id: escrow_setup.program_id,
+        accounts: vec![
+            AccountMeta::new(escrow_setup.maker.pubkey(), false),
+            AccountMeta::new(escrow_setup.mint_a, false),
+            AccountMeta::new(escrow_setup.mint_b, false),
+            AccountMeta::new(escrow_setup.maker_ata_a, false),
+            AccountMeta::new(vault, false),
+            AccountMeta::new(escrow_pda, false),
+            AccountMeta::new(SYSTEM_PROGRAM_ID, false),
+            AccountMeta::new(TOKEN_PROGRAM_ID, false),
+            AccountMeta::new_readonly(escrow_setup.event_authority, false),
+            AccountMeta::new_readonly(escrow_setup.program_id, false),
+        ],
+        data: vec![3u8],  // discriminator
+    };
+
+    // Closing before the expiry must fail
+    let message = Message::new(std::slice::from_ref(&close_expired_instruction), Some(&escrow_setup.taker.pubkey()));
+    let recent_blockhash = escrow_setup.litesvm.latest_blockhash();
+    let transaction = Transaction::new(&[&escrow_setup.taker], message, recent_blockhash);
+    assert_escrow_error(escrow_setup.litesvm.send_transaction(transaction), EscrowError::EscrowNotExpired);
+
+    // Move the clock past the expiry
+    clock.unix_timestamp = expires_at;
+    escrow_setup.litesvm.set_sysvar(&clock);
+    escrow_setup.litesvm.expire_blockhash();
+
+    // Taking after the expiry must fail
+    let take_instruction = Instruction {
+        program_id: escrow_setup.program_id,
+        accounts: vec![
+            AccountMeta::new(escrow_setup.taker.pubkey(), true),
+            AccountMeta::new(escrow_setup.maker.pubkey(), false),
+            AccountMeta::new(escrow_setup.mint_a, false),
+            AccountMeta::new(escrow_setup.mint_b, false),
+            AccountMeta::new(escrow_setup.taker_ata_a, false),
+            AccountMeta::new(escrow_setup.taker_ata_b, false),
+            AccountMeta::new(vault, false),
+            AccountMeta::new(escrow_setup.maker_ata_b, false),
+            AccountMeta::new(escrow_pda, false),
+            AccountMeta::new(SYSTEM_PROGRAM_ID, false),
+            AccountMeta::new(TOKEN_PROGRAM_ID, false),
+            AccountMeta::new(TOKEN_PROGRAM_ID, false),
+            AccountMeta::new(escrow_setup.config, false),
+            AccountMeta::new(escrow_setup.treasury_ata_b, false),
+            AccountMeta::new_readonly(escrow_setup.event_authority, false),
+            AccountMeta::new_readonly(escrow_setup.program_id, false),
+        ],
+        data: [vec![1u8], amount_a.to_le_bytes().to_vec()].concat(),
+    };
+    let message = Message::new(&[take_instruction], Some(&escrow_setup.taker.pubkey()));
+    let recent_blockhash = escrow_setup.litesvm.latest_blockhash();
+    let transaction = Transaction::new(&[&escrow_setup.taker], message, recent_blockhash);
+    assert_escrow_error(escrow_setup.litesvm.send_transaction(transaction), EscrowError::EscrowExpired);
+
+    // Nor can anyone pass another token account of the escrow as its vault, stranding the real one
+    let fake_vault = CreateAccount::new(&mut escrow_setup.litesvm, &escrow_setup.taker, &escrow_setup.mint_a)
+        .owner(&escrow_pda)
+        .send()
+        .unwrap();
+    let mut fake_close_instruction = close_expired_instruction.clone();
+    fake_close_instruction.accounts[4] = AccountMeta::new(fake_vault, false);
+    let message = Message::new(&[fake_close_instruction], Some(&escrow_setup.taker.pubkey()));
+    let recent_blockhash = escrow_setup.litesvm.latest_blockhash();
+    let transaction = Transaction::new(&[&escrow_setup.taker], message, recent_blockhash);
+    assert_escrow_error(escrow_setup.litesvm.send_transaction(transaction), EscrowError::InvalidVaultAddress);
+
+    // Closing after the expiry returns the tokens and the rent to the maker
+    let maker_lamports = escrow_setup.litesvm.get_balance(&escrow_setup.maker.pubkey()).unwrap();
+    let message = Message::new(&[close_expired_instruction], Some(&escrow_setup.taker.pubkey()));
+    let recent_blockhash = escrow_setup.litesvm.latest_blockhash();
+    let transaction = Transaction::new(&[&escrow_setup.taker], message, recent_blockhash);
+    let tx = escrow_setup.litesvm.send_transaction(transaction).unwrap();
+
+    assert_eq!(get_token_balance(&escrow_setup.litesvm, &escrow_setup.maker_ata_a), 100_000_000);
+    assert!(escrow_setup.litesvm.get_balance(&escrow_setup.maker.pubkey()).unwrap() > maker_lamports);
+    assert!(escrow_setup.litesvm.get_account(&escrow_pda).is_none_or(|account| account.lamports == 0));
+
+    // Log transaction details
+    msg!("\n\nClose expired escrow transaction sucessfull");
+    msg!("CUs Consumed: {}", tx.compute_units_consumed);
+
+}
+
+#[test]
+fn test_make_errors() {
+    let mut escrow_setup = setup_escrow_test();
+
+    let seed: u8 = 9;
+
+    // Derive the escrow PDA
+    let maker_pubkey = escrow_setup.maker.pubkey();
+    let escrow_seeds: &[&[u8]] = &[b"escrow", maker_pubkey.as_ref(), &[seed]];
+    let (escrow_pda, escrow_bump) = Pubkey::find_program_address(escrow_seeds, &escrow_setup.program_id);
+
+    // Derive the vault PDA
+    let vault = get_associated_token_address(
+        &escrow_pda,
+        &escrow_setup.mint_a,
+    );
+
+    let make_accounts = |maker_is_signer: bool| vec![
+        AccountMeta::new(escrow_setup.maker.pubkey(), maker_is_signer),
+        AccountMeta::new(escrow_setup.mint_a, false),
+        AccountMeta::new(escrow_setup.mint_b, false),
+        AccountMeta::new(escrow_setup.maker_ata_a, false),
+        AccountMeta::new(vault, false),
+        AccountMeta::new(escrow_pda, false),
+        AccountMeta::new(SYSTEM_PROGRAM_ID, false),
+        AccountMeta::new(TOKEN_PROGRAM_ID, false),
+        AccountMeta::new(ASSOCIATED_TOKEN_PROGRAM_ID, false),
+        AccountMeta::new(escrow_setup.config, false),
+        AccountMeta::new_readonly(escrow_setup.event_authority, false),
+        AccountMeta::new_readonly(escrow_setup.program_id, false),
+    ];
+    let make_data = |amount_a: u64, amount_b: u64| [
+        vec![0u8],  // discriminator
+        amount_a.to_le_bytes().to_vec(),
+        amount_b.to_le_bytes().to_vec(),
+        vec![seed],
+        vec![escrow_bump],
+        0i64.to_le_bytes().to_vec(),  // expires_at (no expiry)
+    ].concat();
+
+    // A maker that did not sign is reported as a missing signer
+    let make_instruction = Instruction {
+        program_id: escrow_setup.program_id,
+        accounts: make_accounts(false),
+        data: make_data(70_000_000, 30_000_000),
+    };
+    let message = Message::new(&[make_instruction], Some(&escrow_setup.taker.pubkey()));
+    let recent_blockhash = escrow_setup.litesvm.latest_blockhash();
+    let transaction = Transaction::new(&[&escrow_setup.taker], message, recent_blockhash);
+    assert_escrow_error(escrow_setup.litesvm.send_transaction(transaction), EscrowError::MissingSigner);
+
+    // A zero amount is reported as such
+    let make_instruction = Instruction {
+        program_id: escrow_setup.program_id,
+        accounts: make_accounts(true),
+        data: make_data(70_000_000, 0),
+    };
+    let message = Message::new(&[make_instruction], Some(&escrow_setup.maker.pubkey()));
+    let recent_blockhash = escrow_setup.litesvm.latest_blockhash();
+    let transaction = Transaction::new(&[&escrow_setup.maker], message, recent_blockhash);
+    assert_escrow_error(escrow_setup.litesvm.send_transaction(transaction), EscrowError::ZeroAmount);
+
+    // Asking for the deposited mint is rejected
+    let mut accounts = make_accounts(true);
+    accounts[2] = AccountMeta::new(escrow_setup.mint_a, false);
+    let make_instruction = Instruction {
+        program_id: escrow_setup.program_id,
+        accounts,
+        data: make_data(70_000_000, 30_000_000),
+    };
+    let message = Message::new(&[make_instruction], Some(&escrow_setup.maker.pubkey()));
+    let recent_blockhash = escrow_setup.litesvm.latest_blockhash();
+    let transaction = Transaction::new(&[&escrow_setup.maker], message, recent_blockhash);
+    assert_escrow_error(escrow_setup.litesvm.send_transaction(transaction), EscrowError::SameMint);
+
+}
+
+#[test]
+fn test_take_with_protocol_fee() {
+    let mut escrow_setup = setup_escrow_test();
+
+    let seed: u8 = 11;
+    let amount_a: u64 = 30_000_000;
+    let amount_b: u64 = 70_000_000;
+
+    // Charge 1% at make time
+    set_protocol_fee(&mut escrow_setup, 100);
+
+    // Derive the escrow PDA
+    let maker_pubkey = escrow_setup.maker.pubkey();
+    let escrow_seeds: &[&[u8]] = &[b"escrow", maker_pubkey.as_ref(), &[seed]];
+    let (escrow_pda, escrow_bump) = Pubkey::find_program_address(escrow_seeds, &escrow_setup.program_id);
+
+    // Derive the vault PDA
+    let vault = get_associated_token_address(
+        &escrow_pda,
+        &escrow_setup.mint_a,
+    );
+
+    // Create the make instruction
+    let make_data = [
+        vec![0u8],  // discriminator
+        amount_a.to_le_bytes().to_vec(),
+        amount_b.to_le_bytes().to_vec(),
+        vec![seed],
+        vec![escrow_bump],
+        0i64.to_le_bytes().to_vec(),  // expires_at (no expiry)
+    ].concat();
+    let make_accounts = vec![
+        AccountMeta::new(escrow_setup.maker.pubkey(), true),
+        AccountMeta::new(escrow_setup.mint_a, false),
+        AccountMeta::new(escrow_setup.mint_b, false),
+        AccountMeta::new(escrow_setup.maker_ata_a, false),
+        AccountMeta::new(vault, false),
+        AccountMeta::new(escrow_pda, false),
+        AccountMeta::new(SYSTEM_PROGRAM_ID, false),
+        AccountMeta::new(TOKEN_PROGRAM_ID, false),
+        AccountMeta::new(ASSOCIATED_TOKEN_PROGRAM_ID, false),
+        AccountMeta::new(escrow_setup.config, false),
+        AccountMeta::new_readonly(escrow_setup.event_authority, false),
+        AccountMeta::new_readonly(escrow_setup.program_id, false),
+    ];
+    let make_instruction = Instruction {
+        program_id: escrow_setup.program_id,
+        accounts: make_accounts,
+        data: make_data,
+    };
+    let message = Message::new(&[make_instruction], Some(&escrow_setup.maker.pubkey()));
+    let recent_blockhash = escrow_setup.litesvm.latest_blockhash();
+    let transaction = Transaction::new(&[&escrow_setup.maker], message, recent_blockhash);
+    escrow_setup.litesvm.send_transaction(transaction).unwrap();
+
+    // Raising the fee afterwards does not apply to the open escrow
+    set_protocol_fee(&mut escrow_setup, 500);
+
+    // Only the admin can change the config
+    let update_config_instruction = Instruction {
+        program_id: escrow_setup.program_id,
+        accounts: vec![
+            AccountMeta::new(escrow_setup.taker.pubkey(), true),
+            AccountMeta::new(escrow_setup.config, false),
+        ],
+        data: [
+            vec![5u8],  // discriminator
+            0u16.to_le_bytes().to_vec(),
+            escrow_setup.taker.pubkey().to_bytes().to_vec(),
+            escrow_setup.taker.pubkey().to_bytes().to_vec(),
+            escrow_setup.taker.pubkey().to_bytes().to_vec(),
+        ].concat(),
+    };
+    let message = Message::new(&[update_config_instruction], Some(&escrow_setup.taker.pubkey()));
+    let recent_blockhash = escrow_setup.litesvm.latest_blockhash();
+    let transaction = Transaction::new(&[&escrow_setup.taker], message, recent_blockhash);
+    assert_escrow_error(escrow_setup.litesvm.send_transaction(transaction), EscrowError::Unauthorized);
+
+    // Create the take instruction
+    let take_instruction = Instruction {
+        program_id: escrow_setup.program_id,
+        accounts: vec![
+            AccountMeta::new(escrow_setup.taker.pubkey(), true),
+            AccountMeta::new(escrow_setup.maker.pubkey(), false),
+            AccountMeta::new(escrow_setup.mint_a, false),
+            AccountMeta::new(escrow_setup.mint_b, false),
+            AccountMeta::new(escrow_setup.taker_ata_a, false),
+            AccountMeta::new(escrow_setup.taker_ata_b, false),
+            AccountMeta::new(vault, false),
+            AccountMeta::new(escrow_setup.maker_ata_b, false),
+            AccountMeta::new(escrow_pda, false),
+            AccountMeta::new(SYSTEM_PROGRAM_ID, false),
+            AccountMeta::new(TOKEN_PROGRAM_ID, false),
+            AccountMeta::new(TOKEN_PROGRAM_ID, false),
+            AccountMeta::new(escrow_setup.config, false),
+            AccountMeta::new(escrow_setup.treasury_ata_b, false),
+            AccountMeta::new_readonly(escrow_setup.event_authority, false),
+            AccountMeta::new_readonly(escrow_setup.program_id, false),
+        ],
+        data: [vec![1u8], amount_a.to_le_bytes().to_vec()].concat(),
+    };
+    let message = Message::new(&[take_instruction], Some(&escrow_setup.taker.pubkey()));
+    let recent_blockhash = escrow_setup.litesvm.latest_blockhash();
+    let transaction = Transaction::new(&[&escrow_setup.taker], message, recent_blockhash);
+    let tx = escrow_setup.litesvm.send_transaction(transaction).unwrap();
+
+    // The 1% fee snapshotted at make is skimmed from the mint_b leg
+    assert_eq!(get_token_balance(&escrow_setup.litesvm, &escrow_setup.treasury_ata_b), 700_000);
+    assert_eq!(get_token_balance(&escrow_setup.litesvm, &escrow_setup.maker_ata_b), 69_300_000);
+    assert_eq!(get_token_balance(&escrow_setup.litesvm, &escrow_setup.taker_ata_a), amount_a);
+
+    // Log transaction details
+    msg!("\n\nTake escrow with protocol fee transaction sucessfull");
+    msg!("CUs Consumed: {}", tx.compute_units_consumed);
+
+}
+#[test]
+fn test_config_authority_and_fee_cap() {
+    let mut escrow_setup = setup_escrow_test();
+
+    // Only the upgrade authority can create the config
+    let transaction = init_config_transaction(&escrow_setup.litesvm, &escrow_setup.taker, 0, &escrow_setup.taker.pubkey(), &escrow_setup.taker.pubkey());
+    assert_escrow_error(escrow_setup.litesvm.send_transaction(transaction), EscrowError::Unauthorized);
+
+    // The fee is capped at 1000 basis points
+    let update_config_instruction = Instruction {
+        program_id: escrow_setup.program_id,
+        accounts: vec![
+            AccountMeta::new(escrow_setup.admin.pubkey(), true),
+            AccountMeta::new(escrow_setup.config, false),
+        ],
+        data: [
+            vec![5u8],  // discriminator
+            1_001u16.to_le_bytes().to_vec(),
+            escrow_setup.treasury.to_bytes().to_vec(),
+            escrow_setup.admin.pubkey().to_bytes().to_vec(),
+            escrow_setup.price_feed_program.to_bytes().to_vec(),
+        ].concat(),
+    };
+    let message = Message::new(&[update_config_instruction], Some(&escrow_setup.admin.pubkey()));
+    let recent_blockhash = escrow_setup.litesvm.latest_blockhash();
+    let transaction = Transaction::new(&[&escrow_setup.admin], message, recent_blockhash);
+    assert_escrow_error(escrow_setup.litesvm.send_transaction(transaction), EscrowError::InvalidFee);
+    set_protocol_fee(&mut escrow_setup, 1_000);
+}
+
+#[test]
+fn test_take_private_escrow() {
+    let mut escrow_setup = setup_escrow_test();
+
+    let seed: u8 = 12;
+    let amount_a: u64 = 30_000_000;
+    let amount_b: u64 = 70_000_000;
+
+    // Derive the escrow PDA
+    let maker_pubkey = escrow_setup.maker.pubkey();
+    let escrow_seeds: &[&[u8]] = &[b"escrow", maker_pubkey.as_ref(), &[seed]];
+    let (escrow_pda, escrow_bump) = Pubkey::find_program_address(escrow_seeds, &escrow_setup.program_id);
+
+    // Derive the vault PDA
+    let vault = get_associated_token_address(
+        &escrow_pda,
+        &escrow_setup.mint_a,
+    );
+
+    // Create the make instruction reserving the escrow for the taker
+    let make_data = [
+        vec![0u8],  // discriminator
+        amount_a.to_le_bytes().to_vec(),
+        amount_b.to_le_bytes().to_vec(),
+        vec![seed],
+        vec![escrow_bump],
+        0i64.to_le_bytes().to_vec(),  // expires_at (no expiry)
+        escrow_setup.taker.pubkey().to_bytes().to_vec(),  // designated taker
+    ].concat();
+    let make_accounts = vec![
+        AccountMeta::new(escrow_setup.maker.pubkey(), true),
+        AccountMeta::new(escrow_setup.mint_a, false),
+        AccountMeta::new(escrow_setup.mint_b, false),
+        AccountMeta::new(escrow_setup.maker_ata_a, false),
+        AccountMeta::new(vault, false),
+        AccountMeta::new(escrow_pda, false),
+        AccountMeta::new(SYSTEM_PROGRAM_ID, false),
+        AccountMeta::new(TOKEN_PROGRAM_ID, false),
+        AccountMeta::new(ASSOCIATED_TOKEN_PROGRAM_ID, false),
+        AccountMeta::new(escrow_setup.config, false),
+        AccountMeta::new_readonly(escrow_setup.event_authority, false),
+        AccountMeta::new_readonly(escrow_setup.program_id, false),
+    ];
+    let make_instruction = Instruction {
+        program_id: escrow_setup.program_id,
+        accounts: make_accounts,
+        data: make_data,
+    };
+    let message = Message::new(&[make_instruction], Some(&escrow_setup.maker.pubkey()));
+    let recent_blockhash = escrow_setup.litesvm.latest_blockhash();
+    let transaction = Transaction::new(&[&escrow_setup.maker], message, recent_blockhash);
+    escrow_setup.litesvm.send_transaction(transaction).unwrap();
+
+    // Any signer other than the designated taker is rejected
+    let other_taker_instruction = Instruction {
+        program_id: escrow_setup.program_id,
+        accounts: vec![
+            AccountMeta::new(escrow_setup.maker.pubkey(), true),
+            AccountMeta::new(escrow_setup.maker.pubkey(), false),
+            AccountMeta::new(escrow_setup.mint_a, false),
+            AccountMeta::new(escrow_setup.mint_b, false),
+            AccountMeta::new(escrow_setup.maker_ata_a, false),
+            AccountMeta::new(escrow_setup.maker_ata_b, false),
+            AccountMeta::new(vault, false),
+            AccountMeta::new(escrow_setup.maker_ata_b, false),
+            AccountMeta::new(escrow_pda, false),
+            AccountMeta::new(SYSTEM_PROGRAM_ID, false),
+            AccountMeta::new(TOKEN_PROGRAM_ID, false),
+            AccountMeta::new(TOKEN_PROGRAM_ID, false),
+            AccountMeta::new(escrow_setup.config, false),
+            AccountMeta::new(escrow_setup.treasury_ata_b, false),
+            AccountMeta::new_readonly(escrow_setup.event_authority, false),
+            AccountMeta::new_readonly(escrow_setup.program_id, false),
+        ],
+        data: [vec![1u8], amount_a.to_le_bytes().to_vec()].concat(),
+    };
+    let message = Message::new(&[other_taker_instruction], Some(&escrow_setup.maker.pubkey()));
+    let recent_blockhash = escrow_setup.litesvm.latest_blockhash();
+    let transaction = Transaction::new(&[&escrow_setup.maker], message, recent_blockhash);
+    assert_escrow_error(escrow_setup.litesvm.send_transaction(transaction), EscrowError::TakerNotAllowed);
+
+    // The designated taker can take it
+    let take_instruction = Instruction {
+        program_id: escrow_setup.program_id,
+        accounts: vec![
+            AccountMeta::new(escrow_setup.taker.pubkey(), true),
+            AccountMeta::new(escrow_setup.maker.pubkey(), false),
+            AccountMeta::new(escrow_setup.mint_a, false),
+            AccountMeta::new(escrow_setup.mint_b, false),
+            AccountMeta::new(escrow_setup.taker_ata_a, false),
+            AccountMeta::new(escrow_setup.taker_ata_b, false),
+            AccountMeta::new(vault, false),
+            AccountMeta::new(escrow_setup.maker_ata_b, false),
+            AccountMeta::new(escrow_pda, false),
+            AccountMeta::new(SYSTEM_PROGRAM_ID, false),
+            AccountMeta::new(TOKEN_PROGRAM_ID, false),
+            AccountMeta::new(TOKEN_PROGRAM_ID, false),
+            AccountMeta::new(escrow_setup.config, false),
+            AccountMeta::new(escrow_setup.treasury_ata_b, false),
+            AccountMeta::new_readonly(escrow_setup.event_authority, false),
+            AccountMeta::new_readonly(escrow_setup.program_id, false),
+        ],
+        data: [vec![1u8], amount_a.to_le_bytes().to_vec()].concat(),
+    };
+    let message = Message::new(&[take_instruction], Some(&escrow_setup.taker.pubkey()));
+    let recent_blockhash = escrow_setup.litesvm.latest_blockhash();
+    let transaction = Transaction::new(&[&escrow_setup.taker], message, recent_blockhash);
+    let tx = escrow_setup.litesvm.send_transaction(transaction).unwrap();
+
+    assert_eq!(get_token_balance(&escrow_setup.litesvm, &escrow_setup.taker_ata_a), amount_a);
+    assert!(escrow_setup.litesvm.get_account(&escrow_pda).is_none_or(|account| account.lamports == 0));
+
+    // Log transaction details
+    msg!("\n\nTake private escrow transaction sucessfull");
+    msg!("CUs Consumed: {}", tx.compute_units_consumed);
+
+}
+
+#[test]
+fn test_update() {
+    let mut escrow_setup = setup_escrow_test();
+
+    let seed: u8 = 13;
+    let amount_a: u64 = 30_000_000;
+    let amount_b: u64 = 70_000_000;
+
+    // Derive the escrow PDA
+    let maker_pubkey = escrow_setup.maker.pubkey();
+    let escrow_seeds: &[&[u8]] = &[b"escrow", maker_pubkey.as_ref(), &[seed]];
+    let (escrow_pda, escrow_bump) = Pubkey::find_program_address(escrow_seeds, &escrow_setup.program_id);
+
+    // Derive the vault PDA
+    let vault = get_associated_token_address(
+        &escrow_pda,
+        &escrow_setup.mint_a,
+    );
+
+    // Create the make instruction
+    let make_data = [
+        vec![0u8],  // discriminator
+        amount_a.to_le_bytes().to_vec(),
+        amount_b.to_le_bytes().to_vec(),
+        vec![seed],
+        vec![escrow_bump],
+        0i64.to_le_bytes().to_vec(),  // expires_at (no expiry)
+    ].concat();
+    let make_accounts = vec![
+        AccountMeta::new(escrow_setup.maker.pubkey(), true),
+        AccountMeta::new(escrow_setup.mint_a, false),
+        AccountMeta::new(escrow_setup.mint_b, false),
+        AccountMeta::new(escrow_setup.maker_ata_a, false),
+        AccountMeta::new(vault, false),
+        AccountMeta::new(escrow_pda, false),
+        AccountMeta::new(SYSTEM_PROGRAM_ID, false),
+        AccountMeta::new(TOKEN_PROGRAM_ID, false),
+        AccountMeta::new(ASSOCIATED_TOKEN_PROGRAM_ID, false),
+        AccountMeta::new(escrow_setup.config, false),
+        AccountMeta::new_readonly(escrow_setup.event_authority, false),
+        AccountMeta::new_readonly(escrow_setup.program_id, false),
+    ];
+    let make_instruction = Instruction {
+        program_id: escrow_setup.program_id,
+        accounts: make_accounts,
+        data: make_data,
+    };
+    let message = Message::new(&[make_instruction], Some(&escrow_setup.maker.pubkey()));
+    let recent_blockhash = escrow_setup.litesvm.latest_blockhash();
+    let transaction = Transaction::new(&[&escrow_setup.maker], message, recent_blockhash);
+    escrow_setup.litesvm.send_transaction(transaction).unwrap();
+
+    let update_accounts = vec![
+        AccountMeta::new(escrow_setup.maker.pubkey(), true),
+        AccountMeta::new(escrow_setup.mint_a, false),
+        AccountMeta::new(escrow_setup.maker_ata_a, false),
+        AccountMeta::new(vault, false),
+        AccountMeta::new(escrow_pda, false),
+        AccountMeta::new(SYSTEM_PROGRAM_ID, false),
+        AccountMeta::new(TOKEN_PROGRAM_ID, false),
+    ];
+
+    // Depositing and withdrawing in the same update is rejected
+    let update_instruction = Instruction {
+        program_id: escrow_setup.program_id,
+        accounts: update_accounts.clone(),
+        data: [
+            vec![6u8],  // discriminator
+            40_000_000u64.to_le_bytes().to_vec(),  // amount_b
+            1u64.to_le_bytes().to_vec(),  // deposit_a
+            1u64.to_le_bytes().to_vec(),  // withdraw_a
+        ].concat(),
+    };
+    let message = Message::new(&[update_instruction], Some(&escrow_setup.maker.pubkey()));
+    let recent_blockhash = escrow_setup.litesvm.latest_blockhash();
+    let transaction = Transaction::new(&[&escrow_setup.maker], message, recent_blockhash);
+    assert_escrow_error(escrow_setup.litesvm.send_transaction(transaction), EscrowError::InvalidInstructionData);
+
+    // Withdraw 10 tokens and ask 40 tokens for the 20 left
+    let update_instruction = Instruction {
+        program_id: escrow_setup.program_id,
+        accounts: update_accounts.clone(),
+        data: [
+            vec![6u8],  // discriminator
+            40_000_000u64.to_le_bytes().to_vec(),  // amount_b
+            0u64.to_le_bytes().to_vec(),  // deposit_a
+            10_000_000u64.to_le_bytes().to_vec(),  // withdraw_a
+        ].concat(),
+    };
+    let message = Message::new(&[update_instruction], Some(&escrow_setup.maker.pubkey()));
+    let recent_blockhash = escrow_setup.litesvm.latest_blockhash();
+    let transaction = Transaction::new(&[&escrow_setup.maker], message, recent_blockhash);
+    escrow_setup.litesvm.send_transaction(transaction).unwrap();
+    assert_eq!(get_token_balance(&escrow_setup.litesvm, &vault), 20_000_000);
+    assert_eq!(get_token_balance(&escrow_setup.litesvm, &escrow_setup.maker_ata_a), 80_000_000);
+
+    // Top up 5 tokens and ask 50 tokens for the 25 now in the vault
+    let update_instruction = Instruction {
+        program_id: escrow_setup.program_id,
+        accounts: update_accounts,
+        data: [
+            vec![6u8],  // discriminator
+            50_000_000u64.to_le_bytes().to_vec(),  // amount_b
+            5_000_000u64.to_le_bytes().to_vec(),  // deposit_a
+            0u64.to_le_bytes().to_vec(),  // withdraw_a
+        ].concat(),
+    };
+    let message = Message::new(&[update_instruction], Some(&escrow_setup.maker.pubkey()));
+    let recent_blockhash = escrow_setup.litesvm.latest_blockhash();
+    let transaction = Transaction::new(&[&escrow_setup.maker], message, recent_blockhash);
+    let tx = escrow_setup.litesvm.send_transaction(transaction).unwrap();
+    assert_eq!(get_token_balance(&escrow_setup.litesvm, &vault), 25_000_000);
+
+    // The same escrow is taken at the updated terms
+    let take_instruction = Instruction {
+        program_id: escrow_setup.program_id,
+        accounts: vec![
+            AccountMeta::new(escrow_setup.taker.pubkey(), true),
+            AccountMeta::new(escrow_setup.maker.pubkey(), false),
+            AccountMeta::new(escrow_setup.mint_a, false),
+            AccountMeta::new(escrow_setup.mint_b, false),
+            AccountMeta::new(escrow_setup.taker_ata_a, false),
+            AccountMeta::new(escrow_setup.taker_ata_b, false),
+            AccountMeta::new(vault, false),
+            AccountMeta::new(escrow_setup.maker_ata_b, false),
+            AccountMeta::new(escrow_pda, false),
+            AccountMeta::new(SYSTEM_PROGRAM_ID, false),
+            AccountMeta::new(TOKEN_PROGRAM_ID, false),
+            AccountMeta::new(TOKEN_PROGRAM_ID, false),
+            AccountMeta::new(escrow_setup.config, false),
+            AccountMeta::new(escrow_setup.treasury_ata_b, false),
+            AccountMeta::new_readonly(escrow_setup.event_authority, false),
+            AccountMeta::new_readonly(escrow_setup.program_id, false),
+        ],
+        data: [vec![1u8], 25_000_000u64.to_le_bytes().to_vec()].concat(),
+    };
+    let message = Message::new(&[take_instruction], Some(&escrow_setup.taker.pubkey()));
+    let recent_blockhash = escrow_setup.litesvm.latest_blockhash();
+    let transaction = Transaction::new(&[&escrow_setup.taker], message, recent_blockhash);
+    escrow_setup.litesvm.send_transaction(transaction).unwrap();
+    assert_eq!(get_token_balance(&escrow_setup.litesvm, &escrow_setup.maker_ata_b), 50_000_000);
+    assert_eq!(get_token_balance(&escrow_setup.litesvm, &escrow_setup.taker_ata_a), 25_000_000);
+
+    // Log transaction details
+    msg!("\n\nUpdate escrow transaction sucessfull");
+    msg!("CUs Consumed: {}", tx.compute_units_consumed);
+
+}
+
+#[test]
+fn test_take_slippage_guard() {
+    let mut escrow_setup = setup_escrow_test();
+
+    let seed: u8 = 14;
+    let amount_a: u64 = 30_000_000;
+    let amount_b: u64 = 70_000_000;
+
+    // Derive the escrow PDA
+    let maker_pubkey = escrow_setup.maker.pubkey();
+    let escrow_seeds: &[&[u8]] = &[b"escrow", maker_pubkey.as_ref(), &[seed]];
+    let (escrow_pda, escrow_bump) = Pubkey::find_program_address(escrow_seeds, &escrow_setup.program_id);
+
+    // Derive the vault PDA
+    let vault = get_associated_token_address(
+        &escrow_pda,
+        &escrow_setup.mint_a,
+    );
+
+    // Create the make instruction
+    let make_data = [
+        vec![0u8],  // discriminator
+        amount_a.to_le_bytes().to_vec(),
+        amount_b.to_le_bytes().to_vec(),
+        vec![seed],
+        vec![escrow_bump],
+        0i64.to_le_bytes().to_vec(),  // expires_at (no expiry)
+    ].concat();
+    let make_accounts = vec![
+        AccountMeta::new(escrow_setup.maker.pubkey(), true),
+        AccountMeta::new(escrow_setup.mint_a, false),
+        AccountMeta::new(escrow_setup.mint_b, false),
+        AccountMeta::new(escrow_setup.maker_ata_a, false),
+        AccountMeta::new(vault, false),
+        AccountMeta::new(escrow_pda, false),
+        AccountMeta::new(SYSTEM_PROGRAM_ID, false),
+        AccountMeta::new(TOKEN_PROGRAM_ID, false),
+        AccountMeta::new(ASSOCIATED_TOKEN_PROGRAM_ID, false),
+        AccountMeta::new(escrow_setup.config, false),
+        AccountMeta::new_readonly(escrow_setup.event_authority, false),
+        AccountMeta::new_readonly(escrow_setup.program_id, false),
+    ];
+    let make_instruction = Instruction {
+        program_id: escrow_setup.program_id,
+        accounts: make_accounts,
+        data: make_data,
+    };
+    let message = Message::new(&[make_instruction], Some(&escrow_setup.maker.pubkey()));
+    let recent_blockhash = escrow_setup.litesvm.latest_blockhash();
+    let transaction = Transaction::new(&[&escrow_setup.maker], message, recent_blockhash);
+    escrow_setup.litesvm.send_transaction(transaction).unwrap();
+
+    // The maker reprices the escrow before the take lands
+    let update_instruction = Instruction {
+        program_id: escrow_setup.program_id,
+        accounts: vec![
+            AccountMeta::new(escrow_setup.maker.pubkey(), true),
+            AccountMeta::new(escrow_setup.mint_a, false),
+            AccountMeta::new(escrow_setup.maker_ata_a, false),
+            AccountMeta::new(vault, false),
+            AccountMeta::new(escrow_pda, false),
+            AccountMeta::new(SYSTEM_PROGRAM_ID, false),
+            AccountMeta::new(TOKEN_PROGRAM_ID, false),
+        ],
+        data: [
+            vec![6u8],  // discriminator
+            90_000_000u64.to_le_bytes().to_vec(),  // amount_b
+            0u64.to_le_bytes().to_vec(),  // deposit_a
+            0u64.to_le_bytes().to_vec(),  // withdraw_a
+        ].concat(),
+    };
+    let message = Message::new(&[update_instruction], Some(&escrow_setup.maker.pubkey()));
+    let recent_blockhash = escrow_setup.litesvm.latest_blockhash();
+    let transaction = Transaction::new(&[&escrow_setup.maker], message, recent_blockhash);
+    escrow_setup.litesvm.send_transaction(transaction).unwrap();
+
+    let take_accounts = vec![
+        AccountMeta::new(escrow_setup.taker.pubkey(), true),
+        AccountMeta::new(escrow_setup.maker.pubkey(), false),
+        AccountMeta::new(escrow_setup.mint_a, false),
+        AccountMeta::new(escrow_setup.mint_b, false),
+        AccountMeta::new(escrow_setup.taker_ata_a, false),
+        AccountMeta::new(escrow_setup.taker_ata_b, false),
+        AccountMeta::new(vault, false),
+        AccountMeta::new(escrow_setup.maker_ata_b, false),
+        AccountMeta::new(escrow_pda, false),
+        AccountMeta::new(SYSTEM_PROGRAM_ID, false),
+        AccountMeta::new(TOKEN_PROGRAM_ID, false),
+        AccountMeta::new(TOKEN_PROGRAM_ID, false),
+        AccountMeta::new(escrow_setup.config, false),
+        AccountMeta::new(escrow_setup.treasury_ata_b, false),
+        AccountMeta::new_readonly(escrow_setup.event_authority, false),
+        AccountMeta::new_readonly(escrow_setup.program_id, false),
+    ];
+
+    // A take signed against the original terms fails and moves nothing
+    let take_instruction = Instruction {
+        program_id: escrow_setup.program_id,
+        accounts: take_accounts.clone(),
+        data: [vec![1u8], amount_a.to_le_bytes().to_vec(), amount_b.to_le_bytes().to_vec()].concat(),
+    };
+    let message = Message::new(&[take_instruction], Some(&escrow_setup.taker.pubkey()));
+    let recent_blockhash = escrow_setup.litesvm.latest_blockhash();
+    let transaction = Transaction::new(&[&escrow_setup.taker], message, recent_blockhash);
+    assert_escrow_error(escrow_setup.litesvm.send_transaction(transaction), EscrowError::SlippageExceeded);
+    assert_eq!(get_token_balance(&escrow_setup.litesvm, &escrow_setup.taker_ata_b), 100_000_000);
+    assert_eq!(get_token_balance(&escrow_setup.litesvm, &vault), amount_a);
+
+    // A take accepting the new price goes through
+    let take_instruction = Instruction {
+        program_id: escrow_setup.program_id,
+        accounts: take_accounts,
+        data: [vec![1u8], amount_a.to_le_bytes().to_vec(), 90_000_000u64.to_le_bytes().to_vec()].concat(),
+    };
+    let message = Message::new(&[take_instruction], Some(&escrow_setup.taker.pubkey()));
+    let recent_blockhash = escrow_setup.litesvm.latest_blockhash();
+    let transaction = Transaction::new(&[&escrow_setup.taker], message, recent_blockhash);
+    let tx = escrow_setup.litesvm.send_transaction(transaction).unwrap();
+    assert_eq!(get_token_balance(&escrow_setup.litesvm, &escrow_setup.maker_ata_b), 90_000_000);
+
+    // Log transaction details
+    msg!("\n\nTake escrow with slippage guard transaction sucessfull");
+    msg!("CUs Consumed: {}", tx.compute_units_consumed);
+
+}
+
+#[test]
+fn test_v1_make_take_refund() {
+    let mut escrow_setup = setup_escrow_test();
+
+    let amount_a: u64 = 30_000_000;
+    let amount_b: u64 = 70_000_000;
+    let maker_pubkey = escrow_setup.maker.pubkey();
+    let taker_pubkey = escrow_setup.taker.pubkey();
+
+    for seed in [41u8, 42u8] {
+        let (escrow_pda, escrow_bump) = Pubkey::find_program_address(&[b"escrow", maker_pubkey.as_ref(), &[seed]], &escrow_setup.program_id);
+        let vault = get_associated_token_address(&escrow_pda, &escrow_setup.mint_a);
+
+        // Make with the v1 accounts and the original 18-byte data, without an expiry
+        let mut make_instruction = Instruction {
+            program_id: escrow_setup.program_id,
+            accounts: vec![
+                AccountMeta::new(maker_pubkey, true),
+                AccountMeta::new(escrow_setup.mint_a, false),
+                AccountMeta::new(escrow_setup.mint_b, false),
+                AccountMeta::new(escrow_setup.maker_ata_a, false),
+                AccountMeta::new(vault, false),
+                AccountMeta::new(escrow_pda, false),
+                AccountMeta::new(SYSTEM_PROGRAM_ID, false),
+                AccountMeta::new(TOKEN_PROGRAM_ID, false),
+                AccountMeta::new(ASSOCIATED_TOKEN_PROGRAM_ID, false),
+            ],
+            data: [
+                vec![0u8],  // discriminator
+                amount_a.to_le_bytes().to_vec(),
+                amount_b.to_le_bytes().to_vec(),
+                vec![seed],
+                vec![escrow_bump],
+            ].concat(),
+        };
+
+        // The config must be passed, so no account list skips the protocol fee
+        let message = Message::new(std::slice::from_ref(&make_instruction), Some(&maker_pubkey));
+        let recent_blockhash = escrow_setup.litesvm.latest_blockhash();
+        let transaction = Transaction::new(&[&escrow_setup.maker], message, recent_blockhash);
+        let error = escrow_setup.litesvm.send_transaction(transaction).unwrap_err().err;
+        #[allow(deprecated)]
+        let not_enough_account_keys = InstructionError::NotEnoughAccountKeys;
+        assert_eq!(error, TransactionError::InstructionError(0, not_enough_account_keys));
+
+        make_instruction.accounts.push(AccountMeta::new_readonly(escrow_setup.config, false));
+        let message = Message::new(&[make_instruction], Some(&maker_pubkey));
+        escrow_setup.litesvm.expire_blockhash();
+        let recent_blockhash = escrow_setup.litesvm.latest_blockhash();
+        let transaction = Transaction::new(&[&escrow_setup.maker], message, recent_blockhash);
+        escrow_setup.litesvm.send_transaction(transaction).unwrap();
+        assert_eq!(get_token_balance(&escrow_setup.litesvm, &vault), amount_a);
+
+        if seed == 41 {
+            // Take with the v1 accounts and no data, filling the whole escrow
+            let take_instruction = Instruction {
+                program_id: escrow_setup.program_id,
+                accounts: vec![
+                    AccountMeta::new(taker_pubkey, true),
+                    AccountMeta::new(maker_pubkey, false),
+                    AccountMeta::new(escrow_setup.mint_a, false),
+                    AccountMeta::new(escrow_setup.mint_b, false),
+                    AccountMeta::new(escrow_setup.taker_ata_a, false),
+                    AccountMeta::new(escrow_setup.taker_ata_b, false),
+                    AccountMeta::new(vault, false),
+                    AccountMeta::new(escrow_setup.maker_ata_b, false),
+                    AccountMeta::new(escrow_pda, false),
+                    AccountMeta::new(SYSTEM_PROGRAM_ID, false),
+                    AccountMeta::new(TOKEN_PROGRAM_ID, false),
+                ],
+                data: vec![1u8],  // discriminator
+            };
+            let message = Message::new(&[take_instruction], Some(&taker_pubkey));
+            let recent_blockhash = escrow_setup.litesvm.latest_blockhash();
+            let transaction = Transaction::new(&[&escrow_setup.taker], message, recent_blockhash);
+            escrow_setup.litesvm.send_transaction(transaction).unwrap();
+            assert_eq!(get_token_balance(&escrow_setup.litesvm, &escrow_setup.taker_ata_a), amount_a);
+            assert_eq!(get_token_balance(&escrow_setup.litesvm, &escrow_setup.maker_ata_b), amount_b);
+        } else {
+            // Refund with the v1 accounts
+            let refund_instruction = Instruction {
+                program_id: escrow_setup.program_id,
+                accounts: vec![
+                    AccountMeta::new(maker_pubkey, true),
+                    AccountMeta::new(escrow_setup.mint_a, false),
+                    AccountMeta::new(escrow_setup.mint_b, false),
+                    AccountMeta::new(escrow_setup.maker_ata_a, false),
+                    AccountMeta::new(vault, false),
+                    AccountMeta::new(escrow_pda, false),
+                    AccountMeta::new(SYSTEM_PROGRAM_ID, false),
+                    AccountMeta::new(TOKEN_PROGRAM_ID, false),
+                ],
+                data: vec![2u8],  // discriminator
+            };
+            let message = Message::new(&[refund_instruction], Some(&maker_pubkey));
+            let recent_blockhash = escrow_setup.litesvm.latest_blockhash();
+            let transaction = Transaction::new(&[&escrow_setup.maker], message, recent_blockhash);
+            escrow_setup.litesvm.send_transaction(transaction).unwrap();
+            assert_eq!(get_token_balance(&escrow_setup.litesvm, &escrow_setup.maker_ata_a), 100_000_000 - amount_a);
+        }
+
+        // Both paths close the escrow
+        assert!(escrow_setup.litesvm.get_account(&escrow_pda).is_none_or(|account| account.lamports == 0));
+    }
+
+    msg!("\n\nMake, take and refund with the v1 accounts sucessfull");
+}
@@ -0,0 +1,104 @@
+use solana_message::Message;
+use solana_signer::Signer;
+use solana_transaction::Transaction;
+use solana_pubkey::Pubkey;
+use solana_instruction::{AccountMeta, Instruction};
+use solana_program::msg;
+
+use crate::errors::EscrowError;
+use crate::client::{MakeParams, TakeParams, TOKEN_PROGRAM_ID, find_escrow_address, make_instruction, take_instruction};
+use crate::tests::test_helpers::{assert_escrow_error, setup_escrow_test};
+
+const EVENT_IX_TAG: [u8; 8] = [0xe4, 0x45, 0xa5, 0x2e, 0x51, 0xcb, 0x9a, 0x1d];
+const ESCROW_CREATED: [u8; 8] = [0x46, 0x7f, 0x69, 0x66, 0x5c, 0x61, 0x07, 0xad];
+const ESCROW_TAKEN: [u8; 8] = [0xa4, 0x4f, 0x32, 0x1a, 0xae, 0x95, 0x5c, 0x9e];
+
+/// Returns the event data (after the tag and discriminator) of the first self-CPI event with the given discriminator
+fn find_event(inner_instructions: &[Vec<solana_message::inner_instruction::InnerInstruction>], discriminator: [u8; 8]) -> Option<Vec<u8>> {
+    inner_instructions.iter().flatten().find_map(|inner| {
+        let data = &inner.instruction.data;
+        (data.len() >= 16 && data[0..8] == EVENT_IX_TAG && data[8..16] == discriminator).then(|| data[16..].to_vec())
+    })
+}
+
+#[test]
+fn test_events() {
+    let mut escrow_setup = setup_escrow_test();
+
+    let seed: u8 = 1;
+    let amount_a: u64 = 30_000_000;
+    let amount_b: u64 = 70_000_000;
+    let maker_pubkey = escrow_setup.maker.pubkey();
+    let taker_pubkey = escrow_setup.taker.pubkey();
+    let (escrow_pda, _) = find_escrow_address(&maker_pubkey, seed);
+
+    // Make emits EscrowCreated
+    let make_instruction = make_instruction(&MakeParams {
+        maker: maker_pubkey,
+        mint_a: escrow_setup.mint_a,
+        mint_b: escrow_setup.mint_b,
+        token_program_a: TOKEN_PROGRAM_ID,
+        amount_a,
+        amount_b,
+        seed: seed.into(),
+        expires_at: 0,
+        taker: None,
+        alternatives: vec![],
+    });
+    let message = Message::new(&[make_instruction], Some(&maker_pubkey));
+    let recent_blockhash = escrow_setup.litesvm.latest_blockhash();
+    let transaction = Transaction::new(&[&escrow_setup.maker], message, recent_blockhash);
+    let tx = escrow_setup.litesvm.send_transaction(transaction).unwrap();
+
+    let event = find_event(&tx.inner_instructions, ESCROW_CREATED).expect("EscrowCreated not emitted");
+    assert_eq!(event.len(), 5 * 32 + 4 * 8);
+    assert_eq!(&event[0..32], escrow_pda.as_ref());
+    assert_eq!(&event[32..64], maker_pubkey.as_ref());
+    assert_eq!(&event[64..96], escrow_setup.mint_a.as_ref());
+    assert_eq!(&event[96..128], escrow_setup.mint_b.as_ref());
+    assert_eq!(&event[128..160], Pubkey::default().as_ref());
+    assert_eq!(u64::from_le_bytes(event[160..168].try_into().unwrap()), amount_a);
+    assert_eq!(u64::from_le_bytes(event[168..176].try_into().unwrap()), amount_b);
+    assert_eq!(u64::from_le_bytes(event[184..192].try_into().unwrap()), seed as u64);
+
+    // Take emits EscrowTaken
+    let take_instruction = take_instruction(&TakeParams {
+        taker: taker_pubkey,
+        maker: maker_pubkey,
+        mint_a: escrow_setup.mint_a,
+        mint_b: escrow_setup.mint_b,
+        token_program_a: TOKEN_PROGRAM_ID,
+        token_program_b: TOKEN_PROGRAM_ID,
+        treasury: escrow_setup.treasury,
+        seed: seed.into(),
+        amount_a: amount_a / 3,
+        max_amount_b: None,
+    });
+    let message = Message::new(&[take_instruction], Some(&taker_pubkey));
+    let recent_blockhash = escrow_setup.litesvm.latest_blockhash();
+    let transaction = Transaction::new(&[&escrow_setup.taker], message, recent_blockhash);
+    let tx = escrow_setup.litesvm.send_transaction(transaction).unwrap();
+
+    let event = find_event(&tx.inner_instructions, ESCROW_TAKEN).expect("EscrowTaken not emitted");
+    assert_eq!(event.len(), 5 * 32 + 5 * 8);
+    assert_eq!(&event[32..64], maker_pubkey.as_ref());
+    assert_eq!(&event[64..96], taker_pubkey.as_ref());
+    assert_eq!(u64::from_le_bytes(event[160..168].try_into().unwrap()), amount_a / 3);
+    assert_eq!(u64::from_le_bytes(event[168..176].try_into().unwrap()), amount_b.div_ceil(3));
+    assert_eq!(u64::from_le_bytes(event[176..184].try_into().unwrap()), 0);
+    assert_eq!(u64::from_le_bytes(event[184..192].try_into().unwrap()), amount_a - amount_a / 3);
+
+    msg!("\n\nTake escrow with events transaction sucessfull");
+    msg!("CUs Consumed: {}", tx.compute_units_consumed);
+
+    // Nobody else can sign the event instruction
+    let forged_event_instruction = Instruction {
+        program_id: escrow_setup.program_id,
+        accounts: vec![AccountMeta::new_readonly(taker_pubkey, true)],
+        data: [EVENT_IX_TAG.to_vec(), ESCROW_TAKEN.to_vec()].concat(),
+    };
+    let message = Message::new(&[forged_event_instruction], Some(&taker_pubkey));
+    let recent_blockhash = escrow_setup.litesvm.latest_blockhash();
+    let transaction = Transaction::new(&[&escrow_setup.taker], message, recent_blockhash);
+    assert_escrow_error(escrow_setup.litesvm.send_transaction(transaction), EscrowError::InvalidEventAuthority);
+}
@@ -0,0 +1,120 @@
+use solana_message::Message;
+use solana_signer::Signer;
+use solana_transaction::Transaction;
+use solana_program::{clock::Clock, msg};
+
+use crate::client::{Escrow, EscrowSeed, MakeParams, RefundParams, TakeParams, TOKEN_PROGRAM_ID, find_escrow_address, hashlock_of, make_hashlock_instruction, refund_instruction, take_hashlock_instruction};
+use crate::errors::EscrowError;
+use crate::tests::test_helpers::{EscrowTestSetup, assert_escrow_error, get_token_balance, setup_escrow_test};
+
+/// Makes a hashlocked escrow of 30 mint_a for 70 mint_b with the taker as counterparty, timing out at `timeout`
+fn make_hashlocked(escrow_setup: &mut EscrowTestSetup, seed: u64, hashlock: &[u8; 32], timeout: i64) {
+    let make_params = MakeParams {
+        maker: escrow_setup.maker.pubkey(),
+        mint_a: escrow_setup.mint_a,
+        mint_b: escrow_setup.mint_b,
+        token_program_a: TOKEN_PROGRAM_ID,
+        amount_a: 30_000_000,
+        amount_b: 70_000_000,
+        seed: EscrowSeed::Wide(seed),
+        expires_at: timeout,
+        taker: Some(escrow_setup.taker.pubkey()),
+        alternatives: vec![],
+    };
+    let message = Message::new(&[make_hashlock_instruction(&make_params, hashlock)], Some(&escrow_setup.maker.pubkey()));
+    let recent_blockhash = escrow_setup.litesvm.latest_blockhash();
+    let transaction = Transaction::new(&[&escrow_setup.maker], message, recent_blockhash);
+    escrow_setup.litesvm.send_transaction(transaction).unwrap();
+}
+
+/// Builds a take of `amount_a` revealing `preimage` on a fresh blockhash
+fn take_transaction(escrow_setup: &mut EscrowTestSetup, seed: u64, preimage: &[u8; 32], amount_a: u64) -> Transaction {
+    let take_params = TakeParams {
+        taker: escrow_setup.taker.pubkey(),
+        maker: escrow_setup.maker.pubkey(),
+        mint_a: escrow_setup.mint_a,
+        mint_b: escrow_setup.mint_b,
+        token_program_a: TOKEN_PROGRAM_ID,
+        token_program_b: TOKEN_PROGRAM_ID,
+        treasury: escrow_setup.treasury,
+        seed: EscrowSeed::Wide(seed),
+        amount_a,
+        max_amount_b: None,
+    };
+    let message = Message::new(&[take_hashlock_instruction(&take_params, preimage)], Some(&escrow_setup.taker.pubkey()));
+    escrow_setup.litesvm.expire_blockhash();
+    let recent_blockhash = escrow_setup.litesvm.latest_blockhash();
+    Transaction::new(&[&escrow_setup.taker], message, recent_blockhash)
+}
+
+/// Builds the maker's refund on a fresh blockhash
+fn refund_transaction(escrow_setup: &mut EscrowTestSetup, seed: u64) -> Transaction {
+    let refund_params = RefundParams {
+        maker: escrow_setup.maker.pubkey(),
+        mint_a: escrow_setup.mint_a,
+        mint_b: escrow_setup.mint_b,
+        token_program_a: TOKEN_PROGRAM_ID,
+        seed: EscrowSeed::Wide(seed),
+    };
+    let message = Message::new(&[refund_instruction(&refund_params)], Some(&escrow_setup.maker.pubkey()));
+    escrow_setup.litesvm.expire_blockhash();
+    let recent_blockhash = escrow_setup.litesvm.latest_blockhash();
+    Transaction::new(&[&escrow_setup.maker], message, recent_blockhash)
+}
+
+#[test]
+fn test_hashlock_take() {
+    let mut escrow_setup = setup_escrow_test();
+
+    let now = escrow_setup.litesvm.get_sysvar::<Clock>().unix_timestamp;
+    let preimage = [7u8; 32];
+    let hashlock = hashlock_of(&preimage);
+    make_hashlocked(&mut escrow_setup, 1, &hashlock, now + 100);
+
+    let (escrow_pda, _) = find_escrow_address(&escrow_setup.maker.pubkey(), EscrowSeed::Wide(1));
+    let escrow_account = escrow_setup.litesvm.get_account(&escrow_pda).unwrap();
+    assert_eq!(Escrow::from_bytes(&escrow_account.data).unwrap().hashlock, Some(hashlock));
+
+    // The maker can't pull the deposit back before the timeout
+    let transaction = refund_transaction(&mut escrow_setup, 1);
+    assert_escrow_error(escrow_setup.litesvm.send_transaction(transaction), EscrowError::HashlockedEscrow);
+
+    // The escrow is taken whole, the preimage unlocking the whole counterparty leg
+    let transaction = take_transaction(&mut escrow_setup, 1, &preimage, 10_000_000);
+    assert_escrow_error(escrow_setup.litesvm.send_transaction(transaction), EscrowError::PartialHashlockFill);
+
+    // Only the preimage unlocks the take
+    let transaction = take_transaction(&mut escrow_setup, 1, &[8u8; 32], 30_000_000);
+    assert_escrow_error(escrow_setup.litesvm.send_transaction(transaction), EscrowError::InvalidPreimage);
+    let transaction = take_transaction(&mut escrow_setup, 1, &preimage, 30_000_000);
+    let tx = escrow_setup.litesvm.send_transaction(transaction).unwrap();
+    assert_eq!(get_token_balance(&escrow_setup.litesvm, &escrow_setup.taker_ata_a), 30_000_000);
+    assert!(escrow_setup.litesvm.get_account(&escrow_pda).is_none_or(|account| account.lamports == 0));
+
+    msg!("\n\nTake Hashlock transaction sucessfull");
+    msg!("CUs Consumed: {}", tx.compute_units_consumed);
+
+    // The preimage is revealed in the inner event instruction, for the counterparty chain
+    let revealed = tx.inner_instructions.iter().flatten().any(|inner| inner.instruction.data.windows(32).any(|window| window == preimage));
+    assert!(revealed);
+}
+
+#[test]
+fn test_hashlock_refund_after_timeout() {
+    let mut escrow_setup = setup_escrow_test();
+
+    let mut clock: Clock = escrow_setup.litesvm.get_sysvar();
+    let now = clock.unix_timestamp;
+    let preimage = [7u8; 32];
+    make_hashlocked(&mut escrow_setup, 1, &hashlock_of(&preimage), now + 100);
+    let maker_a_before = get_token_balance(&escrow_setup.litesvm, &escrow_setup.maker_ata_a);
+
+    // Past the timeout the preimage no longer takes the escrow, and the maker gets the deposit back
+    clock.unix_timestamp = now + 100;
+    escrow_setup.litesvm.set_sysvar(&clock);
+    let transaction = take_transaction(&mut escrow_setup, 1, &preimage, 30_000_000);
+    assert_escrow_error(escrow_setup.litesvm.send_transaction(transaction), EscrowError::EscrowExpired);
+    let transaction = refund_transaction(&mut escrow_setup, 1);
+    escrow_setup.litesvm.send_transaction(transaction).unwrap();
+    assert_eq!(get_token_balance(&escrow_setup.litesvm, &escrow_setup.maker_ata_a), maker_a_before + 30_000_000);
+}
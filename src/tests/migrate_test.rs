@@ -0,0 +1,163 @@
+use litesvm_token::MintTo;
+use solana_instruction::AccountMeta;
+use solana_message::Message;
+use solana_signer::Signer;
+use solana_transaction::Transaction;
+use solana_program::msg;
+
+use crate::client::{Escrow, EscrowSeed, MakeParams, TakeParams, TOKEN_PROGRAM_ID, find_escrow_address, make_instruction, migrate_instruction, take_instruction};
+use crate::errors::EscrowError;
+use crate::tests::test_helpers::{EscrowTestSetup, assert_escrow_error, get_token_balance, setup_escrow_test};
+
+/// Makes an escrow with the current layout and rewrites its data with the older layout of the given size
+fn make_legacy_escrow(escrow_setup: &mut EscrowTestSetup, seed: u8, amount_a: u64, amount_b: u64, legacy_len: usize) {
+    let maker_pubkey = escrow_setup.maker.pubkey();
+
+    // Fund the maker deposit and the take of every legacy escrow
+    MintTo::new(&mut escrow_setup.litesvm, &escrow_setup._mint_authority, &escrow_setup.mint_a, &escrow_setup.maker_ata_a, amount_a)
+        .send()
+        .unwrap();
+    MintTo::new(&mut escrow_setup.litesvm, &escrow_setup._mint_authority, &escrow_setup.mint_b, &escrow_setup.taker_ata_b, amount_b)
+        .send()
+        .unwrap();
+    let make_instruction = make_instruction(&MakeParams {
+        maker: maker_pubkey,
+        mint_a: escrow_setup.mint_a,
+        mint_b: escrow_setup.mint_b,
+        token_program_a: TOKEN_PROGRAM_ID,
+        amount_a,
+        amount_b,
+        seed: seed.into(),
+        expires_at: 0,
+        taker: None,
+        alternatives: vec![],
+    });
+    let message = Message::new(&[make_instruction], Some(&maker_pubkey));
+    let recent_blockhash = escrow_setup.litesvm.latest_blockhash();
+    let transaction = Transaction::new(&[&escrow_setup.maker], message, recent_blockhash);
+    escrow_setup.litesvm.send_transaction(transaction).unwrap();
+
+    let (escrow_pda, _) = find_escrow_address(&maker_pubkey, seed);
+    let mut escrow_account = escrow_setup.litesvm.get_account(&escrow_pda).unwrap();
+    let data = &escrow_account.data;
+    // The fields after mint_a with a single seed byte
+    let unversioned_101 = [&data[66..165], &data[173..175]].concat();
+    escrow_account.data = match legacy_len {
+        // mint_b, amount_b, seed, bump
+        42 => [&data[66..106], &data[164..165], &data[173..174]].concat(),
+        101 => unversioned_101,
+        // Behind the version 1 header
+        103 => [&[1u8, 1u8], unversioned_101.as_slice()].concat(),
+        // Behind the version 2 header, maker and mint_a
+        167 => [&[1u8, 2u8], &data[2..66], unversioned_101.as_slice()].concat(),
+        // The version 3 fields, without the payment alternatives
+        175 => [&[1u8, 3u8], &data[2..175]].concat(),
+        // The version 4 fields, without the oracle terms
+        296 => [&[1u8, 4u8], &data[2..296]].concat(),
+        // The version 5 fields, without the auction terms
+        336 => [&[1u8, 5u8], &data[2..336]].concat(),
+        // The version 6 fields, without the English auction terms
+        360 => [&[1u8, 6u8], &data[2..360]].concat(),
+        // The version 7 fields, without the vesting schedule
+        416 => [&[1u8, 7u8], &data[2..416]].concat(),
+        // The version 8 fields, without the arbiter
+        432 => [&[1u8, 8u8], &data[2..432]].concat(),
+        // The version 9 fields, without the approvers
+        464 => [&[1u8, 9u8], &data[2..464]].concat(),
+        // The version 10 fields, without the milestones
+        627 => [&[1u8, 10u8], &data[2..627]].concat(),
+        // The version 11 fields, without the hashlock
+        693 => [&[1u8, 11u8], &data[2..693]].concat(),
+        // The version 12 fields, without the bid vault payer
+        _ => [&[1u8, 12u8], &data[2..725]].concat(),
+    };
+    escrow_setup.litesvm.set_account(escrow_pda, escrow_account).unwrap();
+}
+
+#[test]
+fn test_migrate() {
+    let mut escrow_setup = setup_escrow_test();
+
+    let amount_a: u64 = 30_000_000;
+    let amount_b: u64 = 70_000_000;
+    let maker_pubkey = escrow_setup.maker.pubkey();
+    let taker_pubkey = escrow_setup.taker.pubkey();
+
+    for (seed, legacy_len) in [(1u8, 42usize), (2u8, 101usize), (3u8, 103usize), (4u8, 167usize), (5u8, 175usize), (6u8, 296usize), (7u8, 336usize), (8u8, 360usize), (9u8, 416usize), (10u8, 432usize), (11u8, 464usize), (12u8, 627usize), (13u8, 693usize), (14u8, 725usize)] {
+        make_legacy_escrow(&mut escrow_setup, seed, amount_a, amount_b, legacy_len);
+        let (escrow_pda, _) = find_escrow_address(&maker_pubkey, seed);
+        let escrow_data = escrow_setup.litesvm.get_account(&escrow_pda).unwrap().data;
+        assert_eq!(Escrow::from_bytes(&escrow_data), Err(EscrowError::OutdatedEscrowVersion));
+
+        // The legacy escrow can't be taken before it is migrated
+        let take_params = TakeParams {
+            taker: taker_pubkey,
+            maker: maker_pubkey,
+            mint_a: escrow_setup.mint_a,
+            mint_b: escrow_setup.mint_b,
+            token_program_a: TOKEN_PROGRAM_ID,
+            token_program_b: TOKEN_PROGRAM_ID,
+            treasury: escrow_setup.treasury,
+            seed: seed.into(),
+            amount_a,
+            max_amount_b: None,
+        };
+        let message = Message::new(&[take_instruction(&take_params)], Some(&taker_pubkey));
+        let recent_blockhash = escrow_setup.litesvm.latest_blockhash();
+        let transaction = Transaction::new(&[&escrow_setup.taker], message, recent_blockhash);
+        assert_escrow_error(escrow_setup.litesvm.send_transaction(transaction), EscrowError::OutdatedEscrowVersion);
+
+        // Only the maker can migrate a layout that predates the stored maker, anyone can zero-extend the later ones
+        let mut forged_migrate = migrate_instruction(&maker_pubkey, &escrow_setup.mint_a, &TOKEN_PROGRAM_ID, seed);
+        forged_migrate.accounts[0] = AccountMeta::new(taker_pubkey, true);
+        let message = Message::new(&[forged_migrate], Some(&taker_pubkey));
+        let recent_blockhash = escrow_setup.litesvm.latest_blockhash();
+        let transaction = Transaction::new(&[&escrow_setup.taker], message, recent_blockhash);
+        if legacy_len < 175 {
+            assert_escrow_error(escrow_setup.litesvm.send_transaction(transaction), EscrowError::InvalidEscrowAddress);
+        } else {
+            escrow_setup.litesvm.send_transaction(transaction).unwrap();
+        }
+
+        // Migrate, twice to check it is idempotent
+        for _ in 0..2 {
+            let migrate = migrate_instruction(&maker_pubkey, &escrow_setup.mint_a, &TOKEN_PROGRAM_ID, seed);
+            let message = Message::new(&[migrate], Some(&maker_pubkey));
+            let recent_blockhash = escrow_setup.litesvm.latest_blockhash();
+            let transaction = Transaction::new(&[&escrow_setup.maker], message, recent_blockhash);
+            escrow_setup.litesvm.send_transaction(transaction).unwrap();
+            escrow_setup.litesvm.expire_blockhash();
+        }
+
+        let escrow_data = escrow_setup.litesvm.get_account(&escrow_pda).unwrap().data;
+        let escrow = Escrow::from_bytes(&escrow_data).unwrap();
+        assert_eq!(escrow.maker, maker_pubkey);
+        assert_eq!(escrow.mint_a, escrow_setup.mint_a);
+        assert_eq!(escrow.mint_b, escrow_setup.mint_b);
+        assert_eq!(escrow.amount_a, amount_a);
+        assert_eq!(escrow.amount_b, amount_b);
+        assert_eq!(escrow.remaining_a, amount_a);
+        assert_eq!(escrow.seed, EscrowSeed::Byte(seed));
+        assert!(escrow.alternatives.is_empty());
+        assert_eq!(escrow.oracle, None);
+        assert_eq!(escrow.dutch_auction, None);
+        assert_eq!(escrow.english_auction, None);
+        assert_eq!(escrow.vesting, None);
+        assert_eq!(escrow.arbiter, None);
+        assert_eq!(escrow.approval, None);
+        assert!(escrow.milestones.is_empty());
+        assert_eq!(escrow.hashlock, None);
+        assert_eq!(escrow.bid_vault_payer, None);
+
+        // The migrated escrow can be taken
+        let taker_a_before = get_token_balance(&escrow_setup.litesvm, &escrow_setup.taker_ata_a);
+        let message = Message::new(&[take_instruction(&take_params)], Some(&taker_pubkey));
+        let recent_blockhash = escrow_setup.litesvm.latest_blockhash();
+        let transaction = Transaction::new(&[&escrow_setup.taker], message, recent_blockhash);
+        let tx = escrow_setup.litesvm.send_transaction(transaction).unwrap();
+        assert_eq!(get_token_balance(&escrow_setup.litesvm, &escrow_setup.taker_ata_a), taker_a_before + amount_a);
+
+        msg!("\n\nTake migrated {}-byte escrow transaction sucessfull", legacy_len);
+        msg!("CUs Consumed: {}", tx.compute_units_consumed);
+    }
+}
@@ -0,0 +1,196 @@
+use litesvm_token::MintTo;
+use solana_keypair::Keypair;
+use solana_message::Message;
+use solana_native_token::LAMPORTS_PER_SOL;
+use solana_signer::Signer;
+use solana_transaction::Transaction;
+use solana_program::msg;
+use spl_associated_token_account_interface::address::get_associated_token_address;
+
+use crate::client::{CancelParams, Escrow, EscrowSeed, MakeParams, MilestoneTerms, RefundParams, ReleaseMilestoneParams, TOKEN_PROGRAM_ID, cancel_instruction, find_escrow_address, make_milestone_instruction, refund_instruction, release_milestone_instruction};
+use crate::errors::EscrowError;
+use crate::tests::test_helpers::{EscrowTestSetup, assert_escrow_error, get_token_balance, setup_escrow_test};
+
+/// Builds the making of a milestone escrow of 30 mint_a with the taker as payee
+fn make_transaction(escrow_setup: &mut EscrowTestSetup, seed: u64, milestones: &MilestoneTerms) -> Transaction {
+    let make_params = MakeParams {
+        maker: escrow_setup.maker.pubkey(),
+        mint_a: escrow_setup.mint_a,
+        mint_b: escrow_setup.mint_b,
+        token_program_a: TOKEN_PROGRAM_ID,
+        amount_a: 30_000_000,
+        amount_b: 1,
+        seed: EscrowSeed::Wide(seed),
+        expires_at: 0,
+        taker: Some(escrow_setup.taker.pubkey()),
+        alternatives: vec![],
+    };
+    let message = Message::new(&[make_milestone_instruction(&make_params, milestones)], Some(&escrow_setup.maker.pubkey()));
+    escrow_setup.litesvm.expire_blockhash();
+    let recent_blockhash = escrow_setup.litesvm.latest_blockhash();
+    Transaction::new(&[&escrow_setup.maker], message, recent_blockhash)
+}
+
+/// Builds the release of `milestone` signed by `authority` on a fresh blockhash
+fn release_transaction(escrow_setup: &mut EscrowTestSetup, seed: u64, authority: &Keypair, milestone: u8) -> Transaction {
+    let release_params = ReleaseMilestoneParams {
+        authority: authority.pubkey(),
+        maker: escrow_setup.maker.pubkey(),
+        payee: escrow_setup.taker.pubkey(),
+        mint_a: escrow_setup.mint_a,
+        token_program_a: TOKEN_PROGRAM_ID,
+        seed: EscrowSeed::Wide(seed),
+    };
+    let message = Message::new(&[release_milestone_instruction(&release_params, milestone)], Some(&authority.pubkey()));
+    escrow_setup.litesvm.expire_blockhash();
+    let recent_blockhash = escrow_setup.litesvm.latest_blockhash();
+    Transaction::new(&[authority], message, recent_blockhash)
+}
+
+/// Builds the cancellation of the milestones left signed by `authority`, and the payee when consenting, on a fresh blockhash
+fn cancel_transaction(escrow_setup: &mut EscrowTestSetup, seed: u64, authority: &Keypair, payee: Option<&Keypair>) -> Transaction {
+    let cancel_params = CancelParams {
+        authority: authority.pubkey(),
+        maker: escrow_setup.maker.pubkey(),
+        mint_a: escrow_setup.mint_a,
+        token_program_a: TOKEN_PROGRAM_ID,
+        seed: EscrowSeed::Wide(seed),
+        payee: payee.map(|payee| payee.pubkey()),
+    };
+    let message = Message::new(&[cancel_instruction(&cancel_params)], Some(&authority.pubkey()));
+    escrow_setup.litesvm.expire_blockhash();
+    let recent_blockhash = escrow_setup.litesvm.latest_blockhash();
+    let signers: Vec<&Keypair> = [Some(authority), payee].into_iter().flatten().collect();
+    Transaction::new(&signers, message, recent_blockhash)
+}
+
+/// The decoded escrow of `seed`
+fn escrow_state(escrow_setup: &EscrowTestSetup, seed: u64) -> Escrow {
+    let (escrow_pda, _) = find_escrow_address(&escrow_setup.maker.pubkey(), EscrowSeed::Wide(seed));
+    Escrow::from_bytes(&escrow_setup.litesvm.get_account(&escrow_pda).unwrap().data).unwrap()
+}
+
+#[test]
+fn test_milestone_release() {
+    let mut escrow_setup = setup_escrow_test();
+
+    let maker = escrow_setup.maker.insecure_clone();
+    let payee = escrow_setup.taker.insecure_clone();
+    let arbiter = Keypair::new();
+    escrow_setup.litesvm.airdrop(&arbiter.pubkey(), LAMPORTS_PER_SOL).unwrap();
+
+    // The milestones must add up to the deposit
+    let short = MilestoneTerms { arbiter: Some(arbiter.pubkey()), amounts: vec![10_000_000, 10_000_000] };
+    let transaction = make_transaction(&mut escrow_setup, 1, &short);
+    assert_escrow_error(escrow_setup.litesvm.send_transaction(transaction), EscrowError::InvalidMilestones);
+
+    let milestones = MilestoneTerms { arbiter: Some(arbiter.pubkey()), amounts: vec![5_000_000, 10_000_000, 15_000_000] };
+    let transaction = make_transaction(&mut escrow_setup, 1, &milestones);
+    escrow_setup.litesvm.send_transaction(transaction).unwrap();
+    let escrow = escrow_state(&escrow_setup, 1);
+    assert_eq!(escrow.milestones, milestones.amounts);
+    assert_eq!(escrow.arbiter, Some(arbiter.pubkey()));
+
+    // The maker can't refund it, nor the payee release the milestones to themselves
+    let refund_params = RefundParams {
+        maker: maker.pubkey(),
+        mint_a: escrow_setup.mint_a,
+        mint_b: escrow_setup.mint_b,
+        token_program_a: TOKEN_PROGRAM_ID,
+        seed: EscrowSeed::Wide(1),
+    };
+    let message = Message::new(&[refund_instruction(&refund_params)], Some(&maker.pubkey()));
+    let recent_blockhash = escrow_setup.litesvm.latest_blockhash();
+    let transaction = Transaction::new(&[&maker], message, recent_blockhash);
+    assert_escrow_error(escrow_setup.litesvm.send_transaction(transaction), EscrowError::MilestoneEscrow);
+    let transaction = release_transaction(&mut escrow_setup, 1, &payee, 0);
+    assert_escrow_error(escrow_setup.litesvm.send_transaction(transaction), EscrowError::UnauthorizedRelease);
+
+    // The milestones are released in order, by the maker or the arbiter
+    let transaction = release_transaction(&mut escrow_setup, 1, &maker, 1);
+    assert_escrow_error(escrow_setup.litesvm.send_transaction(transaction), EscrowError::MilestoneOutOfOrder);
+    let transaction = release_transaction(&mut escrow_setup, 1, &maker, 0);
+    let tx = escrow_setup.litesvm.send_transaction(transaction).unwrap();
+    assert_eq!(get_token_balance(&escrow_setup.litesvm, &escrow_setup.taker_ata_a), 5_000_000);
+    let escrow = escrow_state(&escrow_setup, 1);
+    assert_eq!((escrow.released_milestones, escrow.remaining_a), (1, 25_000_000));
+
+    msg!("\n\nRelease Milestone transaction sucessfull");
+    msg!("CUs Consumed: {}", tx.compute_units_consumed);
+
+    // Retrying a release can't pay the payee twice
+    let transaction = release_transaction(&mut escrow_setup, 1, &maker, 0);
+    assert_escrow_error(escrow_setup.litesvm.send_transaction(transaction), EscrowError::MilestoneOutOfOrder);
+    let transaction = release_transaction(&mut escrow_setup, 1, &arbiter, 1);
+    escrow_setup.litesvm.send_transaction(transaction).unwrap();
+    assert_eq!(get_token_balance(&escrow_setup.litesvm, &escrow_setup.taker_ata_a), 15_000_000);
+
+    // The last release empties the vault, tokens sent to it on top of the deposit included, and closes it with the escrow
+    let (escrow_pda, _) = find_escrow_address(&maker.pubkey(), EscrowSeed::Wide(1));
+    let vault = get_associated_token_address(&escrow_pda, &escrow_setup.mint_a);
+    MintTo::new(&mut escrow_setup.litesvm, &escrow_setup._mint_authority, &escrow_setup.mint_a, &vault, 1_000_000)
+        .send()
+        .unwrap();
+    let transaction = release_transaction(&mut escrow_setup, 1, &maker, 2);
+    escrow_setup.litesvm.send_transaction(transaction).unwrap();
+    assert_eq!(get_token_balance(&escrow_setup.litesvm, &escrow_setup.taker_ata_a), 31_000_000);
+    assert!(escrow_setup.litesvm.get_account(&escrow_pda).is_none_or(|account| account.lamports == 0));
+}
+
+#[test]
+fn test_milestone_cancel() {
+    let mut escrow_setup = setup_escrow_test();
+
+    let maker = escrow_setup.maker.insecure_clone();
+    let payee = escrow_setup.taker.insecure_clone();
+    let arbiter = Keypair::new();
+    escrow_setup.litesvm.airdrop(&arbiter.pubkey(), LAMPORTS_PER_SOL).unwrap();
+
+    // The arbiter must be a third party
+    let payee_arbiter = MilestoneTerms { arbiter: Some(payee.pubkey()), amounts: vec![10_000_000, 20_000_000] };
+    let transaction = make_transaction(&mut escrow_setup, 1, &payee_arbiter);
+    assert_escrow_error(escrow_setup.litesvm.send_transaction(transaction), EscrowError::InvalidMilestones);
+
+    let milestones = MilestoneTerms { arbiter: Some(arbiter.pubkey()), amounts: vec![10_000_000, 20_000_000] };
+    let transaction = make_transaction(&mut escrow_setup, 1, &milestones);
+    escrow_setup.litesvm.send_transaction(transaction).unwrap();
+    let maker_a_before = get_token_balance(&escrow_setup.litesvm, &escrow_setup.maker_ata_a);
+    let transaction = release_transaction(&mut escrow_setup, 1, &maker, 0);
+    escrow_setup.litesvm.send_transaction(transaction).unwrap();
+
+    // With an arbiter, the maker can't cancel the milestones left alone, nor can the payee
+    let transaction = cancel_transaction(&mut escrow_setup, 1, &payee, None);
+    assert_escrow_error(escrow_setup.litesvm.send_transaction(transaction), EscrowError::UnauthorizedRelease);
+    let transaction = cancel_transaction(&mut escrow_setup, 1, &maker, None);
+    assert_escrow_error(escrow_setup.litesvm.send_transaction(transaction), EscrowError::UnauthorizedRelease);
+
+    // The arbiter cancels them, the whole vault going back to the maker
+    let (escrow_pda, _) = find_escrow_address(&maker.pubkey(), EscrowSeed::Wide(1));
+    let vault = get_associated_token_address(&escrow_pda, &escrow_setup.mint_a);
+    MintTo::new(&mut escrow_setup.litesvm, &escrow_setup._mint_authority, &escrow_setup.mint_a, &vault, 1_000_000)
+        .send()
+        .unwrap();
+    let transaction = cancel_transaction(&mut escrow_setup, 1, &arbiter, None);
+    escrow_setup.litesvm.send_transaction(transaction).unwrap();
+    assert_eq!(get_token_balance(&escrow_setup.litesvm, &escrow_setup.maker_ata_a), maker_a_before + 21_000_000);
+    assert_eq!(get_token_balance(&escrow_setup.litesvm, &escrow_setup.taker_ata_a), 10_000_000);
+    assert!(escrow_setup.litesvm.get_account(&escrow_pda).is_none_or(|account| account.lamports == 0));
+
+    // Or the maker does with the payee's consent
+    let transaction = make_transaction(&mut escrow_setup, 2, &milestones);
+    escrow_setup.litesvm.send_transaction(transaction).unwrap();
+    let transaction = cancel_transaction(&mut escrow_setup, 2, &maker, Some(&payee));
+    escrow_setup.litesvm.send_transaction(transaction).unwrap();
+    assert_eq!(get_token_balance(&escrow_setup.litesvm, &escrow_setup.maker_ata_a), maker_a_before + 21_000_000 + 30_000_000);
+
+    // Without an arbiter, the maker cancels alone
+    let unarbitrated = MilestoneTerms { arbiter: None, ..milestones };
+    let transaction = make_transaction(&mut escrow_setup, 3, &unarbitrated);
+    escrow_setup.litesvm.send_transaction(transaction).unwrap();
+    let transaction = cancel_transaction(&mut escrow_setup, 3, &payee, None);
+    assert_escrow_error(escrow_setup.litesvm.send_transaction(transaction), EscrowError::UnauthorizedRelease);
+    let transaction = cancel_transaction(&mut escrow_setup, 3, &maker, None);
+    escrow_setup.litesvm.send_transaction(transaction).unwrap();
+    let (escrow_pda, _) = find_escrow_address(&maker.pubkey(), EscrowSeed::Wide(3));
+    assert!(escrow_setup.litesvm.get_account(&escrow_pda).is_none_or(|account| account.lamports == 0));
+}
@@ -1,3 +1,20 @@
 pub mod test_helpers;
 pub mod escrow_test;
+pub mod token_2022_test;
+pub mod native_sol_test;
+pub mod client_test;
+pub mod events_test;
 
+pub mod migrate_test;
+pub mod canonical_bump_test;
+pub mod wide_seed_test;
+pub mod basket_test;
+pub mod payment_alternatives_test;
+pub mod oracle_test;
+pub mod dutch_auction_test;
+pub mod english_auction_test;
+pub mod vesting_test;
+pub mod arbiter_test;
+pub mod approval_test;
+pub mod milestone_test;
+pub mod hashlock_test;
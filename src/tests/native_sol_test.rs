@@ -0,0 +1,227 @@
+use solana_instruction::{AccountMeta, Instruction};
+use solana_message::Message;
+use solana_native_token::LAMPORTS_PER_SOL;
+use solana_signer::Signer;
+use solana_transaction::Transaction;
+use spl_associated_token_account_interface::address::get_associated_token_address;
+use solana_pubkey::{Pubkey, pubkey};
+use solana_program::msg;
+use litesvm_token::spl_token::ID as TOKEN_PROGRAM_ID;
+use spl_associated_token_account_interface::program::ID as ASSOCIATED_TOKEN_PROGRAM_ID;
+
+use crate::tests::test_helpers::{get_token_balance, setup_escrow_test};
+
+const SYSTEM_PROGRAM_ID: Pubkey = pubkey!("11111111111111111111111111111111");
+
+/// Native SOL is passed as the system program in place of a mint
+const NATIVE_MINT: Pubkey = SYSTEM_PROGRAM_ID;
+
+#[test]
+fn test_native_sol_mint_a() {
+    let mut escrow_setup = setup_escrow_test();
+
+    let amount_a: u64 = 2 * LAMPORTS_PER_SOL;
+    let amount_b: u64 = 70_000_000;
+    let maker_pubkey = escrow_setup.maker.pubkey();
+
+    for seed in [1u8, 2u8] {
+        // Derive the escrow PDA and the program-owned native vault
+        let escrow_seeds: &[&[u8]] = &[b"escrow", maker_pubkey.as_ref(), &[seed]];
+        let (escrow_pda, escrow_bump) = Pubkey::find_program_address(escrow_seeds, &escrow_setup.program_id);
+        let (vault, _) = Pubkey::find_program_address(&[b"vault", escrow_pda.as_ref()], &escrow_setup.program_id);
+
+        // Create the make instruction, depositing lamports instead of mint_a tokens
+        let make_data = [
+            vec![0u8],  // discriminator
+            amount_a.to_le_bytes().to_vec(),
+            amount_b.to_le_bytes().to_vec(),
+            vec![seed],
+            vec![escrow_bump],
+            0i64.to_le_bytes().to_vec(),  // expires_at (no expiry)
+        ].concat();
+        let make_accounts = vec![
+            AccountMeta::new(maker_pubkey, true),
+            AccountMeta::new(NATIVE_MINT, false),
+            AccountMeta::new(escrow_setup.mint_b, false),
+            AccountMeta::new(maker_pubkey, false),
+            AccountMeta::new(vault, false),
+            AccountMeta::new(escrow_pda, false),
+            AccountMeta::new(SYSTEM_PROGRAM_ID, false),
+            AccountMeta::new(SYSTEM_PROGRAM_ID, false),
+            AccountMeta::new(ASSOCIATED_TOKEN_PROGRAM_ID, false),
+            AccountMeta::new(escrow_setup.config, false),
+            AccountMeta::new_readonly(escrow_setup.event_authority, false),
+            AccountMeta::new_readonly(escrow_setup.program_id, false),
+        ];
+        let make_instruction = Instruction {
+            program_id: escrow_setup.program_id,
+            accounts: make_accounts,
+            data: make_data,
+        };
+        let message = Message::new(&[make_instruction], Some(&maker_pubkey));
+        let recent_blockhash = escrow_setup.litesvm.latest_blockhash();
+        let transaction = Transaction::new(&[&escrow_setup.maker], message, recent_blockhash);
+        escrow_setup.litesvm.send_transaction(transaction).unwrap();
+
+        let vault_account = escrow_setup.litesvm.get_account(&vault).unwrap();
+        assert_eq!(vault_account.owner, escrow_setup.program_id);
+        assert!(vault_account.lamports >= amount_a);
+
+        if seed == 1 {
+            // The taker pays mint_b tokens and receives the lamports directly
+            let taker_lamports = escrow_setup.litesvm.get_balance(&escrow_setup.taker.pubkey()).unwrap();
+            let take_instruction = Instruction {
+                program_id: escrow_setup.program_id,
+                accounts: vec![
+                    AccountMeta::new(escrow_setup.taker.pubkey(), true),
+                    AccountMeta::new(maker_pubkey, false),
+                    AccountMeta::new(NATIVE_MINT, false),
+                    AccountMeta::new(escrow_setup.mint_b, false),
+                    AccountMeta::new(escrow_setup.taker.pubkey(), false),
+                    AccountMeta::new(escrow_setup.taker_ata_b, false),
+                    AccountMeta::new(vault, false),
+                    AccountMeta::new(escrow_setup.maker_ata_b, false),
+                    AccountMeta::new(escrow_pda, false),
+                    AccountMeta::new(SYSTEM_PROGRAM_ID, false),
+                    AccountMeta::new(SYSTEM_PROGRAM_ID, false),
+                    AccountMeta::new(TOKEN_PROGRAM_ID, false),
+                    AccountMeta::new(escrow_setup.config, false),
+                    AccountMeta::new(escrow_setup.treasury_ata_b, false),
+                    AccountMeta::new_readonly(escrow_setup.event_authority, false),
+                    AccountMeta::new_readonly(escrow_setup.program_id, false),
+                ],
+                data: [vec![1u8], amount_a.to_le_bytes().to_vec()].concat(),
+            };
+            let message = Message::new(&[take_instruction], Some(&escrow_setup.taker.pubkey()));
+            let recent_blockhash = escrow_setup.litesvm.latest_blockhash();
+            let transaction = Transaction::new(&[&escrow_setup.taker], message, recent_blockhash);
+            let tx = escrow_setup.litesvm.send_transaction(transaction).unwrap();
+
+            let taker_lamports_after = escrow_setup.litesvm.get_balance(&escrow_setup.taker.pubkey()).unwrap();
+            assert_eq!(taker_lamports_after + 5_000, taker_lamports + amount_a);
+            assert_eq!(get_token_balance(&escrow_setup.litesvm, &escrow_setup.maker_ata_b), amount_b);
+
+            msg!("\n\nTake native SOL escrow transaction sucessfull");
+            msg!("CUs Consumed: {}", tx.compute_units_consumed);
+        } else {
+            // The maker refunds and gets the lamports back directly
+            let refund_instruction = Instruction {
+                program_id: escrow_setup.program_id,
+                accounts: vec![
+                    AccountMeta::new(maker_pubkey, true),
+                    AccountMeta::new(NATIVE_MINT, false),
+                    AccountMeta::new(escrow_setup.mint_b, false),
+                    AccountMeta::new(maker_pubkey, false),
+                    AccountMeta::new(vault, false),
+                    AccountMeta::new(escrow_pda, false),
+                    AccountMeta::new(SYSTEM_PROGRAM_ID, false),
+                    AccountMeta::new(SYSTEM_PROGRAM_ID, false),
+                    AccountMeta::new_readonly(escrow_setup.event_authority, false),
+                    AccountMeta::new_readonly(escrow_setup.program_id, false),
+                ],
+                data: vec![2u8],
+            };
+            let message = Message::new(&[refund_instruction], Some(&maker_pubkey));
+            let recent_blockhash = escrow_setup.litesvm.latest_blockhash();
+            let transaction = Transaction::new(&[&escrow_setup.maker], message, recent_blockhash);
+            let tx = escrow_setup.litesvm.send_transaction(transaction).unwrap();
+
+            msg!("\n\nRefund native SOL escrow transaction sucessfull");
+            msg!("CUs Consumed: {}", tx.compute_units_consumed);
+        }
+
+        // The vault and the escrow are closed either way
+        assert!(escrow_setup.litesvm.get_account(&vault).is_none_or(|account| account.lamports == 0));
+        assert!(escrow_setup.litesvm.get_account(&escrow_pda).is_none_or(|account| account.lamports == 0));
+    }
+}
+
+#[test]
+fn test_native_sol_mint_b() {
+    let mut escrow_setup = setup_escrow_test();
+
+    let seed: u8 = 3;
+    let amount_a: u64 = 30_000_000;
+    let amount_b: u64 = LAMPORTS_PER_SOL;
+    let maker_pubkey = escrow_setup.maker.pubkey();
+
+    // Derive the escrow PDA
+    let escrow_seeds: &[&[u8]] = &[b"escrow", maker_pubkey.as_ref(), &[seed]];
+    let (escrow_pda, escrow_bump) = Pubkey::find_program_address(escrow_seeds, &escrow_setup.program_id);
+
+    // Derive the vault PDA
+    let vault = get_associated_token_address(
+        &escrow_pda,
+        &escrow_setup.mint_a,
+    );
+
+    // Create the make instruction, asking for lamports in return
+    let make_data = [
+        vec![0u8],  // discriminator
+        amount_a.to_le_bytes().to_vec(),
+        amount_b.to_le_bytes().to_vec(),
+        vec![seed],
+        vec![escrow_bump],
+        0i64.to_le_bytes().to_vec(),  // expires_at (no expiry)
+    ].concat();
+    let make_accounts = vec![
+        AccountMeta::new(maker_pubkey, true),
+        AccountMeta::new(escrow_setup.mint_a, false),
+        AccountMeta::new(NATIVE_MINT, false),
+        AccountMeta::new(escrow_setup.maker_ata_a, false),
+        AccountMeta::new(vault, false),
+        AccountMeta::new(escrow_pda, false),
+        AccountMeta::new(SYSTEM_PROGRAM_ID, false),
+        AccountMeta::new(TOKEN_PROGRAM_ID, false),
+        AccountMeta::new(ASSOCIATED_TOKEN_PROGRAM_ID, false),
+        AccountMeta::new(escrow_setup.config, false),
+        AccountMeta::new_readonly(escrow_setup.event_authority, false),
+        AccountMeta::new_readonly(escrow_setup.program_id, false),
+    ];
+    let make_instruction = Instruction {
+        program_id: escrow_setup.program_id,
+        accounts: make_accounts,
+        data: make_data,
+    };
+    let message = Message::new(&[make_instruction], Some(&maker_pubkey));
+    let recent_blockhash = escrow_setup.litesvm.latest_blockhash();
+    let transaction = Transaction::new(&[&escrow_setup.maker], message, recent_blockhash);
+    escrow_setup.litesvm.send_transaction(transaction).unwrap();
+
+    // The taker fills half of the escrow and pays the maker with a system transfer
+    let maker_lamports = escrow_setup.litesvm.get_balance(&maker_pubkey).unwrap();
+    let take_instruction = Instruction {
+        program_id: escrow_setup.program_id,
+        accounts: vec![
+            AccountMeta::new(escrow_setup.taker.pubkey(), true),
+            AccountMeta::new(maker_pubkey, false),
+            AccountMeta::new(escrow_setup.mint_a, false),
+            AccountMeta::new(NATIVE_MINT, false),
+            AccountMeta::new(escrow_setup.taker_ata_a, false),
+            AccountMeta::new(escrow_setup.taker.pubkey(), false),
+            AccountMeta::new(vault, false),
+            AccountMeta::new(maker_pubkey, false),
+            AccountMeta::new(escrow_pda, false),
+            AccountMeta::new(SYSTEM_PROGRAM_ID, false),
+            AccountMeta::new(TOKEN_PROGRAM_ID, false),
+            AccountMeta::new(SYSTEM_PROGRAM_ID, false),
+            AccountMeta::new(escrow_setup.config, false),
+            AccountMeta::new(escrow_setup.treasury, false),
+            AccountMeta::new_readonly(escrow_setup.event_authority, false),
+            AccountMeta::new_readonly(escrow_setup.program_id, false),
+        ],
+        data: [vec![1u8], (amount_a / 2).to_le_bytes().to_vec()].concat(),
+    };
+    let message = Message::new(&[take_instruction], Some(&escrow_setup.taker.pubkey()));
+    let recent_blockhash = escrow_setup.litesvm.latest_blockhash();
+    let transaction = Transaction::new(&[&escrow_setup.taker], message, recent_blockhash);
+    let tx = escrow_setup.litesvm.send_transaction(transaction).unwrap();
+
+    assert_eq!(escrow_setup.litesvm.get_balance(&maker_pubkey).unwrap(), maker_lamports + amount_b / 2);
+    assert_eq!(get_token_balance(&escrow_setup.litesvm, &escrow_setup.taker_ata_a), amount_a / 2);
+
+    // Log transaction details
+    msg!("\n\nTake escrow paid in native SOL transaction sucessfull");
+    msg!("CUs Consumed: {}", tx.compute_units_consumed);
+
+}
@@ -0,0 +1,153 @@
+use litesvm::LiteSVM;
+use solana_account::Account;
+use solana_message::Message;
+use solana_pubkey::Pubkey;
+use solana_signer::Signer;
+use solana_transaction::Transaction;
+use solana_program::{clock::Clock, msg};
+
+use crate::client::{Escrow, EscrowSeed, MakeParams, OraclePricing, TakeParams, TOKEN_PROGRAM_ID, find_escrow_address, make_oracle_instruction, take_instruction, take_oracle_instruction};
+use crate::errors::EscrowError;
+use crate::tests::test_helpers::{EscrowTestSetup, assert_escrow_error, get_token_balance, setup_escrow_test};
+
+/// `sha256("account:PriceFeed")[..8]`
+const PRICE_FEED_DISCRIMINATOR: [u8; 8] = [189, 103, 252, 23, 152, 35, 243, 156];
+
+/// Writes a mock price feed account owned by `owner`: `price * 10^-8` mint_b per mint_a, published at `publish_time`
+fn set_price(litesvm: &mut LiteSVM, owner: &Pubkey, price_feed: &Pubkey, price: i64, conf: u64, publish_time: i64) {
+    let data = [
+        PRICE_FEED_DISCRIMINATOR.as_slice(),
+        &price.to_le_bytes(),
+        &conf.to_le_bytes(),
+        &(-8i32).to_le_bytes(),
+        &publish_time.to_le_bytes(),
+    ].concat();
+    litesvm.set_account(*price_feed, Account {
+        lamports: 1_000_000_000,
+        data,
+        owner: *owner,
+        executable: false,
+        rent_epoch: 0,
+    }).unwrap();
+}
+
+/// Builds a take of the oracle-priced escrow on a fresh blockhash, so retrying the same take is a new transaction
+fn take_transaction(escrow_setup: &mut EscrowTestSetup, price_feed: &Pubkey, amount_a: u64, max_amount_b: Option<u64>) -> Transaction {
+    let take_params = TakeParams {
+        taker: escrow_setup.taker.pubkey(),
+        maker: escrow_setup.maker.pubkey(),
+        mint_a: escrow_setup.mint_a,
+        mint_b: escrow_setup.mint_b,
+        token_program_a: TOKEN_PROGRAM_ID,
+        token_program_b: TOKEN_PROGRAM_ID,
+        treasury: escrow_setup.treasury,
+        seed: EscrowSeed::Wide(1),
+        amount_a,
+        max_amount_b,
+    };
+    let message = Message::new(&[take_oracle_instruction(&take_params, price_feed)], Some(&escrow_setup.taker.pubkey()));
+    escrow_setup.litesvm.expire_blockhash();
+    let recent_blockhash = escrow_setup.litesvm.latest_blockhash();
+    Transaction::new(&[&escrow_setup.taker], message, recent_blockhash)
+}
+
+#[test]
+fn test_oracle_priced_take() {
+    let mut escrow_setup = setup_escrow_test();
+
+    let maker_pubkey = escrow_setup.maker.pubkey();
+    let taker_pubkey = escrow_setup.taker.pubkey();
+    let price_feed = Pubkey::new_unique();
+    let price_feed_program = escrow_setup.price_feed_program;
+    let mut clock: Clock = escrow_setup.litesvm.get_sysvar();
+    let now = clock.unix_timestamp;
+
+    // 2.5 mint_b per mint_a, known to 0.04%
+    set_price(&mut escrow_setup.litesvm, &price_feed_program, &price_feed, 250_000_000, 100_000, now);
+
+    // Sell 30 mint_a at the feed price plus 1%, and never under 2 mint_b per mint_a
+    let oracle = OraclePricing {
+        price_feed,
+        premium_bps: 100,
+        max_price_age: 60,
+        max_confidence_bps: 50,
+    };
+    let make_params = MakeParams {
+        maker: maker_pubkey,
+        mint_a: escrow_setup.mint_a,
+        mint_b: escrow_setup.mint_b,
+        token_program_a: TOKEN_PROGRAM_ID,
+        amount_a: 30_000_000,
+        amount_b: 60_000_000,
+        seed: EscrowSeed::Wide(1),
+        expires_at: 0,
+        taker: None,
+        alternatives: vec![],
+    };
+    let message = Message::new(&[make_oracle_instruction(&make_params, &oracle)], Some(&maker_pubkey));
+    let recent_blockhash = escrow_setup.litesvm.latest_blockhash();
+    let transaction = Transaction::new(&[&escrow_setup.maker], message, recent_blockhash);
+    escrow_setup.litesvm.send_transaction(transaction).unwrap();
+
+    let (escrow_pda, _) = find_escrow_address(&maker_pubkey, EscrowSeed::Wide(1));
+    let escrow_account = escrow_setup.litesvm.get_account(&escrow_pda).unwrap();
+    assert_eq!(Escrow::from_bytes(&escrow_account.data).unwrap().oracle, Some(oracle));
+
+    // The price feed must be passed
+    let take_params = TakeParams {
+        taker: taker_pubkey,
+        maker: maker_pubkey,
+        mint_a: escrow_setup.mint_a,
+        mint_b: escrow_setup.mint_b,
+        token_program_a: TOKEN_PROGRAM_ID,
+        token_program_b: TOKEN_PROGRAM_ID,
+        treasury: escrow_setup.treasury,
+        seed: EscrowSeed::Wide(1),
+        amount_a: 10_000_000,
+        max_amount_b: None,
+    };
+    let message = Message::new(&[take_instruction(&take_params)], Some(&taker_pubkey));
+    let recent_blockhash = escrow_setup.litesvm.latest_blockhash();
+    let transaction = Transaction::new(&[&escrow_setup.taker], message, recent_blockhash);
+    assert!(escrow_setup.litesvm.send_transaction(transaction).is_err());
+    let transaction = take_transaction(&mut escrow_setup, &Pubkey::new_unique(), 10_000_000, Some(u64::MAX));
+    assert_escrow_error(escrow_setup.litesvm.send_transaction(transaction), EscrowError::InvalidPriceFeed);
+
+    // The feed must be owned by the trusted price feed program
+    set_price(&mut escrow_setup.litesvm, &Pubkey::new_unique(), &price_feed, 100_000_000, 0, now);
+    let transaction = take_transaction(&mut escrow_setup, &price_feed, 10_000_000, Some(u64::MAX));
+    assert_escrow_error(escrow_setup.litesvm.send_transaction(transaction), EscrowError::InvalidPriceFeed);
+    set_price(&mut escrow_setup.litesvm, &price_feed_program, &price_feed, 250_000_000, 100_000, now);
+
+    // The taker must bound what it pays at the feed price
+    let transaction = take_transaction(&mut escrow_setup, &price_feed, 10_000_000, None);
+    assert_escrow_error(escrow_setup.litesvm.send_transaction(transaction), EscrowError::MissingSlippageGuard);
+
+    // 10 mint_a at 2.5 * 1.01, above the floor; a taker that signed for the floor price is protected by the slippage guard
+    let transaction = take_transaction(&mut escrow_setup, &price_feed, 10_000_000, Some(20_000_000));
+    assert_escrow_error(escrow_setup.litesvm.send_transaction(transaction), EscrowError::SlippageExceeded);
+    let transaction = take_transaction(&mut escrow_setup, &price_feed, 10_000_000, Some(25_250_000));
+    let tx = escrow_setup.litesvm.send_transaction(transaction).unwrap();
+    assert_eq!(get_token_balance(&escrow_setup.litesvm, &escrow_setup.maker_ata_b), 25_250_000);
+
+    msg!("\n\nTake oracle-priced escrow transaction sucessfull");
+    msg!("CUs Consumed: {}", tx.compute_units_consumed);
+
+    // With the price at 1, the floor of 2 mint_b per mint_a applies
+    set_price(&mut escrow_setup.litesvm, &price_feed_program, &price_feed, 100_000_000, 0, now);
+    let transaction = take_transaction(&mut escrow_setup, &price_feed, 10_000_000, Some(20_000_000));
+    escrow_setup.litesvm.send_transaction(transaction).unwrap();
+    assert_eq!(get_token_balance(&escrow_setup.litesvm, &escrow_setup.maker_ata_b), 45_250_000);
+
+    // An imprecise price is rejected
+    set_price(&mut escrow_setup.litesvm, &price_feed_program, &price_feed, 250_000_000, 2_500_000, now);
+    let transaction = take_transaction(&mut escrow_setup, &price_feed, 10_000_000, Some(u64::MAX));
+    assert_escrow_error(escrow_setup.litesvm.send_transaction(transaction), EscrowError::PriceConfidenceTooWide);
+
+    // So is a price older than a minute
+    set_price(&mut escrow_setup.litesvm, &price_feed_program, &price_feed, 250_000_000, 0, now);
+    clock.unix_timestamp = now + 61;
+    escrow_setup.litesvm.set_sysvar(&clock);
+    let transaction = take_transaction(&mut escrow_setup, &price_feed, 10_000_000, Some(u64::MAX));
+    assert_escrow_error(escrow_setup.litesvm.send_transaction(transaction), EscrowError::StalePrice);
+}
@@ -0,0 +1,140 @@
+use litesvm_token::{CreateAssociatedTokenAccount, CreateMint, MintTo};
+use solana_message::Message;
+use solana_pubkey::Pubkey;
+use solana_signer::Signer;
+use solana_transaction::Transaction;
+use solana_program::msg;
+use spl_associated_token_account_interface::address::get_associated_token_address_with_program_id;
+
+use crate::client::{Escrow, MakeParams, NATIVE_MINT, TakeParams, TOKEN_PROGRAM_ID, find_escrow_address, make_instruction, take_instruction};
+use crate::errors::EscrowError;
+use crate::tests::test_helpers::{EscrowTestSetup, assert_escrow_error, get_token_balance, setup_escrow_test};
+
+/// Creates another mint the taker can pay with, with the taker, maker and treasury ATAs, funding the taker
+fn create_payment_mint(escrow_setup: &mut EscrowTestSetup) -> Pubkey {
+    let mint = CreateMint::new(&mut escrow_setup.litesvm, &escrow_setup._mint_authority)
+        .authority(&escrow_setup._mint_authority.pubkey())
+        .decimals(6)
+        .send()
+        .unwrap();
+    let taker_ata = CreateAssociatedTokenAccount::new(&mut escrow_setup.litesvm, &escrow_setup.taker, &mint)
+        .owner(&escrow_setup.taker.pubkey())
+        .send()
+        .unwrap();
+    CreateAssociatedTokenAccount::new(&mut escrow_setup.litesvm, &escrow_setup.maker, &mint)
+        .owner(&escrow_setup.maker.pubkey())
+        .send()
+        .unwrap();
+    CreateAssociatedTokenAccount::new(&mut escrow_setup.litesvm, &escrow_setup.admin, &mint)
+        .owner(&escrow_setup.treasury)
+        .send()
+        .unwrap();
+    MintTo::new(&mut escrow_setup.litesvm, &escrow_setup._mint_authority, &mint, &taker_ata, 100_000_000)
+        .send()
+        .unwrap();
+    mint
+}
+
+fn make_params(escrow_setup: &EscrowTestSetup, seed: u8, alternatives: Vec<(Pubkey, u64)>) -> MakeParams {
+    MakeParams {
+        maker: escrow_setup.maker.pubkey(),
+        mint_a: escrow_setup.mint_a,
+        mint_b: escrow_setup.mint_b,
+        token_program_a: TOKEN_PROGRAM_ID,
+        amount_a: 30_000_000,
+        amount_b: 60_000_000,
+        seed: seed.into(),
+        expires_at: 0,
+        taker: None,
+        alternatives,
+    }
+}
+
+fn take_params(escrow_setup: &EscrowTestSetup, seed: u8, mint_b: Pubkey, amount_a: u64) -> TakeParams {
+    TakeParams {
+        taker: escrow_setup.taker.pubkey(),
+        maker: escrow_setup.maker.pubkey(),
+        mint_a: escrow_setup.mint_a,
+        mint_b,
+        token_program_a: TOKEN_PROGRAM_ID,
+        token_program_b: TOKEN_PROGRAM_ID,
+        treasury: escrow_setup.treasury,
+        seed: seed.into(),
+        amount_a,
+        max_amount_b: None,
+    }
+}
+
+#[test]
+fn test_take_with_payment_alternatives() {
+    let mut escrow_setup = setup_escrow_test();
+    let mint_c = create_payment_mint(&mut escrow_setup);
+
+    let seed: u8 = 1;
+    let maker_pubkey = escrow_setup.maker.pubkey();
+    let taker_pubkey = escrow_setup.taker.pubkey();
+
+    // Accept 60 of mint_b, 90 of mint_c or 3 SOL for the 30 mint_a
+    let alternatives = vec![(mint_c, 90_000_000), (NATIVE_MINT, 3_000_000_000)];
+    let make_instruction = make_instruction(&make_params(&escrow_setup, seed, alternatives.clone()));
+    let message = Message::new(&[make_instruction], Some(&maker_pubkey));
+    let recent_blockhash = escrow_setup.litesvm.latest_blockhash();
+    let transaction = Transaction::new(&[&escrow_setup.maker], message, recent_blockhash);
+    escrow_setup.litesvm.send_transaction(transaction).unwrap();
+
+    let (escrow_pda, _) = find_escrow_address(&maker_pubkey, seed);
+    let escrow_account = escrow_setup.litesvm.get_account(&escrow_pda).unwrap();
+    assert_eq!(Escrow::from_bytes(&escrow_account.data).unwrap().alternatives, alternatives);
+
+    // Take a third of the escrow in every accepted mint, each at its own price
+    let maker_ata_c = get_associated_token_address_with_program_id(&maker_pubkey, &mint_c, &TOKEN_PROGRAM_ID);
+    let maker_lamports = escrow_setup.litesvm.get_account(&maker_pubkey).unwrap().lamports;
+    for mint_b in [mint_c, NATIVE_MINT, escrow_setup.mint_b] {
+        let take_instruction = take_instruction(&take_params(&escrow_setup, seed, mint_b, 10_000_000));
+        let message = Message::new(&[take_instruction], Some(&taker_pubkey));
+        let recent_blockhash = escrow_setup.litesvm.latest_blockhash();
+        let transaction = Transaction::new(&[&escrow_setup.taker], message, recent_blockhash);
+        let tx = escrow_setup.litesvm.send_transaction(transaction).unwrap();
+
+        msg!("\n\nTake paying with {} transaction sucessfull", mint_b);
+        msg!("CUs Consumed: {}", tx.compute_units_consumed);
+
+        if mint_b == NATIVE_MINT {
+            assert_eq!(escrow_setup.litesvm.get_account(&maker_pubkey).unwrap().lamports, maker_lamports + 1_000_000_000);
+        }
+    }
+    assert_eq!(get_token_balance(&escrow_setup.litesvm, &maker_ata_c), 30_000_000);
+    assert_eq!(get_token_balance(&escrow_setup.litesvm, &escrow_setup.maker_ata_b), 20_000_000);
+    assert_eq!(get_token_balance(&escrow_setup.litesvm, &escrow_setup.taker_ata_a), 30_000_000);
+}
+
+#[test]
+fn test_take_with_unlisted_mint() {
+    let mut escrow_setup = setup_escrow_test();
+    let mint_c = create_payment_mint(&mut escrow_setup);
+    let mint_d = create_payment_mint(&mut escrow_setup);
+
+    let seed: u8 = 2;
+    let maker_pubkey = escrow_setup.maker.pubkey();
+    let taker_pubkey = escrow_setup.taker.pubkey();
+
+    // mint_b can't also be an alternative
+    let make_instruction_with_duplicate = make_instruction(&make_params(&escrow_setup, seed, vec![(escrow_setup.mint_b, 90_000_000)]));
+    let message = Message::new(&[make_instruction_with_duplicate], Some(&maker_pubkey));
+    let recent_blockhash = escrow_setup.litesvm.latest_blockhash();
+    let transaction = Transaction::new(&[&escrow_setup.maker], message, recent_blockhash);
+    assert_escrow_error(escrow_setup.litesvm.send_transaction(transaction), EscrowError::InvalidPaymentAlternatives);
+
+    let make_instruction = make_instruction(&make_params(&escrow_setup, seed, vec![(mint_c, 90_000_000)]));
+    let message = Message::new(&[make_instruction], Some(&maker_pubkey));
+    let recent_blockhash = escrow_setup.litesvm.latest_blockhash();
+    let transaction = Transaction::new(&[&escrow_setup.maker], message, recent_blockhash);
+    escrow_setup.litesvm.send_transaction(transaction).unwrap();
+
+    // A mint that is neither mint_b nor an alternative is rejected
+    let take_instruction = take_instruction(&take_params(&escrow_setup, seed, mint_d, 10_000_000));
+    let message = Message::new(&[take_instruction], Some(&taker_pubkey));
+    let recent_blockhash = escrow_setup.litesvm.latest_blockhash();
+    let transaction = Transaction::new(&[&escrow_setup.taker], message, recent_blockhash);
+    assert_escrow_error(escrow_setup.litesvm.send_transaction(transaction), EscrowError::MintMismatch);
+}
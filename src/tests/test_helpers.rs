@@ -1,17 +1,27 @@
-use litesvm::LiteSVM;
+use litesvm::{LiteSVM, types::TransactionResult};
 use litesvm_token::{
-    CreateAssociatedTokenAccount, CreateMint, MintTo
+    CreateAssociatedTokenAccount, CreateMint, MintTo, TOKEN_ID, Transfer
 };
+use solana_account::Account;
 use solana_keypair::Keypair;
 use solana_native_token::LAMPORTS_PER_SOL;
 use solana_pubkey::{Pubkey};
 use solana_signer::Signer;
 use solana_program::msg;
+use solana_instruction::{AccountMeta, Instruction, error::InstructionError};
+use solana_message::Message;
+use solana_transaction::Transaction;
+use solana_transaction_error::TransactionError;
+
+use crate::errors::EscrowError;
 
 use std::path::PathBuf;
 
+const SYSTEM_PROGRAM_ID: Pubkey = solana_pubkey::pubkey!("11111111111111111111111111111111");
+const BPF_LOADER_UPGRADEABLE_ID: Pubkey = solana_pubkey::pubkey!("BPFLoaderUpgradeab1e11111111111111111111111");
+
 pub fn get_program_id() -> Pubkey {
-    Pubkey::from(crate::ID)
+    crate::ID
 }
 
 /// # Escrow Test Setup
@@ -41,9 +51,26 @@ pub struct EscrowTestSetup {
     pub taker_ata_a: Pubkey,
     /// The taker ATA for the mint_b
     pub taker_ata_b: Pubkey,
+    /// The config admin Keypair
+    pub admin: Keypair,
+    /// The global config PDA
+    pub config: Pubkey,
+    /// The treasury receiving the protocol fee
+    pub treasury: Pubkey,
+    /// The treasury ATA for the mint_b
+    pub treasury_ata_b: Pubkey,
+    /// The program trusted to own the price feeds
+    pub price_feed_program: Pubkey,
+    /// The PDA signing the event self-CPI
+    pub event_authority: Pubkey,
 }
 
 pub fn setup_escrow_test() -> EscrowTestSetup {
+    setup_escrow_test_with_token_programs(&TOKEN_ID, &TOKEN_ID)
+}
+
+/// Same as `setup_escrow_test`, with `mint_a` and `mint_b` created under the given token programs
+pub fn setup_escrow_test_with_token_programs(token_program_a: &Pubkey, token_program_b: &Pubkey) -> EscrowTestSetup {
 
     // Create a new LitesVM instance
     let mut litesvm = LiteSVM::new();
@@ -62,11 +89,13 @@ pub fn setup_escrow_test() -> EscrowTestSetup {
     let mint_a = CreateMint::new(&mut litesvm, &mint_authority)
         .authority(&mint_authority.pubkey())
         .decimals(9)
+        .token_program_id(token_program_a)
         .send()
         .unwrap();
     let mint_b = CreateMint::new(&mut litesvm, &mint_authority)
         .authority(&mint_authority.pubkey())
         .decimals(9)
+        .token_program_id(token_program_b)
         .send()
         .unwrap();
     msg!("Mint A created: {}", mint_a);
@@ -81,18 +110,22 @@ pub fn setup_escrow_test() -> EscrowTestSetup {
     // Create the ATA accounts
     let maker_ata_a = CreateAssociatedTokenAccount::new(&mut litesvm, &maker, &mint_a)
         .owner(&maker.pubkey())
+        .token_program_id(token_program_a)
         .send()
         .unwrap();
     let maker_ata_b = CreateAssociatedTokenAccount::new(&mut litesvm, &maker, &mint_b)
         .owner(&maker.pubkey())
+        .token_program_id(token_program_b)
         .send()
         .unwrap();
     let taker_ata_a = CreateAssociatedTokenAccount::new(&mut litesvm, &taker, &mint_a)
         .owner(&taker.pubkey())
+        .token_program_id(token_program_a)
         .send()
         .unwrap();
     let taker_ata_b = CreateAssociatedTokenAccount::new(&mut litesvm, &taker, &mint_b)
         .owner(&taker.pubkey())
+        .token_program_id(token_program_b)
         .send()
         .unwrap();
     msg!("Maker ATA Mint A created: {}", maker_ata_a);
@@ -102,14 +135,34 @@ pub fn setup_escrow_test() -> EscrowTestSetup {
 
     // Mint tokens to the maker and taker
     MintTo::new(&mut litesvm, &mint_authority, &mint_a, &maker_ata_a, 100_000_000)
+        .token_program_id(token_program_a)
         .send()
         .unwrap();
     MintTo::new(&mut litesvm, &mint_authority, &mint_b, &taker_ata_b, 100_000_000)
+        .token_program_id(token_program_b)
         .send()
         .unwrap();
     msg!("Minted 100 tokens of Mint A to Maker");
     msg!("Minted 100 tokens of Mint B to Taker");
 
+    // Create the global config with no protocol fee and the treasury ATA
+    let admin = Keypair::new();
+    let treasury = Pubkey::new_unique();
+    let price_feed_program = Pubkey::new_unique();
+    litesvm.airdrop(&admin.pubkey(), 10 * LAMPORTS_PER_SOL).expect("Failed to airdrop");
+    let treasury_ata_b = CreateAssociatedTokenAccount::new(&mut litesvm, &admin, &mint_b)
+        .owner(&treasury)
+        .token_program_id(token_program_b)
+        .send()
+        .unwrap();
+    let (config, _) = Pubkey::find_program_address(&[b"config"], &program_id);
+    set_upgrade_authority(&mut litesvm, &admin.pubkey());
+    let transaction = init_config_transaction(&litesvm, &admin, 0, &treasury, &price_feed_program);
+    litesvm.send_transaction(transaction).expect("Failed to init config");
+    msg!("Config created: {}", config);
+
+    let (event_authority, _) = Pubkey::find_program_address(&[b"__event_authority"], &program_id);
+
     EscrowTestSetup {
         litesvm,
         program_id,
@@ -122,6 +175,95 @@ pub fn setup_escrow_test() -> EscrowTestSetup {
         maker_ata_b,
         taker_ata_a,
         taker_ata_b,
+        admin,
+        config,
+        treasury,
+        treasury_ata_b,
+        price_feed_program,
+        event_authority,
     }
 }
 
+/// Plants the program data account of the escrow program, as the upgradeable loader would on deployment
+pub fn set_upgrade_authority(litesvm: &mut LiteSVM, upgrade_authority: &Pubkey) {
+    let (program_data, _) = Pubkey::find_program_address(&[get_program_id().as_ref()], &BPF_LOADER_UPGRADEABLE_ID);
+    let data = [
+        &3u32.to_le_bytes()[..],  // account type: program data
+        &0u64.to_le_bytes(),  // deployment slot
+        &[1u8],  // Some(upgrade_authority)
+        upgrade_authority.as_ref(),
+    ].concat();
+    litesvm.set_account(program_data, Account {
+        lamports: 1_000_000_000,
+        data,
+        owner: BPF_LOADER_UPGRADEABLE_ID,
+        executable: false,
+        rent_epoch: 0,
+    }).unwrap();
+}
+
+/// Builds the transaction creating the global config, signed by `admin` which must be the upgrade authority
+pub fn init_config_transaction(litesvm: &LiteSVM, admin: &Keypair, fee_bps: u16, treasury: &Pubkey, price_feed_program: &Pubkey) -> Transaction {
+    let program_id = get_program_id();
+    let (config, _) = Pubkey::find_program_address(&[b"config"], &program_id);
+    let (program_data, _) = Pubkey::find_program_address(&[program_id.as_ref()], &BPF_LOADER_UPGRADEABLE_ID);
+    let init_config_instruction = Instruction {
+        program_id,
+        accounts: vec![
+            AccountMeta::new(admin.pubkey(), true),
+            AccountMeta::new(config, false),
+            AccountMeta::new(SYSTEM_PROGRAM_ID, false),
+            AccountMeta::new_readonly(program_data, false),
+        ],
+        data: [
+            vec![4u8],  // discriminator
+            fee_bps.to_le_bytes().to_vec(),
+            treasury.to_bytes().to_vec(),
+            price_feed_program.to_bytes().to_vec(),
+        ].concat(),
+    };
+    let message = Message::new(&[init_config_instruction], Some(&admin.pubkey()));
+    let recent_blockhash = litesvm.latest_blockhash();
+    Transaction::new(&[admin], message, recent_blockhash)
+}
+
+/// Sets the protocol fee in the global config, signed by the config admin
+pub fn set_protocol_fee(escrow_setup: &mut EscrowTestSetup, fee_bps: u16) {
+    let update_config_instruction = Instruction {
+        program_id: escrow_setup.program_id,
+        accounts: vec![
+            AccountMeta::new(escrow_setup.admin.pubkey(), true),
+            AccountMeta::new(escrow_setup.config, false),
+        ],
+        data: [
+            vec![5u8],  // discriminator
+            fee_bps.to_le_bytes().to_vec(),
+            escrow_setup.treasury.to_bytes().to_vec(),
+            escrow_setup.admin.pubkey().to_bytes().to_vec(),
+            escrow_setup.price_feed_program.to_bytes().to_vec(),
+        ].concat(),
+    };
+    let message = Message::new(&[update_config_instruction], Some(&escrow_setup.admin.pubkey()));
+    let recent_blockhash = escrow_setup.litesvm.latest_blockhash();
+    let transaction = Transaction::new(&[&escrow_setup.admin], message, recent_blockhash);
+    escrow_setup.litesvm.send_transaction(transaction).expect("Failed to update config");
+}
+
+/// Reads the `amount` field of an SPL token account
+pub fn get_token_balance(litesvm: &LiteSVM, token_account: &Pubkey) -> u64 {
+    let account = litesvm.get_account(token_account).expect("Token account not found");
+    u64::from_le_bytes(account.data[64..72].try_into().unwrap())
+}
+
+/// Sends `amount` of `mint` from the owner's ATA straight to a token account, as anyone can do to an escrow vault
+pub fn send_tokens(litesvm: &mut LiteSVM, owner: &Keypair, mint: &Pubkey, to: &Pubkey, amount: u64) {
+    Transfer::new(litesvm, owner, mint, to, amount)
+        .send()
+        .expect("Failed to send tokens");
+}
+
+/// Asserts that the transaction failed in its first instruction with the given escrow error
+pub fn assert_escrow_error(result: TransactionResult, error: EscrowError) {
+    let failed = result.expect_err("Transaction should have failed");
+    assert_eq!(failed.err, TransactionError::InstructionError(0, InstructionError::Custom(error as u32)));
+}
@@ -0,0 +1,177 @@
+use solana_instruction::{AccountMeta, Instruction};
+use solana_message::Message;
+use solana_signer::Signer;
+use solana_transaction::Transaction;
+use spl_associated_token_account_interface::address::get_associated_token_address_with_program_id;
+use solana_pubkey::{Pubkey, pubkey};
+use solana_program::msg;
+use litesvm_token::spl_token::ID as TOKEN_PROGRAM_ID;
+use spl_associated_token_account_interface::program::ID as ASSOCIATED_TOKEN_PROGRAM_ID;
+
+use crate::errors::EscrowError;
+use crate::tests::test_helpers::{assert_escrow_error, get_token_balance, setup_escrow_test, setup_escrow_test_with_token_programs};
+
+const SYSTEM_PROGRAM_ID: Pubkey = pubkey!("11111111111111111111111111111111");
+const TOKEN_2022_PROGRAM_ID: Pubkey = pubkey!("TokenzQdBNbLqP5VEhdkAS6EPFLC1PHnBqCXEpPxuEb");
+
+#[test]
+fn test_take_token_2022_mint_a() {
+    let mut escrow_setup = setup_escrow_test_with_token_programs(&TOKEN_2022_PROGRAM_ID, &TOKEN_PROGRAM_ID);
+
+    let seed: u8 = 1;
+    let amount_a: u64 = 30_000_000;
+    let amount_b: u64 = 70_000_000;
+
+    // Derive the escrow PDA
+    let maker_pubkey = escrow_setup.maker.pubkey();
+    let escrow_seeds: &[&[u8]] = &[b"escrow", maker_pubkey.as_ref(), &[seed]];
+    let (escrow_pda, escrow_bump) = Pubkey::find_program_address(escrow_seeds, &escrow_setup.program_id);
+
+    // Derive the vault PDA under the Token-2022 program
+    let vault = get_associated_token_address_with_program_id(
+        &escrow_pda,
+        &escrow_setup.mint_a,
+        &TOKEN_2022_PROGRAM_ID,
+    );
+
+    // Create the make instruction
+    let make_data = [
+        vec![0u8],  // discriminator
+        amount_a.to_le_bytes().to_vec(),
+        amount_b.to_le_bytes().to_vec(),
+        vec![seed],
+        vec![escrow_bump],
+        0i64.to_le_bytes().to_vec(),  // expires_at (no expiry)
+    ].concat();
+    let make_accounts = vec![
+        AccountMeta::new(escrow_setup.maker.pubkey(), true),
+        AccountMeta::new(escrow_setup.mint_a, false),
+        AccountMeta::new(escrow_setup.mint_b, false),
+        AccountMeta::new(escrow_setup.maker_ata_a, false),
+        AccountMeta::new(vault, false),
+        AccountMeta::new(escrow_pda, false),
+        AccountMeta::new(SYSTEM_PROGRAM_ID, false),
+        AccountMeta::new(TOKEN_2022_PROGRAM_ID, false),
+        AccountMeta::new(ASSOCIATED_TOKEN_PROGRAM_ID, false),
+        AccountMeta::new(escrow_setup.config, false),
+        AccountMeta::new_readonly(escrow_setup.event_authority, false),
+        AccountMeta::new_readonly(escrow_setup.program_id, false),
+    ];
+    let make_instruction = Instruction {
+        program_id: escrow_setup.program_id,
+        accounts: make_accounts,
+        data: make_data,
+    };
+    let message = Message::new(&[make_instruction], Some(&escrow_setup.maker.pubkey()));
+    let recent_blockhash = escrow_setup.litesvm.latest_blockhash();
+    let transaction = Transaction::new(&[&escrow_setup.maker], message, recent_blockhash);
+    escrow_setup.litesvm.send_transaction(transaction).unwrap();
+
+    // Create the take instruction, each leg goes through the token program of its mint
+    let take_data = [
+        vec![1u8],  // discriminator
+        amount_a.to_le_bytes().to_vec(),
+    ].concat();
+    let take_accounts = vec![
+        AccountMeta::new(escrow_setup.taker.pubkey(), true),
+        AccountMeta::new(escrow_setup.maker.pubkey(), false),
+        AccountMeta::new(escrow_setup.mint_a, false),
+        AccountMeta::new(escrow_setup.mint_b, false),
+        AccountMeta::new(escrow_setup.taker_ata_a, false),
+        AccountMeta::new(escrow_setup.taker_ata_b, false),
+        AccountMeta::new(vault, false),
+        AccountMeta::new(escrow_setup.maker_ata_b, false),
+        AccountMeta::new(escrow_pda, false),
+        AccountMeta::new(SYSTEM_PROGRAM_ID, false),
+        AccountMeta::new(TOKEN_2022_PROGRAM_ID, false),
+        AccountMeta::new(TOKEN_PROGRAM_ID, false),
+        AccountMeta::new(escrow_setup.config, false),
+        AccountMeta::new(escrow_setup.treasury_ata_b, false),
+        AccountMeta::new_readonly(escrow_setup.event_authority, false),
+        AccountMeta::new_readonly(escrow_setup.program_id, false),
+    ];
+    let take_instruction = Instruction {
+        program_id: escrow_setup.program_id,
+        accounts: take_accounts,
+        data: take_data,
+    };
+    let message = Message::new(&[take_instruction], Some(&escrow_setup.taker.pubkey()));
+    let recent_blockhash = escrow_setup.litesvm.latest_blockhash();
+    let transaction = Transaction::new(&[&escrow_setup.taker], message, recent_blockhash);
+    let tx = escrow_setup.litesvm.send_transaction(transaction).unwrap();
+
+    assert_eq!(get_token_balance(&escrow_setup.litesvm, &escrow_setup.taker_ata_a), amount_a);
+    assert_eq!(get_token_balance(&escrow_setup.litesvm, &escrow_setup.maker_ata_b), amount_b);
+
+    // Log transaction details
+    msg!("\n\nToken-2022 take escrow transaction sucessfull");
+    msg!("CUs Consumed: {}", tx.compute_units_consumed);
+
+}
+
+#[test]
+fn test_make_rejects_transfer_fee_mint() {
+    let mut escrow_setup = setup_escrow_test();
+
+    let seed: u8 = 2;
+    let amount_a: u64 = 30_000_000;
+    let amount_b: u64 = 70_000_000;
+
+    // Turn mint_b into a Token-2022 mint carrying a TransferFeeConfig extension
+    let mut mint_b_account = escrow_setup.litesvm.get_account(&escrow_setup.mint_b).unwrap();
+    let mut data = mint_b_account.data.clone();
+    data.resize(165, 0);
+    data.push(1);  // account type: mint
+    data.extend_from_slice(&1u16.to_le_bytes());  // extension type: TransferFeeConfig
+    data.extend_from_slice(&108u16.to_le_bytes());  // extension length
+    data.extend_from_slice(&[0u8; 108]);
+    mint_b_account.data = data;
+    mint_b_account.owner = TOKEN_2022_PROGRAM_ID;
+    escrow_setup.litesvm.set_account(escrow_setup.mint_b, mint_b_account).unwrap();
+
+    // Derive the escrow PDA
+    let maker_pubkey = escrow_setup.maker.pubkey();
+    let escrow_seeds: &[&[u8]] = &[b"escrow", maker_pubkey.as_ref(), &[seed]];
+    let (escrow_pda, escrow_bump) = Pubkey::find_program_address(escrow_seeds, &escrow_setup.program_id);
+
+    // Derive the vault PDA
+    let vault = get_associated_token_address_with_program_id(
+        &escrow_pda,
+        &escrow_setup.mint_a,
+        &TOKEN_PROGRAM_ID,
+    );
+
+    // Create the make instruction
+    let make_data = [
+        vec![0u8],  // discriminator
+        amount_a.to_le_bytes().to_vec(),
+        amount_b.to_le_bytes().to_vec(),
+        vec![seed],
+        vec![escrow_bump],
+        0i64.to_le_bytes().to_vec(),  // expires_at (no expiry)
+    ].concat();
+    let make_accounts = vec![
+        AccountMeta::new(escrow_setup.maker.pubkey(), true),
+        AccountMeta::new(escrow_setup.mint_a, false),
+        AccountMeta::new(escrow_setup.mint_b, false),
+        AccountMeta::new(escrow_setup.maker_ata_a, false),
+        AccountMeta::new(vault, false),
+        AccountMeta::new(escrow_pda, false),
+        AccountMeta::new(SYSTEM_PROGRAM_ID, false),
+        AccountMeta::new(TOKEN_PROGRAM_ID, false),
+        AccountMeta::new(ASSOCIATED_TOKEN_PROGRAM_ID, false),
+        AccountMeta::new(escrow_setup.config, false),
+        AccountMeta::new_readonly(escrow_setup.event_authority, false),
+        AccountMeta::new_readonly(escrow_setup.program_id, false),
+    ];
+    let make_instruction = Instruction {
+        program_id: escrow_setup.program_id,
+        accounts: make_accounts,
+        data: make_data,
+    };
+    let message = Message::new(&[make_instruction], Some(&escrow_setup.maker.pubkey()));
+    let recent_blockhash = escrow_setup.litesvm.latest_blockhash();
+    let transaction = Transaction::new(&[&escrow_setup.maker], message, recent_blockhash);
+    assert_escrow_error(escrow_setup.litesvm.send_transaction(transaction), EscrowError::UnsupportedMintExtension);
+
+}
\ No newline at end of file
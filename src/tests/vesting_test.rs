@@ -0,0 +1,149 @@
+use litesvm_token::{CreateAssociatedTokenAccount, MintTo};
+use solana_message::Message;
+use solana_signer::Signer;
+use solana_transaction::Transaction;
+use solana_program::{clock::Clock, msg};
+use spl_associated_token_account_interface::address::get_associated_token_address;
+
+use crate::client::{ClaimParams, Escrow, EscrowSeed, MakeParams, TakeParams, TOKEN_PROGRAM_ID, Vesting, VestingSchedule, claim_instruction, find_escrow_address, find_vesting_address, make_vesting_instruction, take_vesting_instruction};
+use crate::errors::EscrowError;
+use crate::tests::test_helpers::{EscrowTestSetup, assert_escrow_error, get_token_balance, setup_escrow_test};
+
+/// Builds a claim of the taker vesting on a fresh blockhash, so retrying the same claim is a new transaction
+fn claim_transaction(escrow_setup: &mut EscrowTestSetup) -> Transaction {
+    let claim_params = ClaimParams {
+        beneficiary: escrow_setup.taker.pubkey(),
+        maker: escrow_setup.maker.pubkey(),
+        mint_a: escrow_setup.mint_a,
+        token_program_a: TOKEN_PROGRAM_ID,
+        seed: EscrowSeed::Wide(1),
+    };
+    let message = Message::new(&[claim_instruction(&claim_params)], Some(&escrow_setup.taker.pubkey()));
+    escrow_setup.litesvm.expire_blockhash();
+    let recent_blockhash = escrow_setup.litesvm.latest_blockhash();
+    Transaction::new(&[&escrow_setup.taker], message, recent_blockhash)
+}
+
+#[test]
+fn test_vesting_take_and_claim() {
+    let mut escrow_setup = setup_escrow_test();
+
+    let maker_pubkey = escrow_setup.maker.pubkey();
+    let taker_pubkey = escrow_setup.taker.pubkey();
+    let mut clock: Clock = escrow_setup.litesvm.get_sysvar();
+    let now = clock.unix_timestamp;
+
+    // Sell 30 mint_a for 60 mint_b, vesting over 100 seconds after the take with a 25 seconds cliff
+    let make_params = MakeParams {
+        maker: maker_pubkey,
+        mint_a: escrow_setup.mint_a,
+        mint_b: escrow_setup.mint_b,
+        token_program_a: TOKEN_PROGRAM_ID,
+        amount_a: 30_000_000,
+        amount_b: 60_000_000,
+        seed: EscrowSeed::Wide(1),
+        expires_at: 0,
+        taker: None,
+        alternatives: vec![],
+    };
+    let schedule = VestingSchedule {
+        vesting_cliff: 25,
+        vesting_duration: 100,
+    };
+
+    // The cliff can't come after the end of the vesting
+    let late_cliff = VestingSchedule { vesting_cliff: 101, ..schedule };
+    let message = Message::new(&[make_vesting_instruction(&make_params, &late_cliff)], Some(&maker_pubkey));
+    let recent_blockhash = escrow_setup.litesvm.latest_blockhash();
+    let transaction = Transaction::new(&[&escrow_setup.maker], message, recent_blockhash);
+    assert_escrow_error(escrow_setup.litesvm.send_transaction(transaction), EscrowError::InvalidVestingTerms);
+
+    let message = Message::new(&[make_vesting_instruction(&make_params, &schedule)], Some(&maker_pubkey));
+    let recent_blockhash = escrow_setup.litesvm.latest_blockhash();
+    let transaction = Transaction::new(&[&escrow_setup.maker], message, recent_blockhash);
+    escrow_setup.litesvm.send_transaction(transaction).unwrap();
+
+    let (escrow_pda, _) = find_escrow_address(&maker_pubkey, EscrowSeed::Wide(1));
+    let escrow_account = escrow_setup.litesvm.get_account(&escrow_pda).unwrap();
+    assert_eq!(Escrow::from_bytes(&escrow_account.data).unwrap().vesting, Some(schedule));
+
+    // The take pays the maker but locks mint_a in the vesting account of the taker
+    let taker_a_before = get_token_balance(&escrow_setup.litesvm, &escrow_setup.taker_ata_a);
+    let take_params = TakeParams {
+        taker: taker_pubkey,
+        maker: maker_pubkey,
+        mint_a: escrow_setup.mint_a,
+        mint_b: escrow_setup.mint_b,
+        token_program_a: TOKEN_PROGRAM_ID,
+        token_program_b: TOKEN_PROGRAM_ID,
+        treasury: escrow_setup.treasury,
+        seed: EscrowSeed::Wide(1),
+        amount_a: 10_000_000,
+        max_amount_b: None,
+    };
+
+    // A vesting escrow is taken whole, as the taker has a single vesting account for it
+    let message = Message::new(&[take_vesting_instruction(&take_params)], Some(&taker_pubkey));
+    let recent_blockhash = escrow_setup.litesvm.latest_blockhash();
+    let transaction = Transaction::new(&[&escrow_setup.taker], message, recent_blockhash);
+    assert_escrow_error(escrow_setup.litesvm.send_transaction(transaction), EscrowError::PartialVestingFill);
+
+    // Funding the vesting address or creating its ATA ahead of the take doesn't block it
+    let (vesting_pda, _) = find_vesting_address(&escrow_pda, &taker_pubkey);
+    escrow_setup.litesvm.airdrop(&vesting_pda, 1_000).unwrap();
+    CreateAssociatedTokenAccount::new(&mut escrow_setup.litesvm, &escrow_setup.maker, &escrow_setup.mint_a)
+        .owner(&vesting_pda)
+        .send()
+        .unwrap();
+
+    let take_params = TakeParams { amount_a: 30_000_000, ..take_params };
+    let message = Message::new(&[take_vesting_instruction(&take_params)], Some(&taker_pubkey));
+    let recent_blockhash = escrow_setup.litesvm.latest_blockhash();
+    let transaction = Transaction::new(&[&escrow_setup.taker], message, recent_blockhash);
+    let tx = escrow_setup.litesvm.send_transaction(transaction).unwrap();
+
+    msg!("\n\nTake vesting escrow transaction sucessfull");
+    msg!("CUs Consumed: {}", tx.compute_units_consumed);
+
+    let vesting_vault = get_associated_token_address(&vesting_pda, &escrow_setup.mint_a);
+    let vesting = Vesting::from_bytes(&escrow_setup.litesvm.get_account(&vesting_pda).unwrap().data).unwrap();
+    assert_eq!(vesting.beneficiary, taker_pubkey);
+    assert_eq!((vesting.amount_a, vesting.claimed_a), (30_000_000, 0));
+    assert_eq!((vesting.start, vesting.cliff, vesting.end), (now, now + 25, now + 100));
+    assert_eq!(get_token_balance(&escrow_setup.litesvm, &vesting_vault), 30_000_000);
+    assert_eq!(get_token_balance(&escrow_setup.litesvm, &escrow_setup.taker_ata_a), taker_a_before);
+    assert!(escrow_setup.litesvm.get_account(&escrow_pda).is_none_or(|account| account.lamports == 0));
+
+    // Nothing can be claimed before the cliff
+    clock.unix_timestamp = now + 20;
+    escrow_setup.litesvm.set_sysvar(&clock);
+    let transaction = claim_transaction(&mut escrow_setup);
+    assert_escrow_error(escrow_setup.litesvm.send_transaction(transaction), EscrowError::NothingToClaim);
+
+    // Halfway, half of mint_a has vested
+    clock.unix_timestamp = now + 50;
+    escrow_setup.litesvm.set_sysvar(&clock);
+    let transaction = claim_transaction(&mut escrow_setup);
+    let tx = escrow_setup.litesvm.send_transaction(transaction).unwrap();
+    assert_eq!(get_token_balance(&escrow_setup.litesvm, &escrow_setup.taker_ata_a), taker_a_before + 15_000_000);
+    assert_eq!(get_token_balance(&escrow_setup.litesvm, &vesting_vault), 15_000_000);
+
+    msg!("\n\nClaim transaction sucessfull");
+    msg!("CUs Consumed: {}", tx.compute_units_consumed);
+
+    // Nothing more until time passes
+    let transaction = claim_transaction(&mut escrow_setup);
+    assert_escrow_error(escrow_setup.litesvm.send_transaction(transaction), EscrowError::NothingToClaim);
+
+    // Once fully vested, the last claim empties the vault, tokens sent to it included, and closes it with the vesting account
+    MintTo::new(&mut escrow_setup.litesvm, &escrow_setup._mint_authority, &escrow_setup.mint_a, &vesting_vault, 1_000_000)
+        .send()
+        .unwrap();
+    clock.unix_timestamp = now + 150;
+    escrow_setup.litesvm.set_sysvar(&clock);
+    let transaction = claim_transaction(&mut escrow_setup);
+    escrow_setup.litesvm.send_transaction(transaction).unwrap();
+    assert_eq!(get_token_balance(&escrow_setup.litesvm, &escrow_setup.taker_ata_a), taker_a_before + 31_000_000);
+    assert!(escrow_setup.litesvm.get_account(&vesting_pda).is_none_or(|account| account.lamports == 0));
+    assert!(escrow_setup.litesvm.get_account(&vesting_vault).is_none_or(|account| account.lamports == 0));
+}
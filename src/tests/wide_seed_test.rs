@@ -0,0 +1,83 @@
+use solana_message::Message;
+use solana_signer::Signer;
+use solana_transaction::Transaction;
+use solana_program::msg;
+
+use crate::client::{Escrow, EscrowSeed, MakeParams, RefundParams, TakeParams, TOKEN_PROGRAM_ID, find_escrow_address, make_instruction, refund_instruction, take_instruction};
+use crate::tests::test_helpers::{get_token_balance, setup_escrow_test};
+
+#[test]
+fn test_wide_seed() {
+    let mut escrow_setup = setup_escrow_test();
+
+    let amount_a: u64 = 10_000_000;
+    let amount_b: u64 = 20_000_000;
+    let maker_pubkey = escrow_setup.maker.pubkey();
+    let taker_pubkey = escrow_setup.taker.pubkey();
+
+    // A 1-byte seed and the same value as a u64 seed are different escrows, and seeds go past 255
+    let seeds = [EscrowSeed::Byte(1), EscrowSeed::Wide(1), EscrowSeed::Wide(256), EscrowSeed::Wide(u64::MAX)];
+    for seed in seeds {
+        let make_instruction = make_instruction(&MakeParams {
+            maker: maker_pubkey,
+            mint_a: escrow_setup.mint_a,
+            mint_b: escrow_setup.mint_b,
+            token_program_a: TOKEN_PROGRAM_ID,
+            amount_a,
+            amount_b,
+            seed,
+            expires_at: 0,
+            taker: None,
+            alternatives: vec![],
+        });
+        let message = Message::new(&[make_instruction], Some(&maker_pubkey));
+        let recent_blockhash = escrow_setup.litesvm.latest_blockhash();
+        let transaction = Transaction::new(&[&escrow_setup.maker], message, recent_blockhash);
+        escrow_setup.litesvm.send_transaction(transaction).unwrap();
+
+        let (escrow_pda, escrow_bump) = find_escrow_address(&maker_pubkey, seed);
+        let escrow_account = escrow_setup.litesvm.get_account(&escrow_pda).unwrap();
+        let escrow = Escrow::from_bytes(&escrow_account.data).unwrap();
+        assert_eq!(escrow.seed, seed);
+        assert_eq!(escrow.bump, escrow_bump);
+    }
+    assert_eq!(get_token_balance(&escrow_setup.litesvm, &escrow_setup.maker_ata_a), 100_000_000 - 4 * amount_a);
+
+    // Take the 1-byte escrow and the first two wide ones, each derived from its stored seed
+    for seed in &seeds[..3] {
+        let take_instruction = take_instruction(&TakeParams {
+            taker: taker_pubkey,
+            maker: maker_pubkey,
+            mint_a: escrow_setup.mint_a,
+            mint_b: escrow_setup.mint_b,
+            token_program_a: TOKEN_PROGRAM_ID,
+            token_program_b: TOKEN_PROGRAM_ID,
+            treasury: escrow_setup.treasury,
+            seed: *seed,
+            amount_a,
+            max_amount_b: None,
+        });
+        let message = Message::new(&[take_instruction], Some(&taker_pubkey));
+        let recent_blockhash = escrow_setup.litesvm.latest_blockhash();
+        let transaction = Transaction::new(&[&escrow_setup.taker], message, recent_blockhash);
+        let tx = escrow_setup.litesvm.send_transaction(transaction).unwrap();
+
+        msg!("\n\nTake escrow with seed {:?} transaction sucessfull", seed);
+        msg!("CUs Consumed: {}", tx.compute_units_consumed);
+    }
+    assert_eq!(get_token_balance(&escrow_setup.litesvm, &escrow_setup.taker_ata_a), 3 * amount_a);
+
+    // Refund the last wide one
+    let refund_instruction = refund_instruction(&RefundParams {
+        maker: maker_pubkey,
+        mint_a: escrow_setup.mint_a,
+        mint_b: escrow_setup.mint_b,
+        token_program_a: TOKEN_PROGRAM_ID,
+        seed: seeds[3],
+    });
+    let message = Message::new(&[refund_instruction], Some(&maker_pubkey));
+    let recent_blockhash = escrow_setup.litesvm.latest_blockhash();
+    let transaction = Transaction::new(&[&escrow_setup.maker], message, recent_blockhash);
+    escrow_setup.litesvm.send_transaction(transaction).unwrap();
+    assert_eq!(get_token_balance(&escrow_setup.litesvm, &escrow_setup.maker_ata_a), 100_000_000 - 3 * amount_a);
+}
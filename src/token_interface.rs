@@ -0,0 +1,307 @@
+use pinocchio::{
+    AccountView, Address, ProgramResult,
+    cpi::{Signer, invoke_signed},
+    error::ProgramError,
+    instruction::{InstructionAccount, InstructionView},
+};
+
+use crate::errors::EscrowError;
+
+/// The SPL Token program
+pub const TOKEN_PROGRAM_ID: Address = pinocchio_token::ID;
+
+/// The Token-2022 (Token Extensions) program
+pub const TOKEN_2022_PROGRAM_ID: Address = Address::new_from_array(five8_const::decode_32_const("TokenzQdBNbLqP5VEhdkAS6EPFLC1PHnBqCXEpPxuEb"));
+
+// Layout offsets shared by both token programs
+const MINT_BASE_LEN: usize = 82;
+const MINT_DECIMALS_OFFSET: usize = 44;
+const MINT_INITIALIZED_OFFSET: usize = 45;
+const TOKEN_ACCOUNT_BASE_LEN: usize = 165;
+const TOKEN_ACCOUNT_STATE_OFFSET: usize = 108;
+
+// Token-2022 extension layout: the account type byte follows the (padded) base account, then TLV entries
+const ACCOUNT_TYPE_OFFSET: usize = 165;
+const ACCOUNT_TYPE_MINT: u8 = 1;
+const ACCOUNT_TYPE_ACCOUNT: u8 = 2;
+
+/// Mint extensions the escrow rejects, as their amounts or transferability don't match what was agreed in `make`:
+/// TransferFeeConfig (1), NonTransferable (9), PermanentDelegate (12) and TransferHook (14)
+const UNSUPPORTED_MINT_EXTENSIONS: [u16; 4] = [1, 9, 12, 14];
+
+/// Native SOL, which an escrow leg uses by passing the system program in place of the mint
+pub const NATIVE_MINT: Address = pinocchio_system::ID;
+
+/// Decimals of native SOL, counted in lamports
+pub const NATIVE_DECIMALS: u8 = 9;
+
+/// Seed prefix of the program-owned vault that holds native SOL deposited as `mint_a`
+pub const NATIVE_VAULT_SEED: &[u8] = b"vault";
+
+/// Whether the address is one of the supported token programs
+pub fn is_token_program(address: &Address) -> bool {
+    address == &TOKEN_PROGRAM_ID || address == &TOKEN_2022_PROGRAM_ID
+}
+
+/// Whether the account is owned by one of the supported token programs
+pub fn owned_by_token_program(account: &AccountView) -> bool {
+    account.owned_by(&TOKEN_PROGRAM_ID) || account.owned_by(&TOKEN_2022_PROGRAM_ID)
+}
+
+/// Whether the mint account stands for native SOL
+pub fn is_native_mint(mint: &AccountView) -> bool {
+    mint.address() == &NATIVE_MINT
+}
+
+/// Decimals of a leg mint, `NATIVE_DECIMALS` for native SOL
+pub fn mint_decimals(mint: &AccountView) -> Result<u8, ProgramError> {
+    if is_native_mint(mint) {
+        return Ok(NATIVE_DECIMALS);
+    }
+    Ok(Mint::from_account_view(mint)?.decimals())
+}
+
+/// Checks the token program moving a leg: the system program for native SOL, otherwise a supported token program owning the mint
+pub fn check_token_program(mint: &AccountView, token_program: &AccountView) -> ProgramResult {
+    let valid = if is_native_mint(mint) {
+        token_program.address() == &pinocchio_system::ID
+    } else {
+        is_token_program(token_program.address()) && mint.owned_by(token_program.address())
+    };
+    if !valid {
+        return Err(EscrowError::InvalidTokenProgram.into());
+    }
+    Ok(())
+}
+
+/// Checks the native vault is the program-owned PDA `["vault", escrow, bump]`
+pub fn check_native_vault(vault: &AccountView, escrow: &AccountView, bump: [u8; 1]) -> ProgramResult {
+    let vault_seeds = [NATIVE_VAULT_SEED, escrow.address().as_ref(), bump.as_slice()];
+    let vault_pda = pinocchio_pubkey::derive_address_const(&vault_seeds, None, crate::ID.as_array());
+    if vault_pda != vault.address().to_bytes() || !vault.owned_by(&crate::ID) {
+        return Err(EscrowError::InvalidVaultAddress.into());
+    }
+    Ok(())
+}
+
+/// Checks the token vault is the ATA `[owner, token_program, mint]` of the program account owning it, so only one vault can hold its mint
+pub fn check_token_vault(vault: &AccountView, owner: &AccountView, mint: &AccountView, token_program: &AccountView) -> ProgramResult {
+    let (vault_ata, _) = Address::find_program_address(
+        &[owner.address().as_ref(), token_program.address().as_ref(), mint.address().as_ref()],
+        &pinocchio_associated_token_account::ID,
+    );
+    if &vault_ata != vault.address() {
+        return Err(EscrowError::InvalidVaultAddress.into());
+    }
+    Ok(())
+}
+
+/// Checks the account receiving the protocol fee: the treasury itself for native SOL, otherwise a treasury token account of the mint
+pub fn check_treasury(treasury_ata: &AccountView, treasury: &[u8; 32], mint: &AccountView, token_program: &AccountView) -> ProgramResult {
+    if is_native_mint(mint) {
+        if &treasury_ata.address().to_bytes() != treasury {
+            return Err(EscrowError::InvalidTokenAccount.into());
+        }
+        return Ok(());
+    }
+    if !treasury_ata.owned_by(token_program.address()) {
+        return Err(EscrowError::InvalidTokenAccount.into());
+    }
+    if &TokenAccount::from_account_view(treasury_ata)?.owner().to_bytes() != treasury {
+        return Err(EscrowError::TokenAccountAuthorityMismatch.into());
+    }
+    if TokenAccount::from_account_view(treasury_ata)?.mint() != mint.address() {
+        return Err(EscrowError::TokenAccountMintMismatch.into());
+    }
+    Ok(())
+}
+
+/// Pays `lamports` out of a program-owned native vault
+pub fn withdraw_native(vault: &AccountView, to: &AccountView, lamports: u64) -> ProgramResult {
+    let balance = vault.lamports().checked_sub(lamports).ok_or(EscrowError::MathOverflow)?;
+    vault.set_lamports(balance);
+    to.set_lamports(to.lamports() + lamports);
+    Ok(())
+}
+
+/// Closes a program-owned native vault, sending what is left (rent included) to the destination
+pub fn close_native_vault(vault: &AccountView, destination: &AccountView) {
+    destination.set_lamports(destination.lamports() + vault.lamports());
+    vault.set_lamports(0);
+}
+
+/// Rejects Token-2022 mints carrying an extension the escrow can't honour
+pub fn check_mint_extensions(mint: &AccountView) -> ProgramResult {
+    if !mint.owned_by(&TOKEN_2022_PROGRAM_ID) {
+        return Ok(());
+    }
+
+    let data = mint.try_borrow()?;
+    if data.len() <= ACCOUNT_TYPE_OFFSET {
+        return Ok(());
+    }
+    if data[ACCOUNT_TYPE_OFFSET] != ACCOUNT_TYPE_MINT {
+        return Err(EscrowError::InvalidMint.into());
+    }
+
+    let mut offset = ACCOUNT_TYPE_OFFSET + 1;
+    while offset + 4 <= data.len() {
+        let extension_type = u16::from_le_bytes([data[offset], data[offset + 1]]);
+        let length = u16::from_le_bytes([data[offset + 2], data[offset + 3]]) as usize;
+        if extension_type == 0 {
+            break;
+        }
+        if UNSUPPORTED_MINT_EXTENSIONS.contains(&extension_type) {
+            return Err(EscrowError::UnsupportedMintExtension.into());
+        }
+        offset += 4 + length;
+    }
+
+    Ok(())
+}
+
+/// Mint data readable under either token program
+pub struct Mint {
+    decimals: u8,
+}
+
+impl Mint {
+    /// Return a `Mint` from the given account view, validating the owner and the base layout
+    pub fn from_account_view(account_view: &AccountView) -> Result<Self, ProgramError> {
+        if !owned_by_token_program(account_view) {
+            return Err(EscrowError::InvalidMint.into());
+        }
+        let data = account_view.try_borrow()?;
+        if data.len() < MINT_BASE_LEN || (data.len() > MINT_BASE_LEN && data.len() <= ACCOUNT_TYPE_OFFSET) {
+            return Err(EscrowError::InvalidMint.into());
+        }
+        if data[MINT_INITIALIZED_OFFSET] == 0 {
+            return Err(EscrowError::InvalidMint.into());
+        }
+        Ok(Self { decimals: data[MINT_DECIMALS_OFFSET] })
+    }
+
+    pub fn decimals(&self) -> u8 {
+        self.decimals
+    }
+}
+
+/// Token account data readable under either token program
+pub struct TokenAccount {
+    mint: Address,
+    owner: Address,
+    amount: u64,
+}
+
+impl TokenAccount {
+    /// Return a `TokenAccount` from the given account view, validating the owner and the base layout
+    pub fn from_account_view(account_view: &AccountView) -> Result<Self, ProgramError> {
+        if !owned_by_token_program(account_view) {
+            return Err(EscrowError::InvalidTokenAccount.into());
+        }
+        let data = account_view.try_borrow()?;
+        if data.len() < TOKEN_ACCOUNT_BASE_LEN {
+            return Err(EscrowError::InvalidTokenAccount.into());
+        }
+        if data.len() > TOKEN_ACCOUNT_BASE_LEN && data[ACCOUNT_TYPE_OFFSET] != ACCOUNT_TYPE_ACCOUNT {
+            return Err(EscrowError::InvalidTokenAccount.into());
+        }
+        if data[TOKEN_ACCOUNT_STATE_OFFSET] == 0 {
+            return Err(EscrowError::InvalidTokenAccount.into());
+        }
+        Ok(Self {
+            mint: Address::new_from_array(data[0..32].try_into().unwrap()),
+            owner: Address::new_from_array(data[32..64].try_into().unwrap()),
+            amount: u64::from_le_bytes(data[64..72].try_into().unwrap()),
+        })
+    }
+
+    pub fn mint(&self) -> &Address {
+        &self.mint
+    }
+
+    pub fn owner(&self) -> &Address {
+        &self.owner
+    }
+
+    pub fn amount(&self) -> u64 {
+        self.amount
+    }
+}
+
+/// Transfer tokens through the given token program, checking the mint decimals
+pub struct TransferChecked<'a, 'b> {
+    /// Sender account
+    pub from: &'a AccountView,
+    /// Mint account
+    pub mint: &'a AccountView,
+    /// Recipient account
+    pub to: &'a AccountView,
+    /// Authority account
+    pub authority: &'a AccountView,
+    /// Amount of micro-tokens to transfer
+    pub amount: u64,
+    /// Decimals of the mint
+    pub decimals: u8,
+    /// The token program that owns the mint
+    pub token_program: &'b Address,
+}
+
+impl TransferChecked<'_, '_> {
+    pub fn invoke(&self) -> ProgramResult {
+        self.invoke_signed(&[])
+    }
+
+    pub fn invoke_signed(&self, signers: &[Signer]) -> ProgramResult {
+        let instruction_accounts: [InstructionAccount; 4] = [
+            InstructionAccount::writable(self.from.address()),
+            InstructionAccount::readonly(self.mint.address()),
+            InstructionAccount::writable(self.to.address()),
+            InstructionAccount::readonly_signer(self.authority.address()),
+        ];
+
+        // Instruction data layout: [12 (discriminator), amount (u64), decimals (u8)]
+        let mut instruction_data = [0u8; 10];
+        instruction_data[0] = 12;
+        instruction_data[1..9].copy_from_slice(&self.amount.to_le_bytes());
+        instruction_data[9] = self.decimals;
+
+        let instruction = InstructionView {
+            program_id: self.token_program,
+            accounts: &instruction_accounts,
+            data: &instruction_data,
+        };
+
+        invoke_signed(&instruction, &[self.from, self.mint, self.to, self.authority], signers)
+    }
+}
+
+/// Close a token account through the given token program, sending its lamports to the destination
+pub struct CloseAccount<'a, 'b> {
+    /// Token account
+    pub account: &'a AccountView,
+    /// Destination account
+    pub destination: &'a AccountView,
+    /// Owner account
+    pub authority: &'a AccountView,
+    /// The token program that owns the token account
+    pub token_program: &'b Address,
+}
+
+impl CloseAccount<'_, '_> {
+    pub fn invoke_signed(&self, signers: &[Signer]) -> ProgramResult {
+        let instruction_accounts: [InstructionAccount; 3] = [
+            InstructionAccount::writable(self.account.address()),
+            InstructionAccount::writable(self.destination.address()),
+            InstructionAccount::readonly_signer(self.authority.address()),
+        ];
+
+        let instruction = InstructionView {
+            program_id: self.token_program,
+            accounts: &instruction_accounts,
+            data: &[9],
+        };
+
+        invoke_signed(&instruction, &[self.account, self.destination, self.authority], signers)
+    }
+}